@@ -1,6 +1,13 @@
 use norma::{
-    compiler::{self},
-    interpreter::{program::Program, Interpreter},
+    compiler::{self, format::Config},
+    encoding::Encoding,
+    graph::{svg, Graph},
+    interpreter::{
+        program::{Program, EXIT_LABEL},
+        snapshot::Snapshot,
+        Interpreter,
+    },
+    recursive,
 };
 use num_bigint::BigUint;
 use pinguim_language::{
@@ -8,7 +15,7 @@ use pinguim_language::{
     position::Span,
 };
 use serde::{Deserialize, Serialize};
-use std::{cmp::Ordering, str::FromStr};
+use std::str::FromStr;
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen(start)]
@@ -19,17 +26,25 @@ pub fn start() {
 //
 // - Checar erros de compilação.
 //
+// - Formatar código.
+//
 // - Compilar código criando intepretador.
 //
 // - Obter instruções.
 //
 // - Obter registradores.
 //
+// - Desenhar o fluxograma do programa.
+//
 // - Executar passo do interpretador.
 //
 // - Executar passos do interpretador (e parar interpretador).
 //
 // - Resetar interpretador.
+//
+// - Salvar e restaurar estado do interpretador.
+//
+// - Codificar entrada e decodificar saída.
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExportableSpan {
@@ -77,10 +92,50 @@ pub fn check(source: &str) -> Result<(), JsValue> {
     }
 }
 
+#[wasm_bindgen]
+pub fn format(source: &str) -> Result<String, JsValue> {
+    match compiler::format::format(source, &Config::default()) {
+        Ok(formatted) => Ok(formatted),
+
+        Err(diagnostics) => Err(export_diagnostics(&diagnostics)),
+    }
+}
+
+fn parse_encoding(encoding: &str) -> Result<Encoding, JsValue> {
+    Encoding::from_str(encoding)
+        .map_err(|error| JsValue::from_str(&error.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn encode(text: &str, encoding: &str) -> Result<String, JsValue> {
+    parse_encoding(encoding)?
+        .encode_str(text)
+        .map(|number| number.to_string())
+        .map_err(|error| JsValue::from_str(&error.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn decode(value_text: &str, encoding: &str) -> Result<String, JsValue> {
+    let value = parse_value(value_text)?;
+    parse_encoding(encoding)?
+        .decode(&value)
+        .map(|data| data.to_string())
+        .map_err(|error| JsValue::from_str(&error.to_string()))
+}
+
 #[wasm_bindgen]
 pub fn compile(source: &str) -> Result<InterpreterHandle, JsValue> {
     match compiler::compile(source) {
-        Ok(program) => Ok(InterpreterHandle::new(program)),
+        Ok(program) => InterpreterHandle::try_new(program),
+
+        Err(diagnostics) => Err(export_diagnostics(&diagnostics)),
+    }
+}
+
+#[wasm_bindgen(js_name = "compileRecursive")]
+pub fn compile_recursive(source: &str) -> Result<InterpreterHandle, JsValue> {
+    match recursive::compile(source) {
+        Ok(program) => InterpreterHandle::try_new(program),
 
         Err(diagnostics) => Err(export_diagnostics(&diagnostics)),
     }
@@ -107,6 +162,13 @@ pub struct InterpreterStatus {
     pub running: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepsOutcome {
+    #[serde(flatten)]
+    pub status: InterpreterStatus,
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InterpreterData {
     pub instructions: Vec<ExportableInstruction>,
@@ -124,6 +186,13 @@ impl InterpreterHandle {
         Self { interpreter: Interpreter::new(program) }
     }
 
+    pub fn try_new(program: Program) -> Result<Self, JsValue> {
+        program
+            .validate(&[EXIT_LABEL], |_| true)
+            .map_err(|error| JsValue::from_str(&error.to_string()))?;
+        Ok(Self::new(program))
+    }
+
     fn running(&self) -> bool {
         let current_label = self.interpreter.current_label();
         self.interpreter.program().is_label_valid(current_label)
@@ -139,25 +208,14 @@ impl InterpreterHandle {
     }
 
     fn export_registers(&self) -> Vec<ExportableRegister> {
-        let mut registers = Vec::new();
-        for name in self.interpreter.machine().register_names() {
-            registers.push(ExportableRegister {
+        self.interpreter
+            .machine()
+            .registers()
+            .map(|(name, value)| ExportableRegister {
                 name: name.to_owned(),
-                value: self.interpreter.machine().get_value(name).to_string(),
-            });
-        }
-
-        registers.sort_by(|left, right| {
-            match (left.name.as_str(), right.name.as_str()) {
-                ("X", _) => Ordering::Less,
-                (_, "X") => Ordering::Greater,
-                ("Y", _) => Ordering::Less,
-                (_, "Y") => Ordering::Greater,
-                _ => left.name.cmp(&right.name),
-            }
-        });
-
-        registers
+                value: value.to_string(),
+            })
+            .collect()
     }
 
     fn export_instructions(&self) -> Vec<ExportableInstruction> {
@@ -188,6 +246,12 @@ impl InterpreterHandle {
         JsValue::from_serde(&self.export_instructions()).unwrap()
     }
 
+    #[wasm_bindgen(js_name = "flowchart")]
+    pub fn js_flowchart(&self) -> String {
+        let graph = Graph::from_program(self.interpreter.program());
+        svg::render(&graph, None)
+    }
+
     #[wasm_bindgen(js_name = "input")]
     pub fn js_input(&mut self, value_text: &str) -> Result<(), JsValue> {
         match BigUint::from_str(&value_text) {
@@ -200,6 +264,48 @@ impl InterpreterHandle {
         }
     }
 
+    #[wasm_bindgen(js_name = "setRegister")]
+    pub fn js_set_register(
+        &mut self,
+        name: &str,
+        value_text: &str,
+    ) -> Result<(), JsValue> {
+        let value = parse_value(value_text)?;
+        self.interpreter
+            .set_register(name, value)
+            .map_err(|error| JsValue::from_str(&error.to_string()))
+    }
+
+    #[wasm_bindgen(js_name = "createRegister")]
+    pub fn js_create_register(
+        &mut self,
+        name: &str,
+        value_text: &str,
+    ) -> Result<(), JsValue> {
+        let value = parse_value(value_text)?;
+        self.interpreter
+            .create_register(name, value)
+            .map_err(|error| JsValue::from_str(&error.to_string()))
+    }
+
+    #[wasm_bindgen(js_name = "jumpTo")]
+    pub fn js_jump_to(&mut self, label: &str) -> Result<(), JsValue> {
+        self.interpreter
+            .jump_to(label)
+            .map_err(|error| JsValue::from_str(&error.to_string()))
+    }
+
+    #[wasm_bindgen(js_name = "edits")]
+    pub fn js_edits(&self) -> Vec<JsValue> {
+        self.interpreter
+            .edits()
+            .iter()
+            .map(|record| {
+                JsValue::from_str(&format!("{}: {}", record.steps, record.edit))
+            })
+            .collect()
+    }
+
     #[wasm_bindgen(js_name = "reset")]
     pub fn js_reset(&mut self) {
         self.interpreter.reset();
@@ -212,16 +318,43 @@ impl InterpreterHandle {
     }
 
     #[wasm_bindgen(js_name = "runStep")]
-    pub fn js_run_step(&mut self) -> JsValue {
-        self.interpreter.run_step();
+    pub fn js_run_step(&mut self) -> Result<JsValue, JsValue> {
+        self.interpreter
+            .try_run_step()
+            .map_err(|error| JsValue::from_str(&error.to_string()))?;
         let running = self.running();
-        JsValue::from_serde(&self.export_status(running)).unwrap()
+        Ok(JsValue::from_serde(&self.export_status(running)).unwrap())
     }
 
     #[wasm_bindgen(js_name = "runSteps")]
     pub fn js_run_steps(&mut self, max_steps: u32) -> JsValue {
-        self.interpreter.run_steps(max_steps);
+        let error = self
+            .interpreter
+            .try_run_steps(max_steps)
+            .err()
+            .map(|error| error.to_string());
         let running = self.running();
-        JsValue::from_serde(&self.export_status(running)).unwrap()
+        let outcome =
+            StepsOutcome { status: self.export_status(running), error };
+        JsValue::from_serde(&outcome).unwrap()
     }
+
+    #[wasm_bindgen(js_name = "snapshot")]
+    pub fn js_snapshot(&self) -> String {
+        serde_json::to_string(&self.interpreter.snapshot()).unwrap()
+    }
+
+    #[wasm_bindgen(js_name = "restore")]
+    pub fn js_restore(&mut self, snapshot_json: &str) -> Result<(), JsValue> {
+        let snapshot: Snapshot = serde_json::from_str(snapshot_json)
+            .map_err(|_| JsValue::from_str("Estado salvo inválido"))?;
+        self.interpreter
+            .restore(snapshot)
+            .map_err(|error| JsValue::from_str(&error.to_string()))
+    }
+}
+
+fn parse_value(value_text: &str) -> Result<BigUint, JsValue> {
+    BigUint::from_str(value_text.trim())
+        .map_err(|_| JsValue::from_str("Valor de registrador inválido"))
 }
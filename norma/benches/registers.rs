@@ -0,0 +1,118 @@
+//! Compara a vazão de passos primitivos (`inc`, `dec` e `zero`) dos
+//! registradores da máquina, que guardam valores pequenos em um `u64`, com a
+//! representação anterior, que sempre usava `BigUint`.
+//!
+//! Rode com `cargo bench -p norma`.
+
+use criterion::{
+    black_box, criterion_group, criterion_main, BenchmarkId, Criterion,
+    Throughput,
+};
+use norma::{compiler::compile, interpreter::Interpreter, machine::Machine};
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+/// Programa que copia X para Y passo a passo, só com instruções primitivas.
+const COPY: &str = "main {
+    1: if zero X then goto 0 else goto 2
+    2: do dec X goto 3
+    3: do inc Y goto 1
+}";
+
+/// Registradores como eram antes: cada valor sempre em um `BigUint`.
+struct BigRegisters {
+    values: Vec<BigUint>,
+}
+
+impl BigRegisters {
+    fn inc(&mut self, index: usize) {
+        self.values[index] += 1u8;
+    }
+
+    fn dec(&mut self, index: usize) {
+        let value = &mut self.values[index];
+        if !value.is_zero() {
+            *value -= 1u8;
+        }
+    }
+
+    fn is_zero(&self, index: usize) -> bool {
+        self.values[index].is_zero()
+    }
+}
+
+/// Conta de `count` até zero em X e de volta em Y, como um laço da Norma
+/// faria. Os registradores são acessados pela posição, para medir só a
+/// representação dos valores, e não a pesquisa pelo nome.
+fn primitive_steps(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("primitive_steps");
+
+    for &count in &[100u64, 10_000] {
+        group.throughput(Throughput::Elements(count * 2));
+
+        group.bench_with_input(
+            BenchmarkId::new("biguint", count),
+            &count,
+            |bencher, &count| {
+                let mut registers = BigRegisters {
+                    values: vec![BigUint::from(count), BigUint::zero()],
+                };
+                bencher.iter(|| {
+                    while !registers.is_zero(0) {
+                        registers.dec(0);
+                        registers.inc(1);
+                    }
+                    while !registers.is_zero(1) {
+                        registers.dec(1);
+                        registers.inc(0);
+                    }
+                    black_box(&registers.values);
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("machine", count),
+            &count,
+            |bencher, &count| {
+                let mut machine = Machine::new(BigUint::from(count));
+                let x = machine.register_id("X").unwrap();
+                let y = machine.register_id("Y").unwrap();
+                bencher.iter(|| {
+                    while !machine.is_zero_by_id(x) {
+                        machine.dec_by_id(x);
+                        machine.inc_by_id(y);
+                    }
+                    while !machine.is_zero_by_id(y) {
+                        machine.dec_by_id(y);
+                        machine.inc_by_id(x);
+                    }
+                    black_box(&machine);
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+/// Executa um programa inteiro no interpretador.
+fn interpreter_steps(criterion: &mut Criterion) {
+    let program = compile(COPY).unwrap();
+    let count = 10_000u64;
+
+    let mut group = criterion.benchmark_group("interpreter_steps");
+    group.throughput(Throughput::Elements(count * 3 + 1));
+    group.bench_function("copy", |bencher| {
+        bencher.iter(|| {
+            let mut interpreter = Interpreter::new(program.clone());
+            interpreter.input(BigUint::from(count));
+            interpreter.run_all();
+            black_box(interpreter.output())
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, primitive_steps, interpreter_steps);
+criterion_main!(benches);
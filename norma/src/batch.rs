@@ -0,0 +1,179 @@
+//! Execução em lote: roda um programa já compilado para muitas entradas, em
+//! paralelo, e tabula a função computada. Útil para conferir um programa
+//! contra uma especificação, e.g. para X de `0` a `10000`.
+
+#[cfg(test)]
+mod test;
+
+use crate::interpreter::{program::Program, Interpreter};
+use num_bigint::BigUint;
+use std::{
+    fmt::Write,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+/// Configuração de uma execução em lote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    /// Combustível de cada execução: o máximo de instruções executadas para
+    /// uma entrada. Uma execução que gasta todo o combustível é considerada
+    /// não-terminante.
+    pub fuel: u64,
+    /// Número de threads. Zero usa o paralelismo disponível na máquina.
+    pub threads: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self { fuel: 1_000_000, threads: 0 }
+    }
+}
+
+impl Config {
+    /// Número de threads a ser usado para `jobs` execuções.
+    fn thread_count(&self, jobs: usize) -> usize {
+        let threads = if self.threads == 0 {
+            thread::available_parallelism().map_or(1, |count| count.get())
+        } else {
+            self.threads
+        };
+        threads.min(jobs).max(1)
+    }
+}
+
+/// Resultado de uma execução.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// O programa terminou, com a dada saída (AKA registrador Y).
+    Halted(BigUint),
+    /// O combustível acabou antes do programa terminar.
+    OutOfFuel,
+}
+
+impl Outcome {
+    /// Retorna a saída, se o programa terminou.
+    pub fn output(&self) -> Option<&BigUint> {
+        match self {
+            Outcome::Halted(output) => Some(output),
+            Outcome::OutOfFuel => None,
+        }
+    }
+}
+
+/// Uma linha da tabela: a execução para uma entrada.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    /// Entrada (AKA registrador X).
+    pub input: BigUint,
+    /// Resultado da execução.
+    pub outcome: Outcome,
+    /// Passos dados, contados como em [`Interpreter::steps`].
+    pub steps: BigUint,
+}
+
+/// Tabela de resultados, na ordem das entradas.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Table {
+    /// Linhas da tabela, uma por entrada.
+    pub rows: Vec<Row>,
+}
+
+impl Table {
+    /// Exporta a tabela em CSV, com as colunas `input`, `halted`, `output` e
+    /// `steps`. A saída fica vazia em execuções não-terminantes.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("input,halted,output,steps\n");
+        for row in &self.rows {
+            let output = match row.outcome.output() {
+                Some(output) => output.to_string(),
+                None => String::new(),
+            };
+            writeln!(
+                csv,
+                "{},{},{},{}",
+                row.input,
+                row.outcome.output().is_some(),
+                output,
+                row.steps
+            )
+            .expect("writing to a string never fails");
+        }
+        csv
+    }
+}
+
+/// Roda o programa para cada uma das entradas, distribuindo as execuções
+/// entre as threads da configuração. A tabela resultante segue a ordem das
+/// entradas.
+pub fn run<P, I>(program: P, inputs: I, config: &Config) -> Table
+where
+    P: Into<Arc<Program>>,
+    I: IntoIterator<Item = BigUint>,
+{
+    let program = program.into();
+    let inputs: Vec<BigUint> = inputs.into_iter().collect();
+    let next = AtomicUsize::new(0);
+
+    let mut indexed_rows: Vec<(usize, Row)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..config.thread_count(inputs.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut rows = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let input = match inputs.get(index) {
+                            Some(input) => input.clone(),
+                            None => break rows,
+                        };
+                        rows.push((index, run_one(&program, input, config)));
+                    }
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("batch worker panicked"))
+            .collect()
+    });
+
+    indexed_rows.sort_by_key(|(index, _)| *index);
+    Table { rows: indexed_rows.into_iter().map(|(_, row)| row).collect() }
+}
+
+/// Roda o programa para uma única entrada, com o combustível da configuração.
+pub(crate) fn run_one(
+    program: &Arc<Program>,
+    input: BigUint,
+    config: &Config,
+) -> Row {
+    let mut interpreter = Interpreter::new(program.clone());
+    interpreter.input(input.clone());
+
+    let outcome = if run_with_fuel(&mut interpreter, config.fuel) {
+        Outcome::Halted(interpreter.output())
+    } else {
+        Outcome::OutOfFuel
+    };
+    Row { input, outcome, steps: interpreter.steps() }
+}
+
+/// Roda o interpretador por no máximo `fuel` instruções, retornando se o
+/// programa terminou.
+pub(crate) fn run_with_fuel(interpreter: &mut Interpreter, fuel: u64) -> bool {
+    let mut fuel = fuel;
+    while fuel > 0 {
+        let chunk = fuel.min(u64::from(u32::MAX));
+        if !interpreter.run_steps(chunk as u32) {
+            break;
+        }
+        fuel -= chunk;
+    }
+
+    let current = interpreter.current_label();
+    !interpreter.program().is_label_valid(current)
+}
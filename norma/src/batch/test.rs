@@ -0,0 +1,95 @@
+use super::{run, Config, Outcome, Row, Table};
+use crate::{compiler::compile, interpreter::Interpreter};
+use num_bigint::BigUint;
+use std::sync::Arc;
+
+/// Y := 2 * X, mas não termina quando X deixa resto 3 na divisão por 4.
+const SOURCE: &str = "main {
+    1: if zero X then goto 0 else goto 2
+    2: do dec X goto 3
+    3: do inc Y goto 4
+    4: do inc Y goto 5
+    5: if zero X then goto 0 else goto 6
+    6: do dec X goto 7
+    7: do inc Y goto 8
+    8: do inc Y goto 9
+    9: if zero X then goto 0 else goto 10
+    10: do dec X goto 11
+    11: do inc Y goto 12
+    12: do inc Y goto 13
+    13: if zero X then goto 13 else goto 14
+    14: do dec X goto 15
+    15: do inc Y goto 16
+    16: do inc Y goto 1
+}";
+
+#[test]
+fn tabulate_in_parallel() {
+    let program = compile(SOURCE).unwrap();
+    let config = Config { fuel: 1000, threads: 4 };
+    let table = run(program, (0..40u32).map(BigUint::from), &config);
+
+    assert_eq!(table.rows.len(), 40);
+    for (input, row) in (0..40u32).zip(&table.rows) {
+        assert_eq!(row.input, BigUint::from(input));
+        if input % 4 == 3 {
+            assert_eq!(row.outcome, Outcome::OutOfFuel);
+        } else {
+            assert_eq!(row.outcome, Outcome::Halted(BigUint::from(input * 2)));
+        }
+    }
+
+    let single = run(
+        compile(SOURCE).unwrap(),
+        (0..40u32).map(BigUint::from),
+        &Config { threads: 1, ..config },
+    );
+    assert_eq!(single, table);
+}
+
+#[test]
+fn fuel_limits_each_run() {
+    let program = Arc::new(compile(SOURCE).unwrap());
+
+    let table = run(
+        program.clone(),
+        vec![BigUint::from(1u8)],
+        &Config { fuel: 5, threads: 1 },
+    );
+    assert_eq!(table.rows[0].outcome, Outcome::Halted(BigUint::from(2u8)));
+    assert_eq!(table.rows[0].steps, BigUint::from(5u8));
+
+    let table =
+        run(program, vec![BigUint::from(1u8)], &Config { fuel: 4, threads: 1 });
+    assert_eq!(table.rows[0].outcome, Outcome::OutOfFuel);
+    assert_eq!(table.rows[0].steps, BigUint::from(4u8));
+}
+
+#[test]
+fn export_csv() {
+    let table = Table {
+        rows: vec![
+            Row {
+                input: BigUint::from(0u8),
+                outcome: Outcome::Halted(BigUint::from(0u8)),
+                steps: BigUint::from(1u8),
+            },
+            Row {
+                input: BigUint::from(3u8),
+                outcome: Outcome::OutOfFuel,
+                steps: BigUint::from(1000u16),
+            },
+        ],
+    };
+    assert_eq!(
+        table.to_csv(),
+        "input,halted,output,steps\n0,true,0,1\n3,false,,1000\n"
+    );
+}
+
+#[test]
+fn interpreter_is_send() {
+    fn assert_send<T: Send>() {}
+    assert_send::<Interpreter>();
+    assert_send::<Arc<crate::interpreter::program::Program>>();
+}
@@ -0,0 +1,90 @@
+//! Geração de instruções monolíticas a partir de sequências de instruções
+//! estruturadas, comum às linguagens iterativa ([`crate::iterative`]),
+//! recursiva ([`crate::recursive`]) e LOOP/WHILE ([`crate::loop_while`]).
+//!
+//! Cada instrução estruturada gera um bloco contíguo de instruções
+//! monolíticas com rótulos numéricos, de modo que o rótulo de cada instrução
+//! de uma sequência é o rótulo da anterior somado ao tamanho dela.
+
+use std::fmt;
+
+/// Uma instrução estruturada, que gera um bloco contíguo de instruções
+/// monolíticas.
+pub(crate) trait Node {
+    /// Número de instruções monolíticas geradas por esta instrução.
+    fn size(&self) -> usize;
+}
+
+/// Número de instruções monolíticas geradas por uma sequência.
+pub(crate) fn block_size<N>(block: &[N]) -> usize
+where
+    N: Node,
+{
+    block.iter().map(N::size).sum()
+}
+
+/// Rótulo da primeira instrução de uma sequência que começa no rótulo
+/// `start`, ou `next` se a sequência for vazia.
+pub(crate) fn entry<N>(block: &[N], start: usize, next: &str) -> String {
+    if block.is_empty() {
+        next.to_owned()
+    } else {
+        start.to_string()
+    }
+}
+
+/// Gera as instruções de uma sequência a partir do rótulo `start`, saindo
+/// para `next`: `emit` é chamada para cada instrução com o seu rótulo
+/// inicial e o rótulo de saída dela.
+pub(crate) fn emit_block<N, F>(
+    block: &[N],
+    start: usize,
+    next: &str,
+    mut emit: F,
+) where
+    N: Node,
+    F: FnMut(&N, usize, &str),
+{
+    let mut label = start;
+    for (i, node) in block.iter().enumerate() {
+        let following = label + node.size();
+        let node_next = if i + 1 == block.len() {
+            next.to_owned()
+        } else {
+            following.to_string()
+        };
+        emit(node, label, &node_next);
+        label = following;
+    }
+}
+
+/// Escolhe um nome de registrador a partir de `base` que não esteja em uso,
+/// acrescentando `_` se preciso.
+pub(crate) fn fresh_register(base: &str, used: &[String]) -> String {
+    let mut name = base.to_owned();
+    while used.contains(&name) {
+        name.push('_');
+    }
+    name
+}
+
+/// Escreve uma sequência separada por `;`, uma instrução por linha, cada uma
+/// escrita por `write` com a indentação dada.
+pub(crate) fn write_sequence<N, F>(
+    fmtr: &mut fmt::Formatter,
+    block: &[N],
+    indent: usize,
+    write: F,
+) -> fmt::Result
+where
+    F: Fn(&N, &mut fmt::Formatter, usize) -> fmt::Result,
+{
+    for (i, node) in block.iter().enumerate() {
+        if i > 0 {
+            writeln!(fmtr, ";")?;
+        }
+        write!(fmtr, "{:width$}", "", width = indent * 4)?;
+        write(node, fmtr, indent)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,247 @@
+//! Busca limitada no estilo do castor ocupado: enumera todos os programas
+//! com até `N` instruções primitivas (`inc`, `dec` e `zero`) e `R`
+//! registradores, roda cada um a partir da mesma entrada com um limite de
+//! combustível e reporta os programas que terminam com a maior saída e com o
+//! maior número de passos.
+//!
+//! Programas isomorfos, isto é, que só diferem nos rótulos, na ordem das
+//! instruções, nos nomes dos registradores auxiliares ou em código morto, são
+//! contados uma única vez: só são rodados os programas que já estão na forma
+//! canônica (ver
+//! [`canonicalize`](crate::interpreter::program::canonical::canonicalize)).
+//!
+//! Como a função do castor ocupado não é computável, um programa que gasta
+//! todo o combustível pode ainda terminar mais tarde: o resultado só vale
+//! para o combustível dado.
+
+#[cfg(test)]
+mod test;
+
+use crate::{
+    batch::run_with_fuel,
+    encoding::godel::register_name,
+    interpreter::{
+        program::{
+            canonical::canonicalize, Instruction, InstructionKind, Operation,
+            OperationKind, Program, Test, TestKind,
+        },
+        Interpreter,
+    },
+};
+use num_bigint::BigUint;
+
+/// Configuração de uma busca.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// Número máximo de instruções dos programas.
+    pub instructions: usize,
+    /// Número de registradores usados, contando `X` e `Y`: os registradores
+    /// são `X`, `Y`, `R1`, `R2`, ...
+    pub registers: usize,
+    /// Entrada (AKA registrador X) dada a todos os programas.
+    pub input: BigUint,
+    /// Combustível de cada execução: o máximo de instruções executadas. Uma
+    /// execução que gasta todo o combustível é considerada não-terminante.
+    pub fuel: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            instructions: 3,
+            registers: 2,
+            input: BigUint::from(0u8),
+            fuel: 1000,
+        }
+    }
+}
+
+/// Um programa que terminou, com sua saída e seus passos.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Champion {
+    /// O programa, na forma canônica.
+    pub program: Program,
+    /// Saída (AKA registrador Y).
+    pub output: BigUint,
+    /// Passos dados, contados como em [`Interpreter::steps`].
+    pub steps: BigUint,
+}
+
+/// Resultado de uma busca.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Report {
+    /// Número de programas rodados, já sem os isomorfos.
+    pub programs: usize,
+    /// Número de programas que terminaram.
+    pub halted: usize,
+    /// Número de programas que gastaram todo o combustível.
+    pub out_of_fuel: usize,
+    /// O primeiro programa, na ordem da enumeração, que terminou com a maior
+    /// saída.
+    pub max_output: Option<Champion>,
+    /// O primeiro programa, na ordem da enumeração, que terminou com o maior
+    /// número de passos.
+    pub max_steps: Option<Champion>,
+}
+
+/// Roda a busca com a configuração dada.
+pub fn search(config: &Config) -> Report {
+    let mut report = Report::default();
+
+    for program in programs(config.instructions, config.registers) {
+        report.programs += 1;
+
+        let mut interpreter = Interpreter::new(program.clone());
+        interpreter.input(config.input.clone());
+        if !run_with_fuel(&mut interpreter, config.fuel) {
+            report.out_of_fuel += 1;
+            continue;
+        }
+        report.halted += 1;
+
+        let champion = Champion {
+            program,
+            output: interpreter.output(),
+            steps: interpreter.steps(),
+        };
+        let beats_output = match &report.max_output {
+            Some(best) => champion.output > best.output,
+            None => true,
+        };
+        let beats_steps = match &report.max_steps {
+            Some(best) => champion.steps > best.steps,
+            None => true,
+        };
+        if beats_steps {
+            report.max_steps = Some(champion.clone());
+        }
+        if beats_output {
+            report.max_output = Some(champion);
+        }
+    }
+
+    report
+}
+
+/// Enumera os programas com de `1` a `instructions` instruções primitivas e
+/// os registradores `X`, `Y`, `R1`, ... até completar `registers`, um por
+/// classe de isomorfismo, na forma canônica. Os programas menores vêm
+/// primeiro.
+pub fn programs(instructions: usize, registers: usize) -> Programs {
+    let registers =
+        (0..registers).map(|number| register_name(&number.into())).collect();
+    Programs {
+        max_size: instructions,
+        registers,
+        alphabet: Vec::new(),
+        choices: Vec::new(),
+    }
+}
+
+/// Iterador sobre os programas de uma busca, um por classe de isomorfismo.
+/// Criado por [`programs`].
+#[derive(Debug, Clone)]
+pub struct Programs {
+    /// Número máximo de instruções.
+    max_size: usize,
+    /// Registradores que podem ser usados.
+    registers: Vec<String>,
+    /// Instruções possíveis para programas do tamanho atual.
+    alphabet: Vec<InstructionKind>,
+    /// Índice, no alfabeto, da instrução de cada rótulo do programa atual.
+    /// Vazio antes de começar um novo tamanho.
+    choices: Vec<usize>,
+}
+
+impl Programs {
+    /// Avança para o próximo programa, canônico ou não, retornando `false`
+    /// quando não houver mais programas.
+    fn advance(&mut self) -> bool {
+        if self.choices.is_empty() {
+            return self.start_size(1);
+        }
+
+        for choice in self.choices.iter_mut() {
+            *choice += 1;
+            if *choice < self.alphabet.len() {
+                return true;
+            }
+            *choice = 0;
+        }
+
+        let size = self.choices.len() + 1;
+        self.start_size(size)
+    }
+
+    /// Começa a enumerar os programas de `size` instruções, retornando
+    /// `false` se eles passarem do máximo ou não houver registradores.
+    fn start_size(&mut self, size: usize) -> bool {
+        if size > self.max_size || self.registers.is_empty() {
+            self.choices.clear();
+            self.max_size = 0;
+            return false;
+        }
+        self.alphabet = alphabet(&self.registers, size);
+        self.choices = vec![0; size];
+        true
+    }
+
+    /// Monta o programa atual.
+    fn current(&self) -> Program {
+        let mut program = Program::empty();
+        for (index, &choice) in self.choices.iter().enumerate() {
+            let label = (index + 1).to_string();
+            program
+                .insert(Instruction::new(label, self.alphabet[choice].clone()));
+        }
+        program
+    }
+}
+
+impl Iterator for Programs {
+    type Item = Program;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.advance() {
+            let program = self.current();
+            if canonicalize(&program) == program {
+                return Some(program);
+            }
+        }
+        None
+    }
+}
+
+/// Todas as instruções primitivas de um programa com `size` instruções, que
+/// desviam para os rótulos de `0` a `size`.
+fn alphabet(registers: &[String], size: usize) -> Vec<InstructionKind> {
+    let targets: Vec<String> =
+        (0..=size).map(|label| label.to_string()).collect();
+    let mut alphabet = Vec::new();
+
+    for register in registers {
+        let operations = [
+            OperationKind::Inc(register.clone()),
+            OperationKind::Dec(register.clone()),
+        ];
+        for kind in &operations {
+            for next in &targets {
+                alphabet.push(InstructionKind::Operation(Operation {
+                    kind: kind.clone(),
+                    next: next.clone(),
+                }));
+            }
+        }
+        for next_then in &targets {
+            for next_else in &targets {
+                alphabet.push(InstructionKind::Test(Test {
+                    kind: TestKind::Zero(register.clone()),
+                    next_then: next_then.clone(),
+                    next_else: next_else.clone(),
+                }));
+            }
+        }
+    }
+
+    alphabet
+}
@@ -0,0 +1,99 @@
+use super::{programs, search, Config};
+use crate::{
+    batch::run_with_fuel,
+    interpreter::{program::canonical::canonicalize, Interpreter},
+};
+use num_bigint::BigUint;
+use std::collections::HashSet;
+
+#[test]
+fn single_instruction_programs() {
+    // Dois registradores, cada um com `inc` e `dec` desviando para 0 ou 1 e
+    // `zero` com quatro pares de destinos.
+    assert_eq!(programs(1, 2).count(), 16);
+    assert_eq!(programs(1, 1).count(), 8);
+    assert_eq!(programs(1, 0).count(), 0);
+    assert_eq!(programs(0, 2).count(), 0);
+
+    let report = search(&Config { instructions: 1, ..Config::default() });
+    assert_eq!(report.programs, 16);
+    assert_eq!(report.halted + report.out_of_fuel, 16);
+    let champion = report.max_output.unwrap();
+    assert_eq!(champion.program.to_string(), "1: do inc Y goto 0\n");
+    assert_eq!(champion.output, BigUint::from(1u8));
+}
+
+#[test]
+fn prune_isomorphic_programs() {
+    let mut seen = HashSet::new();
+    for program in programs(2, 3) {
+        assert_eq!(canonicalize(&program), program);
+        assert!(seen.insert(program.to_string()), "{}", program);
+    }
+
+    // Todo programa de duas instruções tem um representante na enumeração.
+    let mut all = super::Programs {
+        max_size: 2,
+        registers: vec![String::from("X"), String::from("Y")],
+        alphabet: Vec::new(),
+        choices: Vec::new(),
+    };
+    let mut classes = HashSet::new();
+    while all.advance() {
+        classes.insert(canonicalize(&all.current()).to_string());
+    }
+    let pruned: HashSet<_> =
+        programs(2, 2).map(|program| program.to_string()).collect();
+    assert_eq!(classes, pruned);
+}
+
+#[test]
+fn champions() {
+    let config = Config { instructions: 3, fuel: 100, ..Config::default() };
+    let report = search(&config);
+    assert_eq!(report.halted + report.out_of_fuel, report.programs);
+
+    let champion = report.max_output.unwrap();
+    assert_eq!(champion.output, BigUint::from(3u8));
+
+    let champion = report.max_steps.unwrap();
+    let mut interpreter = Interpreter::new(champion.program);
+    interpreter.run_all();
+    assert_eq!(interpreter.steps(), champion.steps);
+    assert!(champion.steps >= BigUint::from(5u8));
+}
+
+#[test]
+fn more_fuel_never_halts_less() {
+    let config = Config { instructions: 2, registers: 2, ..Config::default() };
+    let little = search(&Config { fuel: 3, ..config.clone() });
+    let plenty = search(&Config { fuel: 1000, ..config });
+    assert_eq!(little.programs, plenty.programs);
+    assert!(little.halted <= plenty.halted);
+    assert!(little.out_of_fuel > 0);
+}
+
+#[test]
+fn fuel_agrees_with_single_steps() {
+    for program in programs(2, 2) {
+        for input in 0..3u8 {
+            let mut fueled = Interpreter::new(program.clone());
+            fueled.input(BigUint::from(input));
+            let halted = run_with_fuel(&mut fueled, 50);
+
+            let mut stepped = Interpreter::new(program.clone());
+            stepped.input(BigUint::from(input));
+            let mut running = true;
+            for _ in 0..50 {
+                running = stepped.run_steps(1);
+                if !running {
+                    break;
+                }
+            }
+
+            assert_eq!(halted, !running, "{}", program);
+            assert_eq!(fueled.steps(), stepped.steps(), "{}", program);
+            assert_eq!(fueled.output(), stepped.output(), "{}", program);
+        }
+    }
+}
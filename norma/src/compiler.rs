@@ -1,6 +1,8 @@
 pub mod lexer;
 pub mod parser;
 pub mod expansor;
+pub mod format;
+pub mod emit;
 
 #[cfg(test)]
 mod test;
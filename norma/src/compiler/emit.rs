@@ -0,0 +1,566 @@
+//! Este módulo emite um programa já expandido como código-fonte válido da
+//! Norma: uma única `main` plana, com rótulos e registradores renomeados para
+//! nomes aceitos pelo lexer. O código emitido compila para um programa
+//! equivalente ao original.
+
+#[cfg(test)]
+mod test;
+
+use crate::{
+    compiler::format::{self, format},
+    interpreter::program::{
+        InstructionKind, Operation, OperationKind, Program, Test, TestKind,
+    },
+};
+use indexmap::IndexMap;
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use std::collections::HashSet;
+
+/// Palavras que não podem ser usadas como rótulo nem como registrador.
+const RESERVED: &[&str] = &[
+    "do",
+    "else",
+    "goto",
+    "if",
+    "main",
+    "operation",
+    "test",
+    "then",
+    "inc",
+    "dec",
+    "zero",
+    "true",
+    "false",
+];
+
+/// Configuração do emissor de código.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    /// Se cada instrução renomeada deve ganhar um comentário com seu rótulo
+    /// original (o caminho das macros expandidas).
+    pub mapping_comments: bool,
+    /// Configuração do formatador aplicado ao código emitido.
+    pub format: format::Config,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self { mapping_comments: true, format: format::Config::default() }
+    }
+}
+
+/// Emite o programa como código-fonte de uma `main` plana, que pode ser
+/// compilado de volta com `compiler::compile`.
+///
+/// Rótulos como `inc_y.incIfNotZero.2` têm os caracteres inválidos trocados
+/// por `_`, palavras reservadas ganham um `_` no final e colisões recebem os
+/// sufixos `_2`, `_3`, etc. Rótulos de saída (que não pertencem ao programa)
+/// são renomeados da mesma forma. Instruções que não são primitivas (como
+/// `clear`, `add` ou `equals`) são reescritas como sequências de `inc`, `dec`
+/// e `zero`, conforme as expansões documentadas no interpretador.
+pub fn emit(program: &Program, config: &Config) -> String {
+    let mut emitter = Emitter::new(program);
+    emitter.lower(program);
+
+    let mut source = String::from("main {\n");
+    for line in &emitter.lines {
+        source.push_str(&line.code);
+        if config.mapping_comments {
+            if let Some(original) = &line.original {
+                source.push_str(" // ");
+                source.push_str(original);
+            }
+        }
+        source.push('\n');
+    }
+    source.push_str("}\n");
+
+    format(&source, &config.format)
+        .expect("emitted source should always be valid")
+}
+
+/// Uma linha de código emitida.
+#[derive(Debug, Clone)]
+struct Line {
+    /// A instrução em si, no formato de código-fonte.
+    code: String,
+    /// O rótulo original, caso a instrução tenha sido renomeada.
+    original: Option<String>,
+}
+
+/// Estado do emissor: nomes já usados e renomeações feitas.
+#[derive(Debug, Clone)]
+struct Emitter {
+    /// Nomes de rótulos já tomados.
+    taken_labels: HashSet<String>,
+    /// Renomeação dos rótulos originais.
+    labels: IndexMap<String, String>,
+    /// Nomes de registradores já tomados.
+    taken_registers: HashSet<String>,
+    /// Renomeação dos registradores originais.
+    registers: IndexMap<String, String>,
+    /// Registrador auxiliar, que não aparece no programa, usado quando os
+    /// registradores de uma instrução coincidem. Sempre fica zerado entre as
+    /// instruções.
+    scratch: Option<String>,
+    /// Linhas emitidas até agora.
+    lines: Vec<Line>,
+}
+
+impl Emitter {
+    /// Cria o emissor, reservando desde já os nomes de todos os rótulos do
+    /// programa, para que rótulos auxiliares nunca colidam com eles.
+    fn new(program: &Program) -> Self {
+        let mut this = Self {
+            taken_labels: HashSet::new(),
+            labels: IndexMap::new(),
+            taken_registers: HashSet::new(),
+            registers: IndexMap::new(),
+            scratch: None,
+            lines: Vec::new(),
+        };
+
+        for instruction in program {
+            this.label(instruction.label());
+        }
+        program.collect_labels(|label| {
+            this.label(label);
+        });
+        for name in ["X", "Y"] {
+            this.register(name);
+        }
+        program.collect_registers(|register| {
+            this.register(register);
+        });
+
+        this
+    }
+
+    /// Retorna o novo nome de um rótulo original, criando-o se necessário.
+    fn label(&mut self, original: &str) -> String {
+        if let Some(renamed) = self.labels.get(original) {
+            return renamed.clone();
+        }
+        let renamed = unique(&mut self.taken_labels, sanitize(original));
+        self.labels.insert(original.to_owned(), renamed.clone());
+        renamed
+    }
+
+    /// Retorna o novo nome de um registrador original, criando-o se
+    /// necessário. Registradores só com dígitos seriam lidos como números, e
+    /// por isso ganham um `_` na frente.
+    fn register(&mut self, original: &str) -> String {
+        if let Some(renamed) = self.registers.get(original) {
+            return renamed.clone();
+        }
+        let mut base = sanitize(original);
+        if base.chars().all(|character| character.is_ascii_digit()) {
+            base.insert(0, '_');
+        }
+        let renamed = unique(&mut self.taken_registers, base);
+        self.registers.insert(original.to_owned(), renamed.clone());
+        renamed
+    }
+
+    /// Retorna o registrador auxiliar, criando-o se necessário. Como todos os
+    /// registradores do programa já foram renomeados em [`Emitter::new`], ele
+    /// nunca colide com algum deles.
+    fn scratch(&mut self) -> String {
+        if let Some(scratch) = &self.scratch {
+            return scratch.clone();
+        }
+        let scratch = unique(&mut self.taken_registers, String::from("Aux"));
+        self.scratch = Some(scratch.clone());
+        scratch
+    }
+
+    /// Emite todas as instruções do programa, na ordem original.
+    fn lower(&mut self, program: &Program) {
+        for instruction in program {
+            let entry = self.label(instruction.label());
+            let original = if entry == instruction.label() {
+                None
+            } else {
+                Some(instruction.label().to_owned())
+            };
+
+            let mut block = Block::new(self, entry, original);
+            match &instruction.kind {
+                InstructionKind::Operation(operation) => {
+                    block.operation(operation);
+                }
+                InstructionKind::Test(test) => block.test(test),
+            }
+        }
+    }
+}
+
+/// Bloco de instruções primitivas que substitui uma única instrução do
+/// programa. A primeira instrução do bloco recebe o rótulo da instrução
+/// original; as demais recebem rótulos auxiliares.
+struct Block<'emitter> {
+    /// O emissor no qual as linhas são escritas.
+    emitter: &'emitter mut Emitter,
+    /// Rótulo (já renomeado) da primeira instrução do bloco.
+    entry: String,
+    /// Quantidade de rótulos auxiliares já alocados no bloco.
+    auxiliary: u64,
+    /// Rótulo original da instrução, para o comentário de mapeamento.
+    original: Option<String>,
+}
+
+impl<'emitter> Block<'emitter> {
+    /// Cria um bloco cuja primeira instrução tem o rótulo `entry`.
+    fn new(
+        emitter: &'emitter mut Emitter,
+        entry: String,
+        original: Option<String>,
+    ) -> Self {
+        Self { emitter, entry, auxiliary: 0, original }
+    }
+
+    /// Aloca um rótulo auxiliar para este bloco, na forma `entrada_N`.
+    fn fresh(&mut self) -> String {
+        loop {
+            self.auxiliary += 1;
+            let label = format!("{}_{}", self.entry, self.auxiliary);
+            if self.emitter.taken_labels.insert(label.clone()) {
+                break label;
+            }
+        }
+    }
+
+    /// Emite uma linha com o rótulo dado.
+    fn push(&mut self, label: &str, code: String) {
+        let original = self.original.take();
+        let code = format!("{}: {}", label, code);
+        self.emitter.lines.push(Line { code, original });
+    }
+
+    /// Emite `do inc/dec` no rótulo dado.
+    fn step(&mut self, label: &str, builtin: &str, reg: &str, next: &str) {
+        let code = format!("do {} {} goto {}", builtin, reg, next);
+        self.push(label, code);
+    }
+
+    /// Emite `if zero` no rótulo dado.
+    fn zero(&mut self, label: &str, reg: &str, then: &str, otherwise: &str) {
+        let code = format!(
+            "if zero {} then goto {} else goto {}",
+            reg, then, otherwise
+        );
+        self.push(label, code);
+    }
+
+    /// Emite uma sequência de `count` instruções iguais (`inc` ou `dec`),
+    /// começando em `label` e terminando em `next`. Se `count` for zero, emite
+    /// um desvio incondicional para `next`.
+    fn repeat(
+        &mut self,
+        label: String,
+        builtin: &str,
+        reg: &str,
+        count: &BigUint,
+        next: &str,
+    ) {
+        if count.is_zero() {
+            self.zero(&label, reg, next, next);
+            return;
+        }
+
+        let mut label = label;
+        let mut remaining = count.clone();
+        while !remaining.is_one() {
+            let following = self.fresh();
+            self.step(&label, builtin, reg, &following);
+            label = following;
+            remaining -= 1u8;
+        }
+        self.step(&label, builtin, reg, next);
+    }
+
+    /// Emite `clear`: decrementa o registrador até zero.
+    fn clear(&mut self, label: String, reg: &str, next: &str) {
+        let dec = self.fresh();
+        self.zero(&label, reg, next, &dec);
+        self.step(&dec, "dec", reg, &label);
+    }
+
+    /// Emite a soma (`builtin == "inc"`) ou subtração (`builtin == "dec"`)
+    /// de registradores: `dest` recebe `dest ± src`, `src` é preservado e
+    /// `tmp` termina zerado. Como no interpretador, o valor de `src` é lido
+    /// antes de `dest` mudar e `tmp` é zerado por último, mesmo que os
+    /// registradores coincidam.
+    fn add_or_sub(
+        &mut self,
+        builtin: &str,
+        [dest, src, tmp]: [&str; 3],
+        next: &str,
+    ) {
+        if dest == src {
+            self.double_or_clear(builtin, dest, tmp, next);
+        } else if tmp == dest || tmp == src {
+            let scratch = self.emitter.scratch();
+            let clear_tmp = self.fresh();
+            self.add_or_sub_distinct(
+                builtin,
+                [dest, src, &scratch],
+                &clear_tmp,
+            );
+            self.clear(clear_tmp, tmp, next);
+        } else {
+            self.add_or_sub_distinct(builtin, [dest, src, tmp], next);
+        }
+    }
+
+    /// Emite `dest := dest + dest` (`builtin == "inc"`) ou `dest := 0`
+    /// (`builtin == "dec"`), zerando `tmp` no final. A soma move `dest` em
+    /// dobro para o registrador auxiliar e depois de volta.
+    fn double_or_clear(
+        &mut self,
+        builtin: &str,
+        dest: &str,
+        tmp: &str,
+        next: &str,
+    ) {
+        let entry = self.entry.clone();
+        let clear_tmp = self.fresh();
+        if builtin == "dec" {
+            self.clear(entry, dest, &clear_tmp);
+        } else {
+            let scratch = self.emitter.scratch();
+            let move_dest = self.fresh();
+            let first_inc = self.fresh();
+            let second_inc = self.fresh();
+            let restore = self.fresh();
+            let move_back = self.fresh();
+            let inc_dest = self.fresh();
+
+            self.zero(&entry, dest, &restore, &move_dest);
+            self.step(&move_dest, "dec", dest, &first_inc);
+            self.step(&first_inc, "inc", &scratch, &second_inc);
+            self.step(&second_inc, "inc", &scratch, &entry);
+            self.zero(&restore, &scratch, &clear_tmp, &move_back);
+            self.step(&move_back, "dec", &scratch, &inc_dest);
+            self.step(&inc_dest, "inc", dest, &restore);
+        }
+        self.clear(clear_tmp, tmp, next);
+    }
+
+    /// Emite a soma ou subtração de [`Block::add_or_sub`] quando os três
+    /// registradores são distintos.
+    fn add_or_sub_distinct(
+        &mut self,
+        builtin: &str,
+        [dest, src, tmp]: [&str; 3],
+        next: &str,
+    ) {
+        let entry = self.entry.clone();
+        let start = self.fresh();
+        let next_src = self.fresh();
+        let save_tmp = self.fresh();
+        let restore = self.fresh();
+        let undo_src = self.fresh();
+        let undo_tmp = self.fresh();
+        let next_dest = self.fresh();
+
+        self.clear(entry, tmp, &start);
+        self.zero(&start, src, &restore, &next_dest);
+        self.step(&next_dest, builtin, dest, &next_src);
+        self.step(&next_src, "dec", src, &save_tmp);
+        self.step(&save_tmp, "inc", tmp, &start);
+        self.zero(&restore, tmp, next, &undo_src);
+        self.step(&undo_src, "inc", src, &undo_tmp);
+        self.step(&undo_tmp, "dec", tmp, &restore);
+    }
+
+    /// Emite a comparação de registradores: desvia para `then` se `left` for
+    /// igual a (`less == false`) ou menor que (`less == true`) `right`, e
+    /// para `otherwise` caso contrário. `left` e `right` são preservados e
+    /// `tmp` termina zerado. Como no interpretador, `tmp` é zerado antes da
+    /// comparação.
+    fn compare(
+        &mut self,
+        less: bool,
+        [left, right, tmp]: [&str; 3],
+        then: &str,
+        otherwise: &str,
+    ) {
+        let entry = self.entry.clone();
+        if left == right {
+            let target = if less { otherwise } else { then };
+            self.clear(entry, tmp, target);
+            return;
+        }
+
+        let check_left = self.fresh();
+        let check_both = self.fresh();
+        let check_right = self.fresh();
+        let next_left = self.fresh();
+        let next_right = self.fresh();
+        let save_tmp = self.fresh();
+        let restore_then = self.fresh();
+        let restore_else = self.fresh();
+
+        self.clear(entry, tmp, &check_left);
+        self.zero(&check_left, left, &check_both, &check_right);
+        if less {
+            self.zero(&check_both, right, &restore_else, &restore_then);
+        } else {
+            self.zero(&check_both, right, &restore_then, &restore_else);
+        }
+        self.zero(&check_right, right, &restore_else, &next_left);
+        self.step(&next_left, "dec", left, &next_right);
+        self.step(&next_right, "dec", right, &save_tmp);
+        self.step(&save_tmp, "inc", tmp, &check_left);
+
+        for (restore, target) in
+            [(restore_then, then), (restore_else, otherwise)]
+        {
+            let undo_tmp = self.fresh();
+            let undo_left = self.fresh();
+            let undo_right = self.fresh();
+            self.zero(&restore, tmp, target, &undo_tmp);
+            self.step(&undo_tmp, "dec", tmp, &undo_left);
+            self.step(&undo_left, "inc", left, &undo_right);
+            self.step(&undo_right, "inc", right, &restore);
+        }
+    }
+
+    /// Emite a comparação com uma constante. O registrador é decrementado até
+    /// `constant` vezes e depois restaurado. Com `less == false`, desvia para
+    /// `then` se o valor for igual à constante; com `less == true`, se for
+    /// menor.
+    fn compare_const(
+        &mut self,
+        less: bool,
+        reg: &str,
+        constant: &BigUint,
+        then: &str,
+        otherwise: &str,
+    ) {
+        let (below, exact, above) = if less {
+            (then, otherwise, otherwise)
+        } else {
+            (otherwise, then, otherwise)
+        };
+
+        let mut label = self.entry.clone();
+        let mut decremented = BigUint::zero();
+        while &decremented < constant {
+            let restore = self.fresh();
+            let dec = self.fresh();
+            let following = self.fresh();
+            self.zero(&label, reg, &restore, &dec);
+            self.restore(restore, reg, &decremented, below);
+            self.step(&dec, "dec", reg, &following);
+            label = following;
+            decremented += 1u8;
+        }
+
+        let restore_exact = self.fresh();
+        let restore_above = self.fresh();
+        self.zero(&label, reg, &restore_exact, &restore_above);
+        self.restore(restore_exact, reg, constant, exact);
+        self.restore(restore_above, reg, constant, above);
+    }
+
+    /// Emite `count` incrementos a partir de `label`, terminando em `next`.
+    fn restore(
+        &mut self,
+        label: String,
+        reg: &str,
+        count: &BigUint,
+        next: &str,
+    ) {
+        self.repeat(label, "inc", reg, count, next);
+    }
+
+    /// Emite uma operação, possivelmente como sequência de primitivas.
+    fn operation(&mut self, operation: &Operation) {
+        let entry = self.entry.clone();
+        let next = self.emitter.label(&operation.next);
+        let kind =
+            operation.kind.map_registers(|reg| self.emitter.register(reg));
+
+        match &kind {
+            OperationKind::Inc(reg) => self.step(&entry, "inc", reg, &next),
+            OperationKind::Dec(reg) => self.step(&entry, "dec", reg, &next),
+            OperationKind::Clear(reg) => self.clear(entry, reg, &next),
+            OperationKind::Load(reg, constant) => {
+                let load = self.fresh();
+                self.clear(entry, reg, &load);
+                self.repeat(load, "inc", reg, constant, &next);
+            }
+            OperationKind::AddConst(reg, constant) => {
+                self.repeat(entry, "inc", reg, constant, &next)
+            }
+            OperationKind::SubConst(reg, constant) => {
+                self.repeat(entry, "dec", reg, constant, &next)
+            }
+            OperationKind::Add(dest, src, tmp) => {
+                self.add_or_sub("inc", [dest, src, tmp], &next)
+            }
+            OperationKind::Sub(dest, src, tmp) => {
+                self.add_or_sub("dec", [dest, src, tmp], &next)
+            }
+        }
+    }
+
+    /// Emite um teste, possivelmente como sequência de primitivas.
+    fn test(&mut self, test: &Test) {
+        let entry = self.entry.clone();
+        let then = self.emitter.label(&test.next_then);
+        let otherwise = self.emitter.label(&test.next_else);
+        let kind = test.kind.map_registers(|reg| self.emitter.register(reg));
+
+        match &kind {
+            TestKind::Zero(reg) => self.zero(&entry, reg, &then, &otherwise),
+            TestKind::EqualsConst(reg, constant) => {
+                self.compare_const(false, reg, constant, &then, &otherwise)
+            }
+            TestKind::LessThanConst(reg, constant) => {
+                self.compare_const(true, reg, constant, &then, &otherwise)
+            }
+            TestKind::Equals(left, right, tmp) => {
+                self.compare(false, [left, right, tmp], &then, &otherwise)
+            }
+            TestKind::LessThan(left, right, tmp) => {
+                self.compare(true, [left, right, tmp], &then, &otherwise)
+            }
+        }
+    }
+}
+
+/// Troca caracteres que não são de identificador por `_` e acrescenta `_` a
+/// palavras reservadas.
+fn sanitize(name: &str) -> String {
+    let mut sanitized: String = name
+        .chars()
+        .map(|character| {
+            if character.is_ascii_alphanumeric() || character == '_' {
+                character
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    if sanitized.is_empty() || RESERVED.contains(&sanitized.as_str()) {
+        sanitized.push('_');
+    }
+
+    sanitized
+}
+
+/// Torna um nome único dentre os já tomados, acrescentando `_2`, `_3`, etc.
+fn unique(taken: &mut HashSet<String>, base: String) -> String {
+    let mut name = base.clone();
+    let mut suffix = 2u64;
+    while !taken.insert(name.clone()) {
+        name = format!("{}_{}", base, suffix);
+        suffix += 1;
+    }
+    name
+}
@@ -0,0 +1,204 @@
+use super::{emit, Config};
+use crate::{
+    compiler::{compile, test::greater_than_one},
+    interpreter::{
+        program::{
+            fixture::{operation, reg, test},
+            InstructionKind, Operation, OperationKind, Program, Test, TestKind,
+        },
+        Interpreter,
+    },
+};
+use num_bigint::BigUint;
+
+fn run(program: Program, input: u32, registers: &[&str]) -> Vec<BigUint> {
+    let mut interpreter = Interpreter::new(program);
+    interpreter.input(BigUint::from(input));
+    interpreter.run_all();
+    registers.iter().map(|name| interpreter.machine().get_value(name)).collect()
+}
+
+#[test]
+fn emit_greater_than_one() {
+    let program = compile(greater_than_one::source_code()).unwrap();
+    let source = emit(&program, &Config::default());
+
+    assert_eq!(
+        source,
+        "main {
+                             dec_x: do dec X goto inc_y_incIfNotZero_1_notZero_1
+    inc_y_incIfNotZero_1_notZero_1: if zero X then goto 0 else goto inc_y_incIfNotZero_2 // inc_y.incIfNotZero.1.notZero.1
+              inc_y_incIfNotZero_2: do inc Y goto 0 // inc_y.incIfNotZero.2
+}
+"
+    );
+
+    let recompiled = compile(&source).unwrap();
+    for input in 0..5 {
+        assert_eq!(
+            run(recompiled.clone(), input, &["Y"]),
+            run(program.clone(), input, &["Y"])
+        );
+    }
+}
+
+#[test]
+fn sanitize_and_deduplicate_names() {
+    let mut program = Program::empty();
+    program.insert(operation("do", OperationKind::Inc(reg("1")), "a.b"));
+    program.insert(operation("a.b", OperationKind::Inc(reg("Y")), "a_b"));
+    program.insert(test("a_b", TestKind::Zero(reg("X")), "true", ""));
+    program.insert(operation("tests", OperationKind::Inc(reg("tests")), "0"));
+
+    let config = Config { mapping_comments: false, ..Config::default() };
+    let source = emit(&program, &config);
+
+    assert_eq!(
+        source,
+        "main {
+      do_: do inc _1 goto a_b
+      a_b: do inc Y goto a_b_2
+    a_b_2: if zero X then goto true_ else goto _
+    tests: do inc tests goto 0
+}
+"
+    );
+    compile(&source).unwrap();
+}
+
+#[test]
+fn lower_composite_instructions() {
+    let mut program = Program::empty();
+    program.insert(operation(
+        "1",
+        OperationKind::Load(reg("A"), 3u8.into()),
+        "2",
+    ));
+    program.insert(operation(
+        "2",
+        OperationKind::Add(reg("A"), reg("X"), reg("T")),
+        "3",
+    ));
+    program.insert(operation(
+        "3",
+        OperationKind::SubConst(reg("A"), 2u8.into()),
+        "4",
+    ));
+    program.insert(test(
+        "4",
+        TestKind::LessThanConst(reg("X"), 2u8.into()),
+        "5",
+        "6",
+    ));
+    program.insert(operation(
+        "5",
+        OperationKind::AddConst(reg("Y"), 10u8.into()),
+        "6",
+    ));
+    program.insert(test(
+        "6",
+        TestKind::Equals(reg("A"), reg("X"), reg("T")),
+        "7",
+        "8",
+    ));
+    program.insert(operation(
+        "7",
+        OperationKind::AddConst(reg("Y"), 100u8.into()),
+        "8",
+    ));
+    program.insert(test(
+        "8",
+        TestKind::EqualsConst(reg("X"), 3u8.into()),
+        "9",
+        "10",
+    ));
+    program.insert(operation(
+        "9",
+        OperationKind::AddConst(reg("Y"), 1000u16.into()),
+        "10",
+    ));
+    program.insert(test(
+        "10",
+        TestKind::LessThan(reg("X"), reg("A"), reg("T")),
+        "11",
+        "12",
+    ));
+    program.insert(operation(
+        "11",
+        OperationKind::Sub(reg("Y"), reg("A"), reg("T")),
+        "12",
+    ));
+    program.insert(operation("12", OperationKind::Clear(reg("T")), "0"));
+
+    let source = emit(&program, &Config::default());
+    let recompiled = compile(&source).unwrap();
+    assert!(recompiled.instructions().all(|instruction| matches!(
+        &instruction.kind,
+        InstructionKind::Operation(Operation {
+            kind: OperationKind::Inc(_) | OperationKind::Dec(_),
+            ..
+        }) | InstructionKind::Test(Test { kind: TestKind::Zero(_), .. })
+    )));
+
+    let registers = ["X", "Y", "A", "T"];
+    for input in 0..6 {
+        assert_eq!(
+            run(recompiled.clone(), input, &registers),
+            run(program.clone(), input, &registers),
+            "input {}",
+            input
+        );
+    }
+}
+
+#[test]
+fn emit_empty_program() {
+    let source = emit(&Program::empty(), &Config::default());
+    assert_eq!(source, "main {\n}\n");
+    assert!(compile(&source).unwrap().is_empty());
+}
+
+#[test]
+fn lower_aliased_registers() {
+    let cases = [
+        OperationKind::Add(reg("A"), reg("A"), reg("T")),
+        OperationKind::Add(reg("A"), reg("A"), reg("A")),
+        OperationKind::Sub(reg("A"), reg("A"), reg("T")),
+        OperationKind::Add(reg("A"), reg("X"), reg("A")),
+        OperationKind::Add(reg("A"), reg("X"), reg("X")),
+        OperationKind::Sub(reg("A"), reg("X"), reg("X")),
+    ];
+    for kind in &cases {
+        let mut program = Program::empty();
+        program.insert(operation(
+            "1",
+            OperationKind::Load(reg("A"), 3u8.into()),
+            "2",
+        ));
+        program.insert(operation(
+            "2",
+            OperationKind::Load(reg("T"), 5u8.into()),
+            "3",
+        ));
+        program.insert(operation("3", kind.clone(), "4"));
+        program.insert(test(
+            "4",
+            TestKind::Equals(reg("A"), reg("A"), reg("T")),
+            "5",
+            "0",
+        ));
+        program.insert(operation("5", OperationKind::Inc(reg("Y")), "0"));
+
+        let recompiled = compile(&emit(&program, &Config::default())).unwrap();
+        let registers = ["X", "Y", "A", "T"];
+        for input in 0..4 {
+            assert_eq!(
+                run(recompiled.clone(), input, &registers),
+                run(program.clone(), input, &registers),
+                "{:?}, input {}",
+                kind,
+                input
+            );
+        }
+    }
+}
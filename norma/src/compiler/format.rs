@@ -0,0 +1,480 @@
+#[cfg(test)]
+mod test;
+
+use crate::compiler::{
+    lexer::{
+        generate_tokens, generate_tokens_with_trivia,
+        token::{Token, TokenType},
+    },
+    parser::parse,
+};
+use pinguim_language::error::Diagnostics;
+
+/// Configuração do formatador
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    ///
+    /// - `indent_width`: quantidade de espaços por nível de indentação
+    pub indent_width: usize,
+    ///
+    /// - `blank_lines`: quantidade de linhas em branco entre a `main` e as
+    ///   macros
+    pub blank_lines: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self { indent_width: 4, blank_lines: 1 }
+    }
+}
+
+/// Formata o código-fonte de um programa no estilo canônico, preservando os
+/// comentários. Os `:` após os labels de um mesmo corpo são alinhados,
+/// parênteses em volta do argumento de `inc`, `dec` e `zero` e vírgulas
+/// finais são removidos, e no máximo uma linha em branco seguida é mantida.
+/// Um bloco `tests` fica logo abaixo do corpo que testa, com um caso por
+/// linha.
+/// O resultado compila para o mesmo programa que a entrada, e formatá-lo de
+/// novo não o altera. Retorna os erros de sintaxe se o código for inválido.
+///
+/// - `source`: código-fonte do programa
+/// - `config`: configuração do formatador
+pub fn format(source: &str, config: &Config) -> Result<String, Diagnostics> {
+    let mut diagnostics = Diagnostics::new();
+    let tokens = generate_tokens(source, &mut diagnostics);
+    parse(tokens, &mut diagnostics);
+
+    if diagnostics.is_err() {
+        return Err(diagnostics);
+    }
+
+    let tokens = generate_tokens_with_trivia(source, &mut diagnostics);
+    Ok(Formatter::new(&tokens, config).format())
+}
+
+/// Uma linha de saída do formatador
+#[derive(Debug, Clone, Default)]
+struct Line {
+    ///
+    /// - `indent`: quantidade de espaços no início da linha
+    indent: usize,
+    ///
+    /// - `label`: label da instrução iniciada nesta linha, alinhado à direita
+    ///   na renderização
+    label: Option<String>,
+    ///
+    /// - `body`: índice do corpo ao qual o label pertence
+    body: usize,
+    ///
+    /// - `text`: conteúdo da linha após o label
+    text: String,
+}
+
+/// Tipo do último elemento escrito na linha atual, usado para decidir o
+/// espaçamento
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Last {
+    Token(TokenType),
+    Comment,
+}
+
+struct Formatter<'tokens> {
+    ///
+    /// - `tokens`: fluxo de tokens sem perdas
+    tokens: &'tokens [Token],
+    ///
+    /// - `significant`: índices (em `tokens`) dos tokens que não são
+    ///   comentários nem espaços
+    significant: Vec<usize>,
+    ///
+    /// - `config`: configuração do formatador
+    config: &'tokens Config,
+    ///
+    /// - `lines`: linhas já terminadas
+    lines: Vec<Line>,
+    ///
+    /// - `line`: linha em construção
+    line: Option<Line>,
+    ///
+    /// - `last`: último elemento escrito na linha em construção
+    last: Option<Last>,
+    ///
+    /// - `depth`: profundidade de chaves atual
+    depth: usize,
+    ///
+    /// - `bodies`: quantidade de corpos abertos até agora
+    bodies: usize,
+    ///
+    /// - `newlines`: quebras de linha no código original desde o último
+    ///   elemento escrito
+    newlines: usize,
+    ///
+    /// - `break_pending`: o próximo token deve começar uma linha nova, em um
+    ///   ponto de quebra obrigatório (e não como continuação)
+    break_pending: bool,
+    ///
+    /// - `expect_label`: o próximo token é o label de uma instrução
+    expect_label: bool,
+    ///
+    /// - `after_goto`: o próximo token é o destino de um `goto`
+    after_goto: bool,
+    ///
+    /// - `just_opened`: nada foi escrito desde a última `{`
+    just_opened: bool,
+    ///
+    /// - `need_separator`: as linhas em branco entre declarações ainda devem
+    ///   ser escritas
+    need_separator: bool,
+    ///
+    /// - `skip_close_paren`: o próximo `)` envolvia argumento de builtin e deve
+    ///   ser omitido
+    skip_close_paren: bool,
+}
+
+impl<'tokens> Formatter<'tokens> {
+    /// Cria um formatador sobre um fluxo de tokens sem perdas
+    ///
+    /// - `tokens`: fluxo de tokens, incluindo espaços e comentários
+    /// - `config`: configuração do formatador
+    fn new(tokens: &'tokens [Token], config: &'tokens Config) -> Self {
+        let significant = tokens
+            .iter()
+            .enumerate()
+            .filter(|(_, token)| is_significant(token.token_type))
+            .map(|(index, _)| index)
+            .collect();
+
+        Self {
+            tokens,
+            significant,
+            config,
+            lines: Vec::new(),
+            line: None,
+            last: None,
+            depth: 0,
+            bodies: 0,
+            newlines: 0,
+            break_pending: true,
+            expect_label: false,
+            after_goto: false,
+            just_opened: false,
+            need_separator: false,
+            skip_close_paren: false,
+        }
+    }
+
+    /// Percorre todos os tokens e renderiza o código formatado
+    fn format(mut self) -> String {
+        let mut position = 0;
+
+        for significant_index in 0..self.significant.len() {
+            let token_index = self.significant[significant_index];
+            self.trivia(position, token_index);
+            self.token(significant_index);
+            position = token_index + 1;
+        }
+
+        self.trivia(position, self.tokens.len());
+        self.end_line();
+        self.render()
+    }
+
+    /// Trata espaços e comentários entre dois tokens significativos
+    ///
+    /// - `start`: índice do primeiro token de trivia
+    /// - `end`: índice do próximo token significativo
+    fn trivia(&mut self, start: usize, end: usize) {
+        let tokens = self.tokens;
+        for token in &tokens[start..end] {
+            match token.token_type {
+                TokenType::Whitespace => {
+                    self.newlines += token.content.matches('\n').count();
+                }
+                token_type => self.comment(token_type, &token.content),
+            }
+        }
+    }
+
+    /// Escreve um comentário. Comentários em linha própria ficam na
+    /// indentação atual; os demais seguem o último token da linha. Depois de
+    /// um comentário de linha, o código continua na linha seguinte.
+    ///
+    /// - `token_type`: tipo do comentário
+    /// - `content`: texto do comentário
+    fn comment(&mut self, token_type: TokenType, content: &str) {
+        let own_line = self.newlines > 0 || self.line.is_none();
+
+        if own_line {
+            self.end_line();
+            self.separate(self.break_pending);
+            self.start_line(self.break_pending);
+        }
+
+        let content = match token_type {
+            TokenType::BlockComment => content,
+            _ => content.trim_end(),
+        };
+        self.write(content, true);
+        self.last = Some(Last::Comment);
+        self.newlines = 0;
+        self.just_opened = false;
+
+        if token_type != TokenType::BlockComment {
+            self.end_line();
+        }
+    }
+
+    /// Escreve o `index`-ésimo token significativo e atualiza o estado
+    ///
+    /// - `index`: índice em `significant`
+    fn token(&mut self, index: usize) {
+        let tokens = self.tokens;
+        let token = &tokens[self.significant[index]];
+        let token_type = token.token_type;
+
+        if self.should_skip(index) {
+            self.newlines = 0;
+            return;
+        }
+
+        let is_label = self.expect_label && token_type != TokenType::CloseCurly;
+        if token_type == TokenType::CloseCurly {
+            self.depth -= 1;
+            self.newlines = 0;
+        }
+
+        if token_type == TokenType::Tests {
+            self.need_separator = false;
+            self.newlines = 0;
+        }
+
+        if self.break_pending || token_type == TokenType::CloseCurly {
+            self.end_line();
+            self.separate(true);
+            self.start_line(true);
+        } else if self.line.is_none() {
+            self.start_line(false);
+        }
+
+        if is_label && self.is_label_then_colon(index) {
+            let line = self.line.as_mut().expect("line was just started");
+            line.label = Some(token.content.clone());
+            line.body = self.bodies;
+        } else {
+            let space = matches!(
+                self.last,
+                Some(last) if space_between(last, token_type)
+            );
+            self.write(&token.content, space);
+        }
+
+        self.last = Some(Last::Token(token_type));
+        self.newlines = 0;
+        self.just_opened = false;
+        self.break_pending = false;
+        self.expect_label = false;
+        self.update_state(index, token_type);
+    }
+
+    /// Atualiza o estado do formatador depois de escrever um token
+    ///
+    /// - `index`: índice em `significant` do token escrito
+    /// - `token_type`: tipo do token escrito
+    fn update_state(&mut self, index: usize, token_type: TokenType) {
+        if self.after_goto {
+            self.after_goto = false;
+            if self.next_type(index) != Some(TokenType::Else) {
+                self.break_pending = true;
+                self.expect_label = true;
+            }
+        }
+
+        match token_type {
+            TokenType::OpenCurly => {
+                self.depth += 1;
+                self.bodies += 1;
+                self.break_pending = true;
+                self.expect_label = true;
+                self.just_opened = true;
+            }
+            TokenType::CloseCurly => {
+                self.break_pending = true;
+                self.need_separator = self.depth == 0;
+            }
+            TokenType::Goto => self.after_goto = true,
+            TokenType::Semicolon => self.break_pending = true,
+            TokenType::BuiltInOper(_) | TokenType::BuiltInTest(_) => {
+                self.skip_close_paren =
+                    self.next_type(index) == Some(TokenType::OpenParen);
+            }
+            _ => (),
+        }
+    }
+
+    /// Decide se o `index`-ésimo token significativo deve ser omitido: os
+    /// parênteses em volta do argumento de uma builtin e vírgulas finais,
+    /// inclusive as de casos de teste
+    ///
+    /// - `index`: índice em `significant`
+    fn should_skip(&mut self, index: usize) -> bool {
+        let token_type = self.tokens[self.significant[index]].token_type;
+        let previous = index
+            .checked_sub(1)
+            .map(|prev| self.tokens[self.significant[prev]].token_type);
+
+        match token_type {
+            TokenType::OpenParen => matches!(
+                previous,
+                Some(TokenType::BuiltInOper(_) | TokenType::BuiltInTest(_))
+            ),
+            TokenType::CloseParen if self.skip_close_paren => {
+                self.skip_close_paren = false;
+                true
+            }
+            TokenType::Comma => matches!(
+                self.next_type(index),
+                Some(
+                    TokenType::CloseParen
+                        | TokenType::Arrow
+                        | TokenType::Semicolon
+                )
+            ),
+            _ => false,
+        }
+    }
+
+    /// Verifica se o label no `index`-ésimo token significativo é seguido
+    /// diretamente por `:`, sem comentários entre eles
+    ///
+    /// - `index`: índice em `significant`
+    fn is_label_then_colon(&self, index: usize) -> bool {
+        let start = self.significant[index] + 1;
+        match self.significant.get(index + 1) {
+            Some(&end) => {
+                self.tokens[end].token_type == TokenType::Colon
+                    && self.tokens[start..end]
+                        .iter()
+                        .all(|token| token.token_type == TokenType::Whitespace)
+            }
+            None => false,
+        }
+    }
+
+    /// Tipo do token significativo seguinte ao `index`-ésimo
+    ///
+    /// - `index`: índice em `significant`
+    fn next_type(&self, index: usize) -> Option<TokenType> {
+        self.significant
+            .get(index + 1)
+            .map(|&token_index| self.tokens[token_index].token_type)
+    }
+
+    /// Escreve as linhas em branco antes de uma linha nova: a separação
+    /// configurada entre declarações, ou uma linha se o código original tinha
+    /// linhas em branco
+    ///
+    /// - `boundary`: se a nova linha começa em um ponto de quebra obrigatório
+    fn separate(&mut self, boundary: bool) {
+        if self.lines.is_empty() {
+            return;
+        }
+
+        if boundary && self.depth == 0 && self.need_separator {
+            self.need_separator = false;
+            for _ in 0..self.config.blank_lines {
+                self.lines.push(Line::default());
+            }
+        } else if self.newlines > 1 && !self.just_opened {
+            self.lines.push(Line::default());
+        }
+    }
+
+    /// Começa uma linha nova
+    ///
+    /// - `boundary`: se a linha começa em um ponto de quebra obrigatório; se
+    ///   não, a linha é a continuação de uma instrução ou cabeçalho
+    fn start_line(&mut self, boundary: bool) {
+        let mut indent = self.depth * self.config.indent_width;
+        if !boundary {
+            indent += self.config.indent_width;
+        }
+        self.line = Some(Line { indent, ..Line::default() });
+        self.last = None;
+    }
+
+    /// Termina a linha atual, se houver
+    fn end_line(&mut self) {
+        if let Some(line) = self.line.take() {
+            self.lines.push(line);
+        }
+        self.last = None;
+    }
+
+    /// Escreve um texto na linha atual
+    ///
+    /// - `text`: texto a ser escrito
+    /// - `space`: se um espaço deve separar o texto do anterior
+    fn write(&mut self, text: &str, space: bool) {
+        if self.line.is_none() {
+            self.start_line(false);
+        }
+        let line = self.line.as_mut().expect("line was just started");
+        if space && (!line.text.is_empty() || line.label.is_some()) {
+            line.text.push(' ');
+        }
+        line.text.push_str(text);
+    }
+
+    /// Renderiza as linhas, alinhando os labels de cada corpo à direita
+    fn render(self) -> String {
+        let mut widths = vec![0; self.bodies + 1];
+        for line in &self.lines {
+            if let Some(label) = &line.label {
+                let width = label.chars().count();
+                widths[line.body] = widths[line.body].max(width);
+            }
+        }
+
+        let mut output = String::new();
+        for line in &self.lines {
+            if line.label.is_some() || !line.text.is_empty() {
+                output.extend((0..line.indent).map(|_| ' '));
+            }
+            if let Some(label) = &line.label {
+                let width = widths[line.body];
+                output.push_str(&format!("{:>width$}", label, width = width));
+            }
+            output.push_str(&line.text);
+            output.push('\n');
+        }
+
+        output
+    }
+}
+
+/// Verifica se um token é relevante para a gramática, isto é, não é espaço
+/// nem comentário
+///
+/// - `token_type`: tipo do token
+fn is_significant(token_type: TokenType) -> bool {
+    !token_type.is_trivia() && token_type != TokenType::DocComment
+}
+
+/// Decide se deve haver um espaço entre dois elementos da mesma linha
+///
+/// - `last`: elemento anterior
+/// - `next`: tipo do próximo token
+fn space_between(last: Last, next: TokenType) -> bool {
+    match (last, next) {
+        (Last::Comment, _) => true,
+        (
+            _,
+            TokenType::Colon
+            | TokenType::Comma
+            | TokenType::CloseParen
+            | TokenType::Semicolon,
+        ) => false,
+        (Last::Token(TokenType::OpenParen), _) => false,
+        _ => true,
+    }
+}
@@ -0,0 +1,163 @@
+use super::{format, Config};
+use crate::compiler::{
+    compile,
+    test::{greater_than_one, one_plus_is_zero},
+};
+
+fn assert_formats_to(source: &str, expected: &str) {
+    let formatted = format(source, &Config::default()).unwrap();
+    assert_eq!(formatted, expected);
+    assert_eq!(format(&formatted, &Config::default()).unwrap(), formatted);
+    assert_eq!(compile(&formatted).unwrap(), compile(source).unwrap());
+}
+
+#[test]
+fn canonical_spacing_and_alignment() {
+    let source = "main{1:do inc(X)goto 2
+10 :   if zero ( X ) then
+goto 0 else goto    1
+  2: do add(X,Y,) goto 10}
+operation add(A,B,){1:if zero B then goto 0 else goto 2
+2:do dec B goto 3
+3:do inc A goto 1}";
+
+    let expected = "main {
+     1: do inc X goto 2
+    10: if zero X then goto 0 else goto 1
+     2: do add (X, Y) goto 10
+}
+
+operation add (A, B) {
+    1: if zero B then goto 0 else goto 2
+    2: do dec B goto 3
+    3: do inc A goto 1
+}
+";
+
+    assert_formats_to(source, expected);
+}
+
+#[test]
+fn keeps_comments() {
+    let source = "// cabeçalho
+
+
+/// Programa principal
+main { // corpo
+    1: do inc X goto 2 // incrementa
+
+
+    // comentário próprio
+  2: do /* bloco */ dec X goto 0
+    3: do // quebra
+    inc X goto 0
+    // fim do corpo
+}
+// depois da main
+test t(A) {
+    1: if zero A then goto false else goto true
+}";
+
+    let expected = "// cabeçalho
+
+/// Programa principal
+main { // corpo
+    1: do inc X goto 2 // incrementa
+
+    // comentário próprio
+    2: do /* bloco */ dec X goto 0
+    3: do // quebra
+        inc X goto 0
+    // fim do corpo
+}
+
+// depois da main
+test t (A) {
+    1: if zero A then goto false else goto true
+}
+";
+
+    assert_formats_to(source, expected);
+}
+
+#[test]
+fn custom_config() {
+    let source = "main { 1: do inc X goto 0 }
+operation a(A) { 1: do inc A goto 0 }
+operation b(A) { 1: do inc A goto 0 }";
+    let config = Config { indent_width: 2, blank_lines: 2 };
+
+    let expected = "main {
+  1: do inc X goto 0
+}
+
+
+operation a (A) {
+  1: do inc A goto 0
+}
+
+
+operation b (A) {
+  1: do inc A goto 0
+}
+";
+
+    let formatted = format(source, &config).unwrap();
+    assert_eq!(formatted, expected);
+    assert_eq!(format(&formatted, &config).unwrap(), formatted);
+}
+
+#[test]
+fn fixtures_are_idempotent_and_equivalent() {
+    for source in
+        [one_plus_is_zero::source_code(), greater_than_one::source_code()]
+    {
+        let formatted = format(source, &Config::default()).unwrap();
+        assert_eq!(format(&formatted, &Config::default()).unwrap(), formatted);
+        assert_eq!(compile(&formatted).unwrap(), compile(source).unwrap());
+    }
+}
+
+#[test]
+fn syntax_errors() {
+    let errors = format("main { 1: do inc X }", &Config::default())
+        .unwrap_err()
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        errors,
+        &["Token inesperado encontrado, esperava-se um \"goto\", na linha 1 e coluna 20"]
+    );
+}
+
+#[test]
+fn tests_blocks() {
+    let source = "main { 1: do inc X goto 0 }
+
+
+tests{X=1=>Y=0,X=2,;=>X=0;}
+test t(A) { 1: if zero A then goto false else goto true } tests {
+    A = 0 => true;  A=3=>false, A = 3;
+}";
+
+    let expected = "main {
+    1: do inc X goto 0
+}
+tests {
+    X = 1 => Y = 0, X = 2;
+    => X = 0;
+}
+
+test t (A) {
+    1: if zero A then goto false else goto true
+}
+tests {
+    A = 0 => true;
+    A = 3 => false, A = 3;
+}
+";
+
+    assert_formats_to(source, expected);
+}
@@ -4,7 +4,7 @@ mod test;
 pub mod error;
 pub mod token;
 
-use error::{BadCommentStart, InvalidChar};
+use error::{BadCommentStart, InvalidChar, UnterminatedBlockComment};
 use pinguim_language::{
     error::{Diagnostics, Error},
     position::Span,
@@ -12,11 +12,32 @@ use pinguim_language::{
 use std::{error::Error as StdError, iter::Peekable, str};
 use token::{BuiltInOperation, BuiltInTest, Token, TokenType};
 
+/// Gera os tokens significativos do código-fonte, descartando espaços em
+/// branco e comentários comuns. Comentários de documentação (`///`) são
+/// mantidos, pois o parser os anexa à `main` ou macro seguinte.
 pub fn generate_tokens(
     source: &str,
     diagnostics: &mut Diagnostics,
 ) -> Vec<Token> {
-    let mut lexer = Lexer::new(source);
+    collect_tokens(Lexer::new(source, false), diagnostics)
+}
+
+/// Gera um fluxo de tokens sem perdas: espaços em branco e comentários também
+/// viram tokens (de tipos para os quais [`TokenType::is_trivia`] é
+/// verdadeiro). Se não houver erros, concatenar o conteúdo de todos os tokens
+/// reproduz exatamente o código-fonte. Útil para ferramentas que precisam
+/// preservar comentários, como um formatador.
+pub fn generate_tokens_with_trivia(
+    source: &str,
+    diagnostics: &mut Diagnostics,
+) -> Vec<Token> {
+    collect_tokens(Lexer::new(source, true), diagnostics)
+}
+
+fn collect_tokens(
+    mut lexer: Lexer,
+    diagnostics: &mut Diagnostics,
+) -> Vec<Token> {
     let mut tokens = Vec::new();
 
     while let Some(token) = lexer.generate_token(diagnostics) {
@@ -37,14 +58,22 @@ struct Lexer<'src> {
     source: Peekable<str::Chars<'src>>,
     token_content: String,
     token_span: Span,
+    keep_trivia: bool,
+    /// Se o último token significativo, sem contar comentários de
+    /// documentação, foi um `}`. `tests` só é palavra-chave logo depois do
+    /// corpo da main ou de uma macro, e em qualquer outro lugar é um
+    /// identificador comum.
+    after_block: bool,
 }
 
 impl<'src> Lexer<'src> {
-    fn new(source: &'src str) -> Self {
+    fn new(source: &'src str, keep_trivia: bool) -> Self {
         Self {
             source: source.chars().peekable(),
             token_span: Span::default(),
             token_content: String::new(),
+            keep_trivia,
+            after_block: false,
         }
     }
 
@@ -54,7 +83,14 @@ impl<'src> Lexer<'src> {
     ) -> Option<Token> {
         loop {
             match self.try_generate_token(diagnostics) {
-                Ok(token) => break Some(token),
+                Ok(token) => {
+                    let is_doc = token.token_type == TokenType::DocComment;
+                    if !token.token_type.is_trivia() && !is_doc {
+                        self.after_block =
+                            token.token_type == TokenType::CloseCurly;
+                    }
+                    break Some(token);
+                }
                 Err(Failure::EndOfInput) => break None,
                 Err(Failure::TryAgain) => (),
             }
@@ -65,11 +101,14 @@ impl<'src> Lexer<'src> {
         &mut self,
         diagnostics: &mut Diagnostics,
     ) -> Result<Token, Failure> {
-        self.skip_discardable(diagnostics);
-
         self.clear_current();
 
-        if self.is_identifier() {
+        if self.is_whitespace() {
+            self.tokenize_whitespace();
+            self.make_trivia(TokenType::Whitespace)
+        } else if self.is_comment_start() {
+            self.tokenize_comment(diagnostics)
+        } else if self.is_identifier() {
             Ok(self.tokenize_ident())
         } else if let Some(typ) = self.match_punctuation() {
             Ok(self.tokenize_punct(typ))
@@ -97,34 +136,66 @@ impl<'src> Lexer<'src> {
         self.token_span.finish();
     }
 
-    fn skip_discardable(&mut self, diagnostics: &mut Diagnostics) {
-        while self.skip_whitespace() || self.skip_comment(diagnostics) {}
-    }
-
-    fn skip_whitespace(&mut self) -> bool {
-        let mut skipped = false;
+    fn tokenize_whitespace(&mut self) {
         while self.is_whitespace() {
             self.next_char();
-            skipped = true;
         }
-        skipped
     }
 
-    fn skip_comment(&mut self, diagnostics: &mut Diagnostics) -> bool {
-        if self.is_comment_start() {
-            self.clear_current();
-            self.next_char();
-            if self.is_comment_start() {
+    fn tokenize_comment(
+        &mut self,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<Token, Failure> {
+        self.next_char();
+        match self.source.peek() {
+            Some('/') => {
                 self.next_char();
-            } else {
-                self.raise(diagnostics, BadCommentStart)
+                let is_doc = self.is_comment_start() && {
+                    self.next_char();
+                    !self.is_comment_start()
+                };
+                self.skip_line();
+                if is_doc {
+                    Ok(self.make_token(TokenType::DocComment))
+                } else {
+                    self.make_trivia(TokenType::LineComment)
+                }
             }
-            while !self.is_comment_end() {
+            Some('*') => {
                 self.next_char();
+                self.tokenize_block_comment(diagnostics);
+                self.make_trivia(TokenType::BlockComment)
+            }
+            _ => {
+                self.raise(diagnostics, BadCommentStart);
+                self.skip_line();
+                self.make_trivia(TokenType::LineComment)
+            }
+        }
+    }
+
+    fn tokenize_block_comment(&mut self, diagnostics: &mut Diagnostics) {
+        loop {
+            match self.source.peek() {
+                Some('*') => {
+                    self.next_char();
+                    if self.is_comment_start() {
+                        self.next_char();
+                        break;
+                    }
+                }
+                Some(_) => self.next_char(),
+                None => {
+                    self.raise(diagnostics, UnterminatedBlockComment);
+                    break;
+                }
             }
-            true
-        } else {
-            false
+        }
+    }
+
+    fn skip_line(&mut self) {
+        while !self.is_comment_end() {
+            self.next_char();
         }
     }
 
@@ -152,9 +223,23 @@ impl<'src> Lexer<'src> {
 
     fn tokenize_punct(&mut self, token_type: TokenType) -> Token {
         self.next_char();
+        if token_type == TokenType::Equals && self.source.peek() == Some(&'>') {
+            self.next_char();
+            return self.make_token(TokenType::Arrow);
+        }
         self.make_token(token_type)
     }
 
+    /// Produz um token de trivia se o lexer estiver preservando trivia, ou
+    /// descarta-o e pede uma nova tentativa caso contrário.
+    fn make_trivia(&mut self, token_type: TokenType) -> Result<Token, Failure> {
+        if self.keep_trivia {
+            Ok(self.make_token(token_type))
+        } else {
+            Err(Failure::TryAgain)
+        }
+    }
+
     fn make_token(&mut self, token_type: TokenType) -> Token {
         Token {
             token_type,
@@ -215,6 +300,8 @@ impl<'src> Lexer<'src> {
                 '}' => Some(TokenType::CloseCurly),
                 '(' => Some(TokenType::OpenParen),
                 ')' => Some(TokenType::CloseParen),
+                '=' => Some(TokenType::Equals),
+                ';' => Some(TokenType::Semicolon),
                 _ => None,
             },
             _ => None,
@@ -230,6 +317,7 @@ impl<'src> Lexer<'src> {
             "main" => Some(TokenType::Main),
             "operation" => Some(TokenType::Operation),
             "test" => Some(TokenType::Test),
+            "tests" if self.after_block => Some(TokenType::Tests),
             "then" => Some(TokenType::Then),
             _ => None,
         }
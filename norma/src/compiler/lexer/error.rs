@@ -23,3 +23,14 @@ impl fmt::Display for BadCommentStart {
 }
 
 impl Error for BadCommentStart {}
+
+#[derive(Debug, Clone)]
+pub struct UnterminatedBlockComment;
+
+impl fmt::Display for UnterminatedBlockComment {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "Comentário de bloco não foi terminado")
+    }
+}
+
+impl Error for UnterminatedBlockComment {}
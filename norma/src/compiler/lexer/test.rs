@@ -1,5 +1,5 @@
 use super::{
-    generate_tokens,
+    generate_tokens, generate_tokens_with_trivia,
     token::{BuiltInOperation, BuiltInTest, Token, TokenType},
 };
 use pinguim_language::{
@@ -1150,3 +1150,173 @@ fn many_errors() {
         ]
     );
 }
+
+#[test]
+fn block_comments() {
+    let mut diagnostics = Diagnostics::new();
+    let tokens = generate_tokens("/* a\n * b */do/**/if", &mut diagnostics);
+    assert!(diagnostics.is_ok());
+
+    let types = tokens.iter().map(|token| token.token_type).collect::<Vec<_>>();
+    assert_eq!(types, &[TokenType::Do, TokenType::If]);
+    assert_eq!(tokens[0].span.start.line, 2);
+    assert_eq!(tokens[0].span.start.column, 8);
+}
+
+#[test]
+fn unterminated_block_comment() {
+    let mut diagnostics = Diagnostics::new();
+    let tokens = generate_tokens("/* abc", &mut diagnostics);
+    assert!(tokens.is_empty());
+
+    let errors =
+        diagnostics.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+    assert_eq!(
+        errors,
+        &["Comentário de bloco não foi terminado, da linha 1 e coluna 1, até a coluna 6"]
+    );
+}
+
+#[test]
+fn doc_comments() {
+    let mut diagnostics = Diagnostics::new();
+    let tokens =
+        generate_tokens("/// doc\n//// not doc\nmain", &mut diagnostics);
+    assert!(diagnostics.is_ok());
+
+    assert_eq!(
+        tokens,
+        &[
+            Token {
+                token_type: TokenType::DocComment,
+                content: "/// doc".to_owned(),
+                span: Span {
+                    start: Position {
+                        line: 1,
+                        column: 1,
+                        utf8_index: 0,
+                        utf16_index: 0,
+                    },
+                    end: Position {
+                        line: 1,
+                        column: 8,
+                        utf8_index: 7,
+                        utf16_index: 7,
+                    },
+                },
+            },
+            Token {
+                token_type: TokenType::Main,
+                content: "main".to_owned(),
+                span: Span {
+                    start: Position {
+                        line: 3,
+                        column: 1,
+                        utf8_index: 21,
+                        utf16_index: 21,
+                    },
+                    end: Position {
+                        line: 3,
+                        column: 5,
+                        utf8_index: 25,
+                        utf16_index: 25,
+                    },
+                },
+            },
+        ]
+    );
+}
+
+#[test]
+fn lossless_trivia() {
+    let source = "// id\n/// doc\nmain {\n  1: do inc X /* x */ goto 0\n}\n";
+    let mut diagnostics = Diagnostics::new();
+    let tokens = generate_tokens_with_trivia(source, &mut diagnostics);
+    assert!(diagnostics.is_ok());
+
+    let rebuilt =
+        tokens.iter().map(|token| token.content.as_str()).collect::<String>();
+    assert_eq!(rebuilt, source);
+
+    let types = tokens
+        .iter()
+        .map(|token| token.token_type)
+        .filter(|token_type| !token_type.is_trivia())
+        .collect::<Vec<_>>();
+    let without_trivia = generate_tokens(source, &mut diagnostics)
+        .iter()
+        .map(|token| token.token_type)
+        .collect::<Vec<_>>();
+    assert_eq!(types, without_trivia);
+}
+
+#[test]
+fn test_case_punctuation() {
+    let source = "} tests { X = 8 => Y = 3; A=>B }";
+    let mut diagnostics = Diagnostics::new();
+    let types = generate_tokens(source, &mut diagnostics)
+        .iter()
+        .map(|token| token.token_type)
+        .collect::<Vec<_>>();
+    assert!(diagnostics.is_ok());
+
+    assert_eq!(
+        types,
+        vec![
+            TokenType::CloseCurly,
+            TokenType::Tests,
+            TokenType::OpenCurly,
+            TokenType::Identifier,
+            TokenType::Equals,
+            TokenType::Number,
+            TokenType::Arrow,
+            TokenType::Identifier,
+            TokenType::Equals,
+            TokenType::Number,
+            TokenType::Semicolon,
+            TokenType::Identifier,
+            TokenType::Arrow,
+            TokenType::Identifier,
+            TokenType::CloseCurly,
+        ]
+    );
+}
+
+#[test]
+fn tests_is_contextual() {
+    let source = "main { tests: do inc tests goto 0 } // fim
+    tests { tests = 1 => Y = 0 }";
+    let mut diagnostics = Diagnostics::new();
+    let types = generate_tokens(source, &mut diagnostics)
+        .iter()
+        .map(|token| token.token_type)
+        .filter(|token_type| {
+            matches!(token_type, TokenType::Identifier | TokenType::Tests)
+        })
+        .collect::<Vec<_>>();
+    assert!(diagnostics.is_ok());
+
+    assert_eq!(
+        types,
+        vec![
+            TokenType::Identifier,
+            TokenType::Identifier,
+            TokenType::Tests,
+            TokenType::Identifier,
+            TokenType::Identifier,
+        ]
+    );
+}
+
+#[test]
+fn tests_after_doc_comment() {
+    let source = "main { 1: do inc Y goto 0 } /// casos
+    tests { => Y = 1 }";
+    let mut diagnostics = Diagnostics::new();
+    let tokens = generate_tokens(source, &mut diagnostics);
+    assert!(diagnostics.is_ok());
+
+    let tests = tokens.iter().find(|token| token.content == "tests").unwrap();
+    assert_eq!(tests.token_type, TokenType::Tests);
+}
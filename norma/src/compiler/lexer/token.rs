@@ -11,6 +11,7 @@ pub enum TokenType {
     Main,
     Operation,
     Test,
+    Tests,
     Then,
     BuiltInOper(BuiltInOperation),
     BuiltInTest(BuiltInTest),
@@ -22,6 +23,27 @@ pub enum TokenType {
     CloseParen,
     OpenCurly,
     CloseCurly,
+    Equals,
+    Arrow,
+    Semicolon,
+    DocComment,
+    Whitespace,
+    LineComment,
+    BlockComment,
+}
+
+impl TokenType {
+    /// Retorna se esse tipo de token é trivia, isto é, algo sem significado
+    /// para o parser (espaço em branco ou comentário comum), gerado somente
+    /// pelo fluxo de tokens sem perdas.
+    pub fn is_trivia(self) -> bool {
+        matches!(
+            self,
+            TokenType::Whitespace
+                | TokenType::LineComment
+                | TokenType::BlockComment
+        )
+    }
 }
 
 impl fmt::Display for TokenType {
@@ -34,6 +56,7 @@ impl fmt::Display for TokenType {
             TokenType::Main => write!(formatter, "main"),
             TokenType::Operation => write!(formatter, "operation"),
             TokenType::Test => write!(formatter, "test"),
+            TokenType::Tests => write!(formatter, "tests"),
             TokenType::Then => write!(formatter, "then"),
             TokenType::BuiltInOper(builtin_oper) => {
                 write!(formatter, "{}", builtin_oper)
@@ -49,6 +72,17 @@ impl fmt::Display for TokenType {
             TokenType::CloseParen => write!(formatter, ")"),
             TokenType::OpenCurly => write!(formatter, "{{"),
             TokenType::CloseCurly => write!(formatter, "}}"),
+            TokenType::Equals => write!(formatter, "="),
+            TokenType::Arrow => write!(formatter, "=>"),
+            TokenType::Semicolon => write!(formatter, ";"),
+            TokenType::DocComment => {
+                write!(formatter, "<comentário de documentação>")
+            }
+            TokenType::Whitespace => write!(formatter, "<espaço em branco>"),
+            TokenType::LineComment => write!(formatter, "<comentário>"),
+            TokenType::BlockComment => {
+                write!(formatter, "<comentário de bloco>")
+            }
         }
     }
 }
@@ -8,8 +8,9 @@ use crate::compiler::lexer::token::{
     BuiltInOperation, BuiltInTest, Token, TokenType,
 };
 use ast::{
-    Instruction, InstructionType, Macro, MacroArgument, MacroType, Main,
-    Operation, OperationType, Program, Symbol, Test, TestType,
+    Expectation, Instruction, InstructionType, Macro, MacroArgument, MacroType,
+    Main, Operation, OperationType, Program, RegisterValue, Symbol, Test,
+    TestCase, TestType,
 };
 use error::{
     InvalidLabel, LabelAlreadyDeclared, MacroAlreadyDeclared,
@@ -18,9 +19,13 @@ use error::{
 };
 use indexmap::IndexMap;
 use num_bigint::BigUint;
-use pinguim_language::error::{Diagnostics, Error};
+use pinguim_language::{
+    error::{Diagnostics, Error},
+    position::Span,
+};
+use std::collections::HashMap;
 use std::error::Error as StdError;
-use std::str::FromStr;
+use std::{mem, str::FromStr};
 
 /// - `tokens`: vetor de tokens
 /// - `diagnostics`: vetor que armazena erros coletados durante a compilação
@@ -46,14 +51,57 @@ struct Parser {
     ///
     /// - `is_current_error`: sinaliza se o token atual é válido
     is_current_error: bool,
+    ///
+    /// - `docs`: comentários de documentação indexados pelo índice do token
+    ///   que os sucede
+    docs: HashMap<usize, Vec<Symbol>>,
 }
 
 impl Parser {
-    /// Cria uma nova estrutura de Parser
+    /// Cria uma nova estrutura de Parser. Comentários de documentação são
+    /// retirados do vetor de tokens e guardados à parte, associados ao token
+    /// que vem logo depois deles.
     ///
     /// - `tokens`: vetor de tokens
     fn new(tokens: Vec<Token>) -> Self {
-        Parser { tokens, curr_token: 0, is_current_error: false }
+        let mut significant = Vec::with_capacity(tokens.len());
+        let mut docs = HashMap::new();
+        let mut pending = Vec::new();
+
+        for token in tokens {
+            if token.token_type == TokenType::DocComment {
+                pending.push(Self::doc_symbol(token));
+            } else {
+                if !pending.is_empty() {
+                    docs.insert(significant.len(), mem::take(&mut pending));
+                }
+                significant.push(token);
+            }
+        }
+
+        Parser {
+            tokens: significant,
+            curr_token: 0,
+            is_current_error: false,
+            docs,
+        }
+    }
+
+    /// Converte um token de comentário de documentação em um símbolo com o
+    /// texto do comentário, sem o `///` e sem o espaço que o segue.
+    ///
+    /// - `token`: token do tipo `DocComment`
+    fn doc_symbol(token: Token) -> Symbol {
+        let text = token.content.trim_start_matches('/');
+        let text = text.strip_prefix(' ').unwrap_or(text);
+        Symbol { content: text.trim_end().to_owned(), span: token.span }
+    }
+
+    /// Retira os comentários de documentação que precedem o token atual.
+    /// Comentários que não precedem `main`, `operation` ou `test` nunca são
+    /// retirados e, portanto, são ignorados como comentários comuns.
+    fn take_docs(&mut self) -> Vec<Symbol> {
+        self.docs.remove(&self.current_index()).unwrap_or_default()
     }
 
     fn current_index(&self) -> usize {
@@ -284,10 +332,12 @@ impl Parser {
         &mut self,
         diagnostics: &mut Diagnostics,
     ) -> Result<Option<Main>, Abort> {
+        let docs = self.take_docs();
         self.next();
         let instructions = self.parse_func_body(diagnostics)?;
+        let tests = self.parse_tests(diagnostics)?;
 
-        Ok(Some(Main { code: instructions }))
+        Ok(Some(Main { code: instructions, docs, tests }))
     }
 
     /// Faz o parse do código de qualquer função
@@ -340,16 +390,20 @@ impl Parser {
         macro_type: MacroType,
         diagnostics: &mut Diagnostics,
     ) -> Result<Option<Macro>, Abort> {
+        let docs = self.take_docs();
         self.next();
         let name_option = self.parse_macro_name(diagnostics)?;
         let parameters = self.parse_macro_def_params(diagnostics)?;
         let instructions = self.parse_func_body(diagnostics)?;
+        let tests = self.parse_tests(diagnostics)?;
 
         Ok(name_option.map(|name| Macro {
             macro_type,
             name,
             parameters,
             instr: instructions,
+            docs,
+            tests,
         }))
     }
 
@@ -446,7 +500,9 @@ impl Parser {
         diagnostics: &mut Diagnostics,
     ) -> Result<Option<Operation>, Abort> {
         self.next();
+        let start = self.require_current(diagnostics)?.span.start;
         let oper_type = self.parse_operation_type(diagnostics)?;
+        let end = self.tokens[self.current_index() - 1].span.end;
 
         self.expect(TokenType::Goto, diagnostics)?;
         let oper_label = self.parse_label(diagnostics)?;
@@ -454,6 +510,7 @@ impl Parser {
         let zipped = oper_type.zip(oper_label);
         let operation = zipped.map(|(oper_type, oper_label)| Operation {
             oper_type,
+            span: Span { start, end },
             next_label: oper_label,
         });
 
@@ -527,7 +584,9 @@ impl Parser {
         diagnostics: &mut Diagnostics,
     ) -> Result<Option<Test>, Abort> {
         self.next();
+        let start = self.require_current(diagnostics)?.span.start;
         let test_type = self.parse_test_type(diagnostics)?;
+        let end = self.tokens[self.current_index() - 1].span.end;
 
         self.expect(TokenType::Then, diagnostics)?;
         self.expect(TokenType::Goto, diagnostics)?;
@@ -540,6 +599,7 @@ impl Parser {
         let zipped = test_type.zip(then_label).zip(else_label);
         let test = zipped.map(|((test_type, then_label), else_label)| Test {
             test_type,
+            span: Span { start, end },
             next_true_label: then_label,
             next_false_label: else_label,
         });
@@ -628,25 +688,41 @@ impl Parser {
     /// - `diagnostics`: vetor que armazena erros coletados durante a compilação
     fn parse_param_list<F, T>(
         &mut self,
-        mut parse_param: F,
+        parse_param: F,
         diagnostics: &mut Diagnostics,
     ) -> Result<Vec<T>, Abort>
     where
         F: FnMut(&mut Self, &mut Diagnostics) -> Result<Option<T>, Abort>,
     {
         self.expect(TokenType::OpenParen, diagnostics)?;
+        self.parse_list_until(TokenType::CloseParen, parse_param, diagnostics)
+    }
 
-        let mut parameters = Vec::new();
+    /// Faz o parse de uma lista de itens separados por vírgula, até o token
+    /// que termina a lista (inclusive)
+    ///
+    /// - `terminator`: tipo do token que termina a lista
+    /// - `parse_item`: função genérica que faz o parse de um item
+    /// - `diagnostics`: vetor que armazena erros coletados durante a compilação
+    fn parse_list_until<F, T>(
+        &mut self,
+        terminator: TokenType,
+        mut parse_item: F,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<Vec<T>, Abort>
+    where
+        F: FnMut(&mut Self, &mut Diagnostics) -> Result<Option<T>, Abort>,
+    {
+        let mut items = Vec::new();
         let mut needs_comma = false;
 
         self.loop_parser(|parser| {
-            if parser.check_expect(TokenType::CloseParen, diagnostics)? {
+            if parser.check_expect(terminator, diagnostics)? {
                 return Ok(false);
             }
 
             if needs_comma {
-                let expected_types =
-                    vec![TokenType::Comma, TokenType::CloseParen];
+                let expected_types = vec![TokenType::Comma, terminator];
 
                 parser.raise_error_on_current(
                     UnexpectedToken { expected_types },
@@ -654,8 +730,8 @@ impl Parser {
                 );
             }
 
-            if let Some(parameter) = parse_param(parser, diagnostics)? {
-                parameters.push(parameter);
+            if let Some(item) = parse_item(parser, diagnostics)? {
+                items.push(item);
                 needs_comma =
                     !parser.check_expect(TokenType::Comma, diagnostics)?;
             }
@@ -663,7 +739,7 @@ impl Parser {
             Ok(true)
         })?;
 
-        Ok(parameters)
+        Ok(items)
     }
 
     /// Faz o parse dos argumentos da macro
@@ -713,6 +789,129 @@ impl Parser {
         }
     }
 
+    /// Faz o parse do bloco `tests` opcional que sucede o corpo da main ou de
+    /// uma macro. Retorna nenhum caso se não houver bloco.
+    ///
+    /// - `diagnostics`: vetor que armazena erros coletados durante a compilação
+    fn parse_tests(
+        &mut self,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<Vec<TestCase>, Abort> {
+        match self.current() {
+            Some(token) if token.token_type == TokenType::Tests => self.next(),
+            _ => return Ok(Vec::new()),
+        }
+
+        self.expect(TokenType::OpenCurly, diagnostics)?;
+        let mut cases = Vec::new();
+
+        self.loop_parser(|parser| {
+            if parser.check_expect(TokenType::CloseCurly, diagnostics)? {
+                return Ok(false);
+            }
+            cases.push(parser.parse_test_case(diagnostics)?);
+            Ok(true)
+        })?;
+
+        Ok(cases)
+    }
+
+    /// Faz o parse de um caso de teste, e.g. `X = 8 => Y = 3;`
+    ///
+    /// - `diagnostics`: vetor que armazena erros coletados durante a compilação
+    fn parse_test_case(
+        &mut self,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<TestCase, Abort> {
+        let start = self.require_current(diagnostics)?.span.start;
+        let inputs = self.parse_list_until(
+            TokenType::Arrow,
+            Self::parse_register_value,
+            diagnostics,
+        )?;
+        let expected = self.parse_list_until(
+            TokenType::Semicolon,
+            Self::parse_expectation,
+            diagnostics,
+        )?;
+        let end = self.tokens[self.current_index() - 1].span.end;
+
+        Ok(TestCase { inputs, expected, span: Span { start, end } })
+    }
+
+    /// Faz o parse de um registrador com valor, e.g. `X = 8`
+    ///
+    /// - `diagnostics`: vetor que armazena erros coletados durante a compilação
+    fn parse_register_value(
+        &mut self,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<Option<RegisterValue>, Abort> {
+        let register = self.parse_register(diagnostics)?;
+        self.expect(TokenType::Equals, diagnostics)?;
+        let value = self.parse_number(diagnostics)?;
+
+        Ok(register
+            .zip(value)
+            .map(|(register, value)| RegisterValue { register, value }))
+    }
+
+    /// Faz o parse do resultado esperado de um caso de teste: um registrador
+    /// com valor, ou `true`/`false` para macros de teste
+    ///
+    /// - `diagnostics`: vetor que armazena erros coletados durante a compilação
+    fn parse_expectation(
+        &mut self,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<Option<Expectation>, Abort> {
+        let token = self.require_current(diagnostics)?;
+        let is_branch = token.token_type == TokenType::Identifier
+            && (token.content == "true" || token.content == "false");
+        let is_register = matches!(
+            self.tokens.get(self.current_index() + 1),
+            Some(next) if next.token_type == TokenType::Equals
+        );
+
+        if is_branch && !is_register {
+            let symbol =
+                Symbol { content: token.content.clone(), span: token.span };
+            self.next();
+            Ok(Some(Expectation::Branch(symbol)))
+        } else {
+            let register_value = self.parse_register_value(diagnostics)?;
+            Ok(register_value.map(Expectation::Register))
+        }
+    }
+
+    /// Faz o parse de uma constante numérica
+    ///
+    /// - `diagnostics`: vetor que armazena erros coletados durante a compilação
+    fn parse_number(
+        &mut self,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<Option<BigUint>, Abort> {
+        let token = self.require_current(diagnostics)?;
+
+        if token.token_type == TokenType::Number {
+            let constant = BigUint::from_str(&token.content)
+                .expect("Lexer só deve permitir tokens Number só com dígitos");
+            self.next();
+            Ok(Some(constant))
+        } else {
+            // um registrador no lugar do número é pulado, para que o resto do
+            // caso não acuse erros em cascata
+            let is_register = token.token_type == TokenType::Identifier;
+            let expected_types = vec![TokenType::Number];
+            self.raise_error_on_current(
+                UnexpectedToken { expected_types },
+                diagnostics,
+            );
+            if is_register {
+                self.next();
+            }
+            Ok(None)
+        }
+    }
+
     /// Faz o parse de registradores
     ///
     /// - `diagnostics`: vetor que armazena erros coletados durante a compilação
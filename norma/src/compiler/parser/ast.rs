@@ -50,6 +50,10 @@ pub struct Operation {
     /// - `oper_type`: tipo de operação
     pub oper_type: OperationType,
     ///
+    /// - `span`: localização da operação no código, do nome até o último
+    ///   argumento
+    pub span: Span,
+    ///
     /// - `next_label`: rótulo para o qual essa instrução manda após executar sua operação
     pub next_label: Symbol,
 }
@@ -60,6 +64,10 @@ pub struct Test {
     /// - `test_type`: tipo de teste
     pub test_type: TestType,
     ///
+    /// - `span`: localização do teste no código, do nome até o último
+    ///   argumento
+    pub span: Span,
+    ///
     /// - `next_true_label`: rótulo para o qual essa instrução manda após executar seu teste e ele der verdadeiro
     pub next_true_label: Symbol,
     ///
@@ -81,6 +89,22 @@ pub struct Macro {
     ///
     /// - `instr`: mapeamento das instruções com seus labels (código da macro)
     pub instr: IndexMap<String, Instruction>,
+    ///
+    /// - `docs`: linhas dos comentários de documentação (`///`) que precedem a
+    ///   macro
+    pub docs: Vec<Symbol>,
+    ///
+    /// - `tests`: casos do bloco `tests` que sucede a macro, com os parâmetros
+    ///   ligados diretamente a valores
+    pub tests: Vec<TestCase>,
+}
+
+impl Macro {
+    /// Retorna o texto da documentação da macro, com as linhas dos
+    /// comentários de documentação separadas por quebras de linha
+    pub fn documentation(&self) -> String {
+        join_docs(&self.docs)
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -137,6 +161,63 @@ pub struct Main {
     ///
     /// - `code`: instruções do corpo da função main
     pub code: IndexMap<String, Instruction>,
+    ///
+    /// - `docs`: linhas dos comentários de documentação (`///`) que precedem a
+    ///   main
+    pub docs: Vec<Symbol>,
+    ///
+    /// - `tests`: casos do bloco `tests` que sucede a main
+    pub tests: Vec<TestCase>,
+}
+
+impl Main {
+    /// Retorna o texto da documentação da main, com as linhas dos comentários
+    /// de documentação separadas por quebras de linha
+    pub fn documentation(&self) -> String {
+        join_docs(&self.docs)
+    }
+}
+
+/// Junta as linhas de documentação em um único texto
+///
+/// - `docs`: linhas dos comentários de documentação
+fn join_docs(docs: &[Symbol]) -> String {
+    let lines: Vec<_> = docs.iter().map(|line| line.content.as_str()).collect();
+    lines.join("\n")
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TestCase {
+    ///
+    /// - `inputs`: valores iniciais dos registradores, e.g. `X = 8`
+    pub inputs: Vec<RegisterValue>,
+    ///
+    /// - `expected`: o que se espera ao fim da execução, e.g. `Y = 3`
+    pub expected: Vec<Expectation>,
+    ///
+    /// - `span`: localização do caso no código, do primeiro token até o `;`
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RegisterValue {
+    ///
+    /// - `register`: registrador
+    pub register: Symbol,
+    ///
+    /// - `value`: valor do registrador
+    pub value: BigUint,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Expectation {
+    ///
+    /// - `Register`: o registrador deve terminar com o valor dado
+    Register(RegisterValue),
+    ///
+    /// - `Branch`: uma macro de teste deve resultar em `true` ou `false`, e
+    ///   carrega consigo o símbolo do resultado esperado
+    Branch(Symbol),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
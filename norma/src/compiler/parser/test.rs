@@ -1,6 +1,9 @@
 use crate::compiler::{
-    lexer::generate_tokens, parser::parse, test::greater_than_one,
+    lexer::generate_tokens,
+    parser::{ast::Expectation, parse},
+    test::greater_than_one,
 };
+use num_bigint::BigUint;
 use pinguim_language::error::Diagnostics;
 
 #[test]
@@ -171,3 +174,112 @@ fn normal_label_as_false() {
         &["Nome de label não pode ser \"true\" nem \"false\", da linha 2 e coluna 5, até a coluna 9"]
     )
 }
+
+#[test]
+fn doc_comments_attached_to_declarations() {
+    let code = "/// Programa principal.
+main {
+    /// comentário comum
+    1: do inc X goto 0
+}
+
+// comentário comum
+/// Incrementa duas vezes.
+///
+///Sem espaço.
+operation incTwice(A) {
+    1: do inc A goto 2
+    2: do inc A goto true
+}
+
+test empty(A) {
+    1: if zero A then goto true else goto false
+}";
+    let mut diagnostics = Diagnostics::new();
+
+    let tokens = generate_tokens(code, &mut diagnostics);
+    let result = parse(tokens, &mut diagnostics).unwrap();
+    assert!(diagnostics.is_ok());
+
+    assert_eq!(result.main.documentation(), "Programa principal.");
+    assert_eq!(
+        result.macros["incTwice"].documentation(),
+        "Incrementa duas vezes.\n\nSem espaço."
+    );
+    assert_eq!(result.macros["incTwice"].docs[0].span.start.line, 8);
+    assert!(result.macros["empty"].docs.is_empty());
+}
+
+#[test]
+fn tests_blocks() {
+    let code = "main {
+    1: do inc X goto 0
+}
+tests {
+    X = 8 => Y = 3;
+    => Y = 0, X = 0;
+}
+
+test isZero (A) {
+    1: if zero A then goto true else goto false
+}
+tests {
+    A = 0 => true;
+    A = 2 => false, A = 2;
+}";
+
+    let mut diagnostics = Diagnostics::new();
+    let tokens = generate_tokens(code, &mut diagnostics);
+    let result = parse(tokens, &mut diagnostics).unwrap();
+    assert!(diagnostics.is_ok());
+
+    let cases = &result.main.tests;
+    assert_eq!(cases.len(), 2);
+    assert_eq!(cases[0].inputs.len(), 1);
+    assert_eq!(cases[0].inputs[0].register.content, "X");
+    assert_eq!(cases[0].inputs[0].value, BigUint::from(8u8));
+    match &cases[0].expected[..] {
+        [Expectation::Register(output)] => {
+            assert_eq!(output.register.content, "Y");
+            assert_eq!(output.value, BigUint::from(3u8));
+        }
+        other => panic!("unexpected expectations {:?}", other),
+    }
+    assert_eq!(
+        cases[0].span.to_string(),
+        "da linha 5 e coluna 5, até a coluna 19"
+    );
+    assert!(cases[1].inputs.is_empty());
+    assert_eq!(cases[1].expected.len(), 2);
+
+    let cases = &result.macros["isZero"].tests;
+    assert_eq!(cases.len(), 2);
+    match &cases[1].expected[..] {
+        [Expectation::Branch(branch), Expectation::Register(register)] => {
+            assert_eq!(branch.content, "false");
+            assert_eq!(register.register.content, "A");
+        }
+        other => panic!("unexpected expectations {:?}", other),
+    }
+}
+
+#[test]
+fn tests_block_errors() {
+    let code = "main {
+    1: do inc X goto 0
+}
+tests {
+    X = Y => Y = 3;
+}";
+
+    let mut diagnostics = Diagnostics::new();
+    let tokens = generate_tokens(code, &mut diagnostics);
+    let _result = parse(tokens, &mut diagnostics);
+    let errors =
+        diagnostics.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+    assert_eq!(
+        errors,
+        &["Token inesperado encontrado, esperava-se um \"<número>\", na linha 5 e coluna 9"],
+    );
+}
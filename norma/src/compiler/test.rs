@@ -20,3 +20,14 @@ fn compile_gt_one() {
 
     assert_eq!(expected_program, program);
 }
+
+#[test]
+fn tests_as_identifier() {
+    let program = compile(
+        "main { tests: do tests (Y) goto 0 }
+        operation tests (A) { 1: do inc A goto 0 }",
+    )
+    .unwrap();
+    assert_eq!(program.to_string(), "tests.tests.1: do inc Y goto 0\n");
+    compile("main { 1: do inc tests goto 0 }").unwrap();
+}
@@ -107,6 +107,20 @@ fn ast_main_dec_x() -> ast::Instruction {
                     },
                 },
             ),
+            span: Span {
+                start: Position {
+                    utf8_index: 311,
+                    utf16_index: 311,
+                    line: 15,
+                    column: 19,
+                },
+                end: Position {
+                    utf8_index: 316,
+                    utf16_index: 316,
+                    line: 15,
+                    column: 24,
+                },
+            },
             next_label: ast::Symbol {
                 content: String::from("inc_y"),
                 span: Span {
@@ -211,6 +225,20 @@ fn ast_main_inc_y() -> ast::Instruction {
                 },
                 macro_params,
             ),
+            span: Span {
+                start: Position {
+                    utf8_index: 346,
+                    utf16_index: 346,
+                    line: 16,
+                    column: 19,
+                },
+                end: Position {
+                    utf8_index: 366,
+                    utf16_index: 366,
+                    line: 16,
+                    column: 39,
+                },
+            },
             next_label: ast::Symbol {
                 content: String::from("0"),
                 span: Span {
@@ -240,7 +268,7 @@ fn ast_main() -> ast::Main {
     main_code.insert(instr_dec_x.label.content.clone(), instr_dec_x);
     main_code.insert(instr_inc_y.label.content.clone(), instr_inc_y);
 
-    ast::Main { code: main_code }
+    ast::Main { code: main_code, docs: Vec::new(), tests: Vec::new() }
 }
 
 /// 1: if zero A then goto false else goto true
@@ -284,6 +312,20 @@ fn ast_not_zero_1() -> ast::Instruction {
                     },
                 },
             ),
+            span: Span {
+                start: Position {
+                    utf8_index: 33,
+                    utf16_index: 33,
+                    line: 2,
+                    column: 15,
+                },
+                end: Position {
+                    utf8_index: 39,
+                    utf16_index: 39,
+                    line: 2,
+                    column: 21,
+                },
+            },
             next_true_label: ast::Symbol {
                 content: String::from("false"),
                 span: Span {
@@ -367,6 +409,8 @@ fn ast_not_zero() -> ast::Macro {
             },
         }],
         instr: code,
+        docs: Vec::new(),
+        tests: Vec::new(),
     }
 }
 
@@ -432,6 +476,20 @@ fn ast_inc_if_nz_1() -> ast::Instruction {
                 },
                 ast_inc_if_nz_1_params(),
             ),
+            span: Span {
+                start: Position {
+                    utf8_index: 132,
+                    utf16_index: 132,
+                    line: 6,
+                    column: 15,
+                },
+                end: Position {
+                    utf8_index: 143,
+                    utf16_index: 143,
+                    line: 6,
+                    column: 26,
+                },
+            },
             next_true_label: ast::Symbol {
                 content: String::from("2"),
                 span: Span {
@@ -511,6 +569,20 @@ fn ast_inc_if_nz_2() -> ast::Instruction {
                     },
                 },
             ),
+            span: Span {
+                start: Position {
+                    utf8_index: 182,
+                    utf16_index: 182,
+                    line: 7,
+                    column: 15,
+                },
+                end: Position {
+                    utf8_index: 189,
+                    utf16_index: 189,
+                    line: 7,
+                    column: 22,
+                },
+            },
             next_label: ast::Symbol {
                 content: String::from("0"),
                 span: Span {
@@ -599,6 +671,8 @@ fn ast_inc_if_nz() -> ast::Macro {
             },
         ],
         instr: code,
+        docs: Vec::new(),
+        tests: Vec::new(),
     }
 }
 
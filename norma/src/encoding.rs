@@ -0,0 +1,499 @@
+//! Codificações de dados estruturados em números naturais, para passar tuplas,
+//! listas e booleanos como entrada de um programa (no registrador X) e para
+//! interpretar a saída (no registrador Y).
+//!
+//! As codificações disponíveis são:
+//!
+//! - `natural`: o próprio número;
+//! - `boolean`: `false` é `0` e `true` é `1` (qualquer valor diferente de zero
+//!   é lido como `true`);
+//! - `cantor:n`: uma tupla de `n` elementos codificada com a função de
+//!   pareamento de Cantor, aninhada à direita: `(a, b, c)` vira
+//!   `π(a, π(b, c))`;
+//! - `primes:n`: uma tupla de `n` elementos codificada como potências de
+//!   primos (numeração de Gödel): `(a, b, c)` vira `2^a * 3^b * 5^c`;
+//! - `list:k`: uma lista de dígitos menores que `k` codificada em base `k`
+//!   bijetiva, com o primeiro elemento como dígito mais significativo. Cada
+//!   número corresponde a exatamente uma lista, e `0` é a lista vazia.
+//!
+//! Dados são escritos como `42`, `true`, `(3, 2)` ou `[1, 4, 2]`.
+//!
+//! Os próprios programas também podem ser codificados em números naturais,
+//! com a numeração de Gödel de [`godel`].
+
+#[cfg(test)]
+mod test;
+
+pub mod godel;
+
+use crate::interpreter::{self, program::Program};
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive, Zero};
+use std::{error::Error, fmt, str::FromStr};
+
+/// Maior número de elementos de uma tupla. Limita o trabalho de codificar e
+/// decodificar, que roda a cada atualização da saída no navegador.
+pub const MAX_ARITY: usize = 64;
+
+/// Maior expoente de uma tupla com potências de primos, pelo mesmo motivo.
+pub const MAX_EXPONENT: u32 = 4096;
+
+/// Um dado estruturado, antes de ser codificado ou depois de ser
+/// decodificado.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Data {
+    /// Um número natural.
+    Number(BigUint),
+    /// Um valor-verdade.
+    Boolean(bool),
+    /// Uma tupla de naturais, de tamanho fixo.
+    Tuple(Vec<BigUint>),
+    /// Uma lista de naturais, de tamanho variável.
+    List(Vec<BigUint>),
+}
+
+impl FromStr for Data {
+    type Err = EncodingError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        match text {
+            "true" => Ok(Data::Boolean(true)),
+            "false" => Ok(Data::Boolean(false)),
+            _ => {
+                if let Some(inner) = strip_delimiters(text, '(', ')') {
+                    parse_elements(inner).map(Data::Tuple)
+                } else if let Some(inner) = strip_delimiters(text, '[', ']') {
+                    parse_elements(inner).map(Data::List)
+                } else {
+                    parse_number(text).map(Data::Number)
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Data {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Data::Number(number) => write!(fmtr, "{}", number),
+            Data::Boolean(boolean) => write!(fmtr, "{}", boolean),
+            Data::Tuple(elements) => {
+                write!(fmtr, "(")?;
+                write_elements(fmtr, elements)?;
+                write!(fmtr, ")")
+            }
+            Data::List(elements) => {
+                write!(fmtr, "[")?;
+                write_elements(fmtr, elements)?;
+                write!(fmtr, "]")
+            }
+        }
+    }
+}
+
+/// Uma codificação de dados em números naturais.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// O próprio número.
+    Natural,
+    /// `0` para falso, `1` para verdadeiro.
+    Boolean,
+    /// Tuplas de `arity` elementos, com a função de pareamento de Cantor.
+    Cantor {
+        /// Número de elementos da tupla.
+        arity: usize,
+    },
+    /// Tuplas de `arity` elementos, como potências de primos.
+    PrimePowers {
+        /// Número de elementos da tupla.
+        arity: usize,
+    },
+    /// Listas de dígitos menores que `base`, em base `base` bijetiva.
+    List {
+        /// Base da numeração, que limita os elementos.
+        base: u32,
+    },
+}
+
+impl Encoding {
+    /// Codifica um dado em um número natural.
+    pub fn encode(&self, data: &Data) -> Result<BigUint, EncodingError> {
+        match (self, data) {
+            (Encoding::Natural, Data::Number(number)) => Ok(number.clone()),
+            (Encoding::Boolean, Data::Boolean(boolean)) => {
+                Ok(BigUint::from(*boolean as u8))
+            }
+            (Encoding::Cantor { arity }, Data::Tuple(elements)) => {
+                check_max_arity(*arity)?;
+                check_arity(*arity, elements)?;
+                Ok(encode_cantor(elements))
+            }
+            (Encoding::PrimePowers { arity }, Data::Tuple(elements)) => {
+                check_max_arity(*arity)?;
+                check_arity(*arity, elements)?;
+                encode_prime_powers(elements)
+            }
+            (Encoding::List { base }, Data::List(elements)) => {
+                encode_list(*base, elements)
+            }
+            _ => Err(EncodingError::Mismatch { encoding: *self }),
+        }
+    }
+
+    /// Decodifica um número natural em um dado. Falha se o número não for
+    /// a codificação de dado algum (possível com potências de primos).
+    pub fn decode(&self, number: &BigUint) -> Result<Data, EncodingError> {
+        match self {
+            Encoding::Natural => Ok(Data::Number(number.clone())),
+            Encoding::Boolean => Ok(Data::Boolean(!number.is_zero())),
+            Encoding::Cantor { arity } => {
+                check_max_arity(*arity)?;
+                Ok(Data::Tuple(decode_cantor(*arity, number)))
+            }
+            Encoding::PrimePowers { arity } => {
+                check_max_arity(*arity)?;
+                decode_prime_powers(*arity, number).map(Data::Tuple)
+            }
+            Encoding::List { base } => {
+                Ok(Data::List(decode_list(*base, number)))
+            }
+        }
+    }
+
+    /// Lê um dado escrito em texto (ver [`Data`]) e o codifica.
+    pub fn encode_str(&self, text: &str) -> Result<BigUint, EncodingError> {
+        self.encode(&text.parse()?)
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = EncodingError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        let (name, parameter) = match text.find(':') {
+            Some(position) => (&text[..position], Some(&text[position + 1..])),
+            None => (text, None),
+        };
+        let parameter = match parameter {
+            Some(parameter) => match parameter.trim().parse::<u32>() {
+                Ok(parameter) if parameter > 0 => Some(parameter),
+                _ => {
+                    return Err(EncodingError::UnknownEncoding(text.to_owned()))
+                }
+            },
+            None => None,
+        };
+
+        match (name, parameter) {
+            ("natural", None) => Ok(Encoding::Natural),
+            ("boolean", None) => Ok(Encoding::Boolean),
+            ("cantor", Some(arity)) => {
+                check_max_arity(arity as usize)?;
+                Ok(Encoding::Cantor { arity: arity as usize })
+            }
+            ("primes", Some(arity)) => {
+                check_max_arity(arity as usize)?;
+                Ok(Encoding::PrimePowers { arity: arity as usize })
+            }
+            ("list", Some(base)) => Ok(Encoding::List { base }),
+            _ => Err(EncodingError::UnknownEncoding(text.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Encoding::Natural => write!(fmtr, "natural"),
+            Encoding::Boolean => write!(fmtr, "boolean"),
+            Encoding::Cantor { arity } => write!(fmtr, "cantor:{}", arity),
+            Encoding::PrimePowers { arity } => write!(fmtr, "primes:{}", arity),
+            Encoding::List { base } => write!(fmtr, "list:{}", base),
+        }
+    }
+}
+
+/// Executa um dado programa uma única vez, codificando a entrada com
+/// `input_encoding` e decodificando a saída (AKA registrador Y) com
+/// `output_encoding`.
+pub fn run_once(
+    program: Program,
+    input: &Data,
+    input_encoding: &Encoding,
+    output_encoding: &Encoding,
+) -> Result<Data, EncodingError> {
+    let input = input_encoding.encode(input)?;
+    output_encoding.decode(&interpreter::run_once(input, program))
+}
+
+/// Função de pareamento de Cantor: `π(a, b) = (a + b)(a + b + 1) / 2 + b`.
+pub fn pair(first: &BigUint, second: &BigUint) -> BigUint {
+    let sum = first + second;
+    let triangle = &sum * (&sum + 1u8) / 2u8;
+    triangle + second
+}
+
+/// Inversa da função de pareamento de Cantor.
+pub fn unpair(number: &BigUint) -> (BigUint, BigUint) {
+    let diagonal = ((number * 8u8 + 1u8).sqrt() - 1u8) / 2u8;
+    let triangle = &diagonal * (&diagonal + 1u8) / 2u8;
+    let second = number - triangle;
+    let first = diagonal - &second;
+    (first, second)
+}
+
+/// Retorna os `count` primeiros números primos.
+pub fn primes(count: usize) -> Vec<u64> {
+    let mut primes = Vec::with_capacity(count);
+    let mut candidate = 2u64;
+    while primes.len() < count {
+        if primes.iter().all(|prime| candidate.rem_euclid(*prime) > 0) {
+            primes.push(candidate);
+        }
+        candidate += 1;
+    }
+    primes
+}
+
+/// Codifica uma tupla com o pareamento de Cantor, aninhado à direita.
+fn encode_cantor(elements: &[BigUint]) -> BigUint {
+    let mut iter = elements.iter().rev();
+    let mut encoded = iter.next().cloned().unwrap_or_else(BigUint::zero);
+    for element in iter {
+        encoded = pair(element, &encoded);
+    }
+    encoded
+}
+
+/// Decodifica uma tupla de `arity` elementos codificada com
+/// [`encode_cantor`].
+fn decode_cantor(arity: usize, number: &BigUint) -> Vec<BigUint> {
+    let mut elements = Vec::with_capacity(arity);
+    let mut rest = number.clone();
+    for _ in 1..arity {
+        let (first, second) = unpair(&rest);
+        elements.push(first);
+        rest = second;
+    }
+    if arity > 0 {
+        elements.push(rest);
+    }
+    elements
+}
+
+/// Codifica uma tupla como produto de potências dos primeiros primos.
+fn encode_prime_powers(elements: &[BigUint]) -> Result<BigUint, EncodingError> {
+    let mut encoded = BigUint::one();
+    for (element, prime) in elements.iter().zip(primes(elements.len())) {
+        let exponent = element
+            .to_u32()
+            .filter(|exponent| *exponent <= MAX_EXPONENT)
+            .ok_or_else(|| EncodingError::TooLarge(element.clone()))?;
+        encoded *= BigUint::from(prime).pow(exponent);
+    }
+    Ok(encoded)
+}
+
+/// Decodifica uma tupla de `arity` elementos codificada com
+/// [`encode_prime_powers`]. Falha se o número tiver outros fatores primos,
+/// ou se um expoente passar de [`MAX_EXPONENT`], sem terminar de dividir.
+fn decode_prime_powers(
+    arity: usize,
+    number: &BigUint,
+) -> Result<Vec<BigUint>, EncodingError> {
+    if number.is_zero() {
+        return Err(EncodingError::NotEncoded(number.clone()));
+    }
+
+    let mut rest = number.clone();
+    let mut elements = Vec::with_capacity(arity);
+    for prime in primes(arity) {
+        let mut exponent = 0u32;
+        while (&rest % prime).is_zero() {
+            if exponent == MAX_EXPONENT {
+                return Err(EncodingError::TooLarge(BigUint::from(
+                    MAX_EXPONENT + 1,
+                )));
+            }
+            rest /= prime;
+            exponent += 1;
+        }
+        elements.push(BigUint::from(exponent));
+    }
+
+    if rest.is_one() {
+        Ok(elements)
+    } else {
+        Err(EncodingError::NotEncoded(number.clone()))
+    }
+}
+
+/// Codifica uma lista em base `base` bijetiva: o elemento `d` vira o dígito
+/// `d + 1`, de `1` a `base`.
+fn encode_list(
+    base: u32,
+    elements: &[BigUint],
+) -> Result<BigUint, EncodingError> {
+    let mut encoded = BigUint::zero();
+    for element in elements {
+        if *element >= BigUint::from(base) {
+            return Err(EncodingError::DigitOutOfRange {
+                digit: element.clone(),
+                base,
+            });
+        }
+        encoded = encoded * base + element + 1u8;
+    }
+    Ok(encoded)
+}
+
+/// Decodifica uma lista codificada com [`encode_list`].
+fn decode_list(base: u32, number: &BigUint) -> Vec<BigUint> {
+    let mut elements = Vec::new();
+    let mut rest = number.clone();
+    while !rest.is_zero() {
+        let mut digit = &rest % base;
+        if digit.is_zero() {
+            digit = BigUint::from(base);
+        }
+        rest = (rest - &digit) / base;
+        elements.push(digit - 1u8);
+    }
+    elements.reverse();
+    elements
+}
+
+/// Checa se o número de elementos de uma tupla não passa de [`MAX_ARITY`].
+fn check_max_arity(arity: usize) -> Result<(), EncodingError> {
+    if arity <= MAX_ARITY {
+        Ok(())
+    } else {
+        Err(EncodingError::TooLarge(BigUint::from(arity)))
+    }
+}
+
+/// Checa se a tupla tem o número esperado de elementos.
+fn check_arity(
+    arity: usize,
+    elements: &[BigUint],
+) -> Result<(), EncodingError> {
+    if elements.len() == arity {
+        Ok(())
+    } else {
+        Err(EncodingError::Arity { expected: arity, found: elements.len() })
+    }
+}
+
+/// Remove os delimitadores `open` e `close` das pontas do texto, se houver.
+fn strip_delimiters(text: &str, open: char, close: char) -> Option<&str> {
+    if text.starts_with(open) && text.ends_with(close) && text.len() >= 2 {
+        Some(&text[open.len_utf8()..text.len() - close.len_utf8()])
+    } else {
+        None
+    }
+}
+
+/// Lê uma sequência de naturais separados por vírgula.
+fn parse_elements(text: &str) -> Result<Vec<BigUint>, EncodingError> {
+    if text.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    text.split(',').map(|element| parse_number(element.trim())).collect()
+}
+
+/// Lê um número natural em decimal.
+fn parse_number(text: &str) -> Result<BigUint, EncodingError> {
+    if !text.is_empty()
+        && text.chars().all(|character| character.is_ascii_digit())
+    {
+        text.parse().map_err(|_| EncodingError::InvalidData(text.to_owned()))
+    } else {
+        Err(EncodingError::InvalidData(text.to_owned()))
+    }
+}
+
+/// Escreve elementos separados por vírgula.
+fn write_elements(
+    fmtr: &mut fmt::Formatter,
+    elements: &[BigUint],
+) -> fmt::Result {
+    for (index, element) in elements.iter().enumerate() {
+        if index > 0 {
+            write!(fmtr, ", ")?;
+        }
+        write!(fmtr, "{}", element)?;
+    }
+    Ok(())
+}
+
+/// Erro ao ler, codificar ou decodificar um dado.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodingError {
+    /// O texto não descreve um dado.
+    InvalidData(String),
+    /// O texto não descreve uma codificação.
+    UnknownEncoding(String),
+    /// O tipo do dado não serve para a codificação (e.g. uma lista para
+    /// `cantor:2`).
+    Mismatch {
+        /// A codificação pedida.
+        encoding: Encoding,
+    },
+    /// A tupla tem um número de elementos diferente do esperado.
+    Arity {
+        /// Número de elementos esperado.
+        expected: usize,
+        /// Número de elementos encontrado.
+        found: usize,
+    },
+    /// Um elemento da lista não é um dígito da base.
+    DigitOutOfRange {
+        /// O elemento.
+        digit: BigUint,
+        /// A base da codificação.
+        base: u32,
+    },
+    /// Um expoente ou número de elementos grande demais (ver [`MAX_ARITY`] e
+    /// [`MAX_EXPONENT`]).
+    TooLarge(BigUint),
+    /// O número não é a codificação de dado algum.
+    NotEncoded(BigUint),
+}
+
+impl fmt::Display for EncodingError {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EncodingError::InvalidData(text) => {
+                write!(fmtr, "Dado inválido: \"{}\"", text)
+            }
+            EncodingError::UnknownEncoding(text) => {
+                write!(fmtr, "Codificação desconhecida: \"{}\"", text)
+            }
+            EncodingError::Mismatch { encoding } => write!(
+                fmtr,
+                "Tipo de dado não pode ser codificado como {}",
+                encoding
+            ),
+            EncodingError::Arity { expected, found } => write!(
+                fmtr,
+                "Tupla deveria ter {} elementos, mas tem {}",
+                expected, found
+            ),
+            EncodingError::DigitOutOfRange { digit, base } => write!(
+                fmtr,
+                "Elemento {} não é um dígito na base {}",
+                digit, base
+            ),
+            EncodingError::TooLarge(number) => {
+                write!(fmtr, "Número {} é grande demais para codificar", number)
+            }
+            EncodingError::NotEncoded(number) => {
+                write!(fmtr, "Número {} não é uma codificação válida", number)
+            }
+        }
+    }
+}
+
+impl Error for EncodingError {}
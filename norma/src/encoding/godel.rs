@@ -0,0 +1,327 @@
+//! Numeração de Gödel de programas já expandidos: uma bijeção entre os
+//! números naturais e os programas com nomes canônicos.
+//!
+//! Um programa com nomes canônicos tem `n` instruções com rótulos `1`, ...,
+//! `n`, nessa ordem, desvia só para esses rótulos ou para `0` (a saída) e usa
+//! registradores `X`, `Y`, `R1`, `R2`, ... A forma canônica
+//! ([`canonicalize`](crate::interpreter::program::canonical::canonicalize))
+//! sempre tem nomes canônicos.
+//!
+//! A codificação é:
+//!
+//! - `0` é o programa vazio;
+//! - `2^(n - 1) * (2c + 1)` é o programa de `n` instruções cujos códigos
+//!   formam a tupla `c`, codificada com o pareamento de Cantor aninhado à
+//!   direita (ver [`pair`](super::pair)). Assim, o número de instruções é no
+//!   máximo o número de dígitos binários do código do programa.
+//!
+//! O código de uma instrução é `k + 13 * r`, onde `k` é o tipo da instrução
+//! (de `0` a `7` para as operações, na ordem de
+//! [`OperationKind`](crate::interpreter::program::OperationKind), e de `8` a
+//! `12` para os testes, na ordem de
+//! [`TestKind`](crate::interpreter::program::TestKind)). Em `r`, os rótulos
+//! de destino são dígitos na base `n + 1`, dos menos significativos para os
+//! mais: `r = d1 + (n + 1) * (d2 + (n + 1) * p)`, com `d2` só nos testes, e
+//! `p` é a tupla dos parâmetros com o pareamento de Cantor. Um registrador é
+//! o número `0` para `X`, `1` para `Y` e `i + 1` para `Ri`; uma constante é o
+//! próprio número.
+//!
+//! Como cada passo é uma bijeção, todo número é o código de exatamente um
+//! programa, e [`programs`] enumera todos os programas na ordem dos códigos.
+
+#[cfg(test)]
+mod test;
+
+use super::{decode_cantor, encode_cantor};
+use crate::interpreter::program::{
+    Instruction, InstructionKind, Operation, OperationKind, Program, Test,
+    TestKind,
+};
+use num_bigint::BigUint;
+use num_traits::{ToPrimitive, Zero};
+use std::{error::Error, fmt};
+
+/// Número de parâmetros de cada tipo de instrução, na ordem dos códigos.
+pub(crate) const ARITIES: [usize; 13] = [1, 1, 1, 2, 2, 3, 2, 3, 1, 2, 3, 2, 3];
+
+/// Número de tipos de operação; os tipos seguintes são testes.
+pub(crate) const OPERATIONS: usize = 8;
+
+/// Codifica um programa com nomes canônicos.
+///
+/// # Panics
+///
+/// Invoca `panic!()` se o programa não tiver nomes canônicos.
+pub fn encode(program: &Program) -> BigUint {
+    match try_encode(program) {
+        Ok(number) => number,
+        Err(error) => panic!("{}", error),
+    }
+}
+
+/// Codifica um programa, como [`encode`], falhando se o programa não tiver
+/// nomes canônicos.
+pub fn try_encode(program: &Program) -> Result<BigUint, NamingError> {
+    let size = program.len();
+    if size == 0 {
+        return Ok(BigUint::zero());
+    }
+
+    let mut codes = Vec::with_capacity(size);
+    for (index, instruction) in program.instructions().enumerate() {
+        let expected = (index + 1).to_string();
+        if instruction.label() != expected {
+            let found = instruction.label().to_owned();
+            return Err(NamingError::Label { expected, found });
+        }
+        codes.push(encode_instruction(&instruction.kind, size)?);
+    }
+
+    let odd = encode_cantor(&codes) * 2u8 + 1u8;
+    Ok(odd << (size - 1))
+}
+
+/// Decodifica um número no programa com nomes canônicos correspondente.
+pub fn decode(number: &BigUint) -> Program {
+    let mut program = Program::empty();
+    let exponent = match number.trailing_zeros() {
+        Some(exponent) => exponent,
+        None => return program,
+    };
+
+    let codes = (number >> exponent) / 2u8;
+    let size = exponent as usize + 1;
+    for (index, code) in decode_cantor(size, &codes).iter().enumerate() {
+        let label = (index + 1).to_string();
+        program.insert(Instruction::new(label, decode_instruction(code, size)));
+    }
+    program
+}
+
+/// Enumera todos os programas com nomes canônicos, na ordem dos códigos: o
+/// `i`-ésimo programa é `decode(i)`.
+pub fn programs() -> Programs {
+    Programs { next: BigUint::zero() }
+}
+
+/// Iterador infinito sobre os programas com nomes canônicos, na ordem dos
+/// códigos. Criado por [`programs`].
+#[derive(Debug, Clone)]
+pub struct Programs {
+    next: BigUint,
+}
+
+impl Iterator for Programs {
+    type Item = Program;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let program = decode(&self.next);
+        self.next += 1u8;
+        Some(program)
+    }
+}
+
+/// Nome do registrador com o dado número: `X`, `Y`, `R1`, `R2`, ...
+pub fn register_name(number: &BigUint) -> String {
+    match number.to_u8() {
+        Some(0) => String::from("X"),
+        Some(1) => String::from("Y"),
+        _ => format!("R{}", number - 1u8),
+    }
+}
+
+/// Número do registrador com o dado nome, se o nome for canônico.
+pub fn register_number(name: &str) -> Option<BigUint> {
+    match name {
+        "X" => Some(BigUint::zero()),
+        "Y" => Some(BigUint::from(1u8)),
+        _ => {
+            let index = canonical_number(name.strip_prefix('R')?)?;
+            if index.is_zero() {
+                None
+            } else {
+                Some(index + 1u8)
+            }
+        }
+    }
+}
+
+/// Um parâmetro de uma instrução.
+enum Parameter<'prog> {
+    /// Um registrador.
+    Register(&'prog str),
+    /// Uma constante.
+    Constant(&'prog BigUint),
+}
+
+/// Codifica uma instrução de um programa com `size` instruções.
+fn encode_instruction(
+    kind: &InstructionKind,
+    size: usize,
+) -> Result<BigUint, NamingError> {
+    let (tag, parameters, targets) = split(kind);
+
+    let mut numbers = Vec::with_capacity(parameters.len());
+    for parameter in parameters {
+        numbers.push(match parameter {
+            Parameter::Register(name) => register_number(name)
+                .ok_or_else(|| NamingError::Register(name.to_owned()))?,
+            Parameter::Constant(constant) => constant.clone(),
+        });
+    }
+
+    let mut rest = encode_cantor(&numbers);
+    for target in targets.iter().rev() {
+        let digit = canonical_number(target)
+            .filter(|digit| *digit <= BigUint::from(size))
+            .ok_or_else(|| NamingError::Target(target.to_string()))?;
+        rest = rest * (size + 1) + digit;
+    }
+    Ok(rest * ARITIES.len() + tag)
+}
+
+/// Decodifica uma instrução de um programa com `size` instruções.
+pub(crate) fn decode_instruction(
+    code: &BigUint,
+    size: usize,
+) -> InstructionKind {
+    let tag = (code % ARITIES.len()).to_usize().unwrap_or(0);
+    let mut rest = code / ARITIES.len();
+
+    let target_count = if tag < OPERATIONS { 1 } else { 2 };
+    let mut targets = Vec::with_capacity(target_count);
+    for _ in 0..target_count {
+        targets.push((&rest % (size + 1)).to_string());
+        rest /= size + 1;
+    }
+
+    let parameters = decode_cantor(ARITIES[tag], &rest);
+    let register = |index: usize| register_name(&parameters[index]);
+    let constant = || parameters[1].clone();
+
+    if tag < OPERATIONS {
+        let kind = match tag {
+            0 => OperationKind::Inc(register(0)),
+            1 => OperationKind::Dec(register(0)),
+            2 => OperationKind::Clear(register(0)),
+            3 => OperationKind::Load(register(0), constant()),
+            4 => OperationKind::AddConst(register(0), constant()),
+            5 => OperationKind::Add(register(0), register(1), register(2)),
+            6 => OperationKind::SubConst(register(0), constant()),
+            _ => OperationKind::Sub(register(0), register(1), register(2)),
+        };
+        let next = targets.remove(0);
+        InstructionKind::Operation(Operation { kind, next })
+    } else {
+        let kind = match tag {
+            8 => TestKind::Zero(register(0)),
+            9 => TestKind::EqualsConst(register(0), constant()),
+            10 => TestKind::Equals(register(0), register(1), register(2)),
+            11 => TestKind::LessThanConst(register(0), constant()),
+            _ => TestKind::LessThan(register(0), register(1), register(2)),
+        };
+        let next_else = targets.remove(1);
+        let next_then = targets.remove(0);
+        InstructionKind::Test(Test { kind, next_then, next_else })
+    }
+}
+
+/// Separa uma instrução no seu tipo, nos seus parâmetros e nos seus rótulos
+/// de destino.
+fn split(kind: &InstructionKind) -> (usize, Vec<Parameter<'_>>, Vec<&str>) {
+    use Parameter::{Constant, Register};
+
+    match kind {
+        InstructionKind::Operation(oper) => {
+            let (tag, parameters) = match &oper.kind {
+                OperationKind::Inc(register) => (0, vec![Register(register)]),
+                OperationKind::Dec(register) => (1, vec![Register(register)]),
+                OperationKind::Clear(register) => (2, vec![Register(register)]),
+                OperationKind::Load(register, constant) => {
+                    (3, vec![Register(register), Constant(constant)])
+                }
+                OperationKind::AddConst(register, constant) => {
+                    (4, vec![Register(register), Constant(constant)])
+                }
+                OperationKind::Add(left, right, temp) => {
+                    (5, vec![Register(left), Register(right), Register(temp)])
+                }
+                OperationKind::SubConst(register, constant) => {
+                    (6, vec![Register(register), Constant(constant)])
+                }
+                OperationKind::Sub(left, right, temp) => {
+                    (7, vec![Register(left), Register(right), Register(temp)])
+                }
+            };
+            (tag, parameters, vec![&oper.next])
+        }
+        InstructionKind::Test(test) => {
+            let (tag, parameters) = match &test.kind {
+                TestKind::Zero(register) => (8, vec![Register(register)]),
+                TestKind::EqualsConst(register, constant) => {
+                    (9, vec![Register(register), Constant(constant)])
+                }
+                TestKind::Equals(left, right, temp) => {
+                    (10, vec![Register(left), Register(right), Register(temp)])
+                }
+                TestKind::LessThanConst(register, constant) => {
+                    (11, vec![Register(register), Constant(constant)])
+                }
+                TestKind::LessThan(left, right, temp) => {
+                    (12, vec![Register(left), Register(right), Register(temp)])
+                }
+            };
+            (tag, parameters, vec![&test.next_then, &test.next_else])
+        }
+    }
+}
+
+/// Lê um natural escrito em decimal sem zeros à esquerda.
+fn canonical_number(text: &str) -> Option<BigUint> {
+    let number: BigUint = text.parse().ok()?;
+    if number.to_string() == text {
+        Some(number)
+    } else {
+        None
+    }
+}
+
+/// Erro de um programa que não tem nomes canônicos.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NamingError {
+    /// A instrução na posição de `expected` tem outro rótulo.
+    Label {
+        /// O rótulo esperado para a posição.
+        expected: String,
+        /// O rótulo encontrado.
+        found: String,
+    },
+    /// Um desvio para um rótulo que não é `0` nem de uma instrução.
+    Target(String),
+    /// Um registrador fora de `X`, `Y`, `R1`, `R2`, ...
+    Register(String),
+}
+
+impl fmt::Display for NamingError {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NamingError::Label { expected, found } => write!(
+                fmtr,
+                "Rótulo \"{}\" não é canônico, esperava-se \"{}\"",
+                found, expected
+            ),
+            NamingError::Target(label) => write!(
+                fmtr,
+                "Desvio para \"{}\" não é canônico, use \"0\" para sair",
+                label
+            ),
+            NamingError::Register(register) => write!(
+                fmtr,
+                "Registrador \"{}\" não é canônico, use X, Y, R1, R2, ...",
+                register
+            ),
+        }
+    }
+}
+
+impl Error for NamingError {}
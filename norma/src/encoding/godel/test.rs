@@ -0,0 +1,156 @@
+use super::{
+    decode, encode, programs, register_name, register_number, try_encode,
+    NamingError,
+};
+use crate::{
+    compiler::compile,
+    interpreter::{
+        program::{
+            canonical::canonicalize,
+            fixture::{operation, reg, test},
+            OperationKind, Program, TestKind,
+        },
+        Interpreter,
+    },
+};
+use num_bigint::BigUint;
+use std::collections::HashSet;
+
+fn program(source: &str) -> Program {
+    canonicalize(&compile(source).unwrap())
+}
+
+// Usa instruções que não são escritas diretamente no código-fonte.
+fn extended() -> Program {
+    let mut program = Program::empty();
+    let tmp = || reg("R1");
+    program.insert(test(
+        "1",
+        TestKind::LessThanConst(reg("X"), 3u8.into()),
+        "2",
+        "3",
+    ));
+    program.insert(operation(
+        "2",
+        OperationKind::Load(reg("Y"), 7u8.into()),
+        "0",
+    ));
+    program.insert(operation(
+        "3",
+        OperationKind::SubConst(reg("X"), 2u8.into()),
+        "4",
+    ));
+    program.insert(test(
+        "4",
+        TestKind::Equals(reg("X"), reg("Y"), tmp()),
+        "0",
+        "5",
+    ));
+    program.insert(operation(
+        "5",
+        OperationKind::Add(reg("Y"), reg("X"), tmp()),
+        "1",
+    ));
+    program
+}
+
+fn single(code: u32) -> Program {
+    decode(&BigUint::from(2 * code + 1))
+}
+
+#[test]
+fn first_programs() {
+    assert!(decode(&BigUint::from(0u8)).is_empty());
+    assert_eq!(decode(&BigUint::from(1u8)).to_string(), "1: do inc X goto 0\n");
+    assert_eq!(
+        decode(&BigUint::from(2u8)).to_string(),
+        "1: do inc X goto 0\n2: do inc X goto 0\n"
+    );
+    assert_eq!(single(1).to_string(), "1: do dec X goto 0\n");
+    assert_eq!(single(13).to_string(), "1: do inc X goto 1\n");
+    assert_eq!(single(8).to_string(), "1: if zero X then goto 0 else goto 0\n");
+    assert_eq!(single(26).to_string(), "1: do inc Y goto 0\n");
+}
+
+#[test]
+fn size_is_logarithmic() {
+    let program = decode(&(BigUint::from(1u8) << 200));
+    assert_eq!(program.len(), 201);
+    assert_eq!(encode(&program), BigUint::from(1u8) << 200);
+
+    let program = decode(&BigUint::from(u64::MAX));
+    assert_eq!(program.len(), 1);
+    assert_eq!(encode(&program), BigUint::from(u64::MAX));
+}
+
+#[test]
+fn bijection() {
+    let mut seen = HashSet::new();
+    for (number, program) in programs().take(3000).enumerate() {
+        assert_eq!(encode(&program), BigUint::from(number));
+        assert!(seen.insert(program.to_string()), "{}", program);
+    }
+}
+
+#[test]
+fn round_trip() {
+    let sources = ["main {
+            1: if zero X then goto 0 else goto 2
+            2: do dec X goto 3
+            3: do inc Y goto 1
+        }"];
+    for source in &sources {
+        let program = program(source);
+        assert_eq!(decode(&encode(&program)), program);
+    }
+    assert_eq!(decode(&encode(&extended())), extended());
+}
+
+#[test]
+fn decoded_program_runs() {
+    let mut interpreter = Interpreter::new(decode(&encode(&extended())));
+    interpreter.input(BigUint::from(5u8));
+    interpreter.run_all();
+    assert_eq!(interpreter.output(), BigUint::from(7u8));
+}
+
+#[test]
+fn registers() {
+    for number in 0..20u8 {
+        let number = BigUint::from(number);
+        assert_eq!(register_number(&register_name(&number)), Some(number));
+    }
+    assert_eq!(register_name(&BigUint::from(3u8)), "R2");
+    for name in &["R0", "R01", "A", "Rx", "x"] {
+        assert_eq!(register_number(name), None, "{}", name);
+    }
+}
+
+#[test]
+fn reject_non_canonical_names() {
+    let error = try_encode(
+        &compile("main { 1: do inc X goto 2\n 2: do inc A goto 0 }").unwrap(),
+    )
+    .unwrap_err();
+    assert_eq!(error, NamingError::Register(String::from("A")));
+    assert_eq!(
+        error.to_string(),
+        "Registrador \"A\" não é canônico, use X, Y, R1, R2, ..."
+    );
+
+    let error = try_encode(&compile("main { 1: do inc X goto 3 }").unwrap())
+        .unwrap_err();
+    assert_eq!(error, NamingError::Target(String::from("3")));
+
+    let error = try_encode(
+        &compile("main { 2: do inc X goto 1\n 1: do inc Y goto 0 }").unwrap(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        error,
+        NamingError::Label {
+            expected: String::from("1"),
+            found: String::from("2"),
+        }
+    );
+}
@@ -0,0 +1,153 @@
+use super::{pair, primes, unpair, Data, Encoding, EncodingError};
+use num_bigint::BigUint;
+
+fn numbers(values: &[u32]) -> Vec<BigUint> {
+    values.iter().map(|&value| BigUint::from(value)).collect()
+}
+
+#[test]
+fn parse_and_display_data() {
+    let cases = [
+        ("42", Data::Number(BigUint::from(42u8))),
+        (" true ", Data::Boolean(true)),
+        ("(3, 2)", Data::Tuple(numbers(&[3, 2]))),
+        ("[1,4,2]", Data::List(numbers(&[1, 4, 2]))),
+        ("[]", Data::List(Vec::new())),
+    ];
+    for (text, data) in &cases {
+        assert_eq!(text.parse::<Data>().unwrap(), *data);
+        assert_eq!(data.to_string().parse::<Data>().unwrap(), *data);
+    }
+    assert_eq!(Data::Tuple(numbers(&[3, 2])).to_string(), "(3, 2)");
+
+    for text in &["", "-1", "(1, x)", "[1, 2", "maybe"] {
+        assert!(text.parse::<Data>().is_err(), "{:?}", text);
+    }
+}
+
+#[test]
+fn cantor_pairing() {
+    let mut expected = 0u32;
+    for diagonal in 0..20u32 {
+        for second in 0..=diagonal {
+            let first = BigUint::from(diagonal - second);
+            let second = BigUint::from(second);
+            assert_eq!(pair(&first, &second), BigUint::from(expected));
+            assert_eq!(unpair(&BigUint::from(expected)), (first, second));
+            expected += 1;
+        }
+    }
+
+    let encoding = Encoding::Cantor { arity: 3 };
+    let data = Data::Tuple(numbers(&[3, 0, 7]));
+    let encoded = encoding.encode(&data).unwrap();
+    assert_eq!(encoding.decode(&encoded).unwrap(), data);
+}
+
+#[test]
+fn prime_powers() {
+    assert_eq!(primes(6), &[2, 3, 5, 7, 11, 13]);
+
+    let encoding = Encoding::PrimePowers { arity: 2 };
+    assert_eq!(encoding.encode_str("(3, 2)").unwrap(), BigUint::from(72u8));
+    assert_eq!(
+        encoding.decode(&BigUint::from(72u8)).unwrap(),
+        Data::Tuple(numbers(&[3, 2]))
+    );
+    assert_eq!(
+        encoding.decode(&BigUint::from(10u8)),
+        Err(EncodingError::NotEncoded(BigUint::from(10u8)))
+    );
+    assert_eq!(
+        encoding.encode_str("(1, 2, 3)"),
+        Err(EncodingError::Arity { expected: 2, found: 3 })
+    );
+}
+
+#[test]
+fn bijective_lists() {
+    let encoding = Encoding::List { base: 10 };
+    assert_eq!(encoding.encode_str("[1,4,2]").unwrap(), BigUint::from(253u16));
+    assert_eq!(encoding.encode_str("[]").unwrap(), BigUint::from(0u8));
+    assert_eq!(
+        encoding.encode_str("[10]"),
+        Err(EncodingError::DigitOutOfRange {
+            digit: BigUint::from(10u8),
+            base: 10
+        })
+    );
+
+    let encoding = Encoding::List { base: 2 };
+    for number in 0..64u32 {
+        let number = BigUint::from(number);
+        let data = encoding.decode(&number).unwrap();
+        assert_eq!(encoding.encode(&data).unwrap(), number);
+    }
+}
+
+#[test]
+fn booleans_and_mismatches() {
+    let encoding = Encoding::Boolean;
+    assert_eq!(encoding.encode_str("true").unwrap(), BigUint::from(1u8));
+    assert_eq!(
+        encoding.decode(&BigUint::from(0u8)).unwrap(),
+        Data::Boolean(false)
+    );
+    assert_eq!(
+        encoding.decode(&BigUint::from(5u8)).unwrap(),
+        Data::Boolean(true)
+    );
+
+    let error = encoding.encode_str("[1]").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Tipo de dado não pode ser codificado como boolean"
+    );
+}
+
+#[test]
+fn parse_encodings() {
+    let encodings = [
+        Encoding::Natural,
+        Encoding::Boolean,
+        Encoding::Cantor { arity: 2 },
+        Encoding::PrimePowers { arity: 3 },
+        Encoding::List { base: 10 },
+    ];
+    for encoding in &encodings {
+        assert_eq!(
+            encoding.to_string().parse::<Encoding>().unwrap(),
+            *encoding
+        );
+    }
+    for text in &["cantor", "list:0", "primes:x", "natural:2", "unary"] {
+        assert!(text.parse::<Encoding>().is_err(), "{:?}", text);
+    }
+}
+
+#[test]
+fn limit_sizes() {
+    assert_eq!(
+        "primes:100000000".parse::<Encoding>(),
+        Err(EncodingError::TooLarge(BigUint::from(100000000u32)))
+    );
+    assert_eq!(
+        Encoding::Cantor { arity: 65 }.decode(&BigUint::from(7u8)),
+        Err(EncodingError::TooLarge(BigUint::from(65u8)))
+    );
+    assert!("primes:64".parse::<Encoding>().is_ok());
+
+    let primes = Encoding::PrimePowers { arity: 2 };
+    let error = primes.encode_str("(1, 4097)").unwrap_err();
+    assert_eq!(error, EncodingError::TooLarge(BigUint::from(4097u16)));
+    assert_eq!(error.to_string(), "Número 4097 é grande demais para codificar");
+    assert!(primes.encode_str("(1, 4096)").is_ok());
+
+    let huge = BigUint::from(1u8) << 100_000;
+    assert_eq!(
+        primes.decode(&huge),
+        Err(EncodingError::TooLarge(BigUint::from(4097u16)))
+    );
+    let largest = BigUint::from(3u8) << 4096;
+    assert!(primes.decode(&largest).is_ok());
+}
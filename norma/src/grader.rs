@@ -0,0 +1,448 @@
+//! Correção automática de exercícios: compila cada submissão, confere
+//! restrições do enunciado (construções proibidas, número de registradores),
+//! roda a submissão para as entradas do exercício e compara com a referência,
+//! gerando um relatório por aluno em texto ou JSON.
+
+#[cfg(test)]
+mod test;
+
+use crate::{
+    batch::{self, Outcome, Table},
+    compiler::{
+        expansor::expand,
+        lexer::generate_tokens,
+        parser::{ast, parse},
+    },
+    interpreter::program::{decimal, Program},
+};
+use indexmap::IndexSet;
+use num_bigint::BigUint;
+use num_traits::{ToPrimitive, Zero};
+use pinguim_language::error::Diagnostics;
+use serde::Serialize;
+use std::{fmt, sync::Arc};
+
+/// Máximo de entradas com saída errada listadas no relatório em texto.
+const LISTED_FAILURES: usize = 10;
+
+/// Solução de referência de um exercício.
+#[derive(Clone)]
+pub enum Reference {
+    /// Função Rust que computa a saída esperada para cada entrada.
+    Function(Arc<dyn Fn(&BigUint) -> BigUint + Send + Sync>),
+    /// Programa Norma de referência. Permite comparar a eficiência da
+    /// submissão em passos.
+    Program(Arc<Program>),
+}
+
+impl fmt::Debug for Reference {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Reference::Function(_) => write!(fmtr, "Function(..)"),
+            Reference::Program(program) => {
+                fmtr.debug_tuple("Program").field(program).finish()
+            }
+        }
+    }
+}
+
+/// Enunciado de um exercício.
+#[derive(Debug, Clone)]
+pub struct Exercise {
+    /// Solução de referência.
+    pub reference: Reference,
+    /// Entradas (AKA registrador X) para as quais a submissão é testada.
+    pub inputs: Vec<BigUint>,
+    /// Máximo de passos de cada execução, tanto da submissão quanto do
+    /// programa de referência.
+    pub fuel: u64,
+    /// Nomes de operações e testes, macros ou builtins, que a submissão não
+    /// pode usar, e.g. `add` ou `load`.
+    pub forbidden: Vec<String>,
+    /// Máximo de registradores que o programa compilado pode usar.
+    pub max_registers: Option<usize>,
+}
+
+impl Exercise {
+    /// Cria um exercício cuja referência é uma função Rust, com entradas de
+    /// `0` a `100` e sem restrições.
+    pub fn with_function<F>(function: F) -> Self
+    where
+        F: Fn(&BigUint) -> BigUint + Send + Sync + 'static,
+    {
+        Self::new(Reference::Function(Arc::new(function)))
+    }
+
+    /// Cria um exercício cuja referência é um programa Norma, com entradas de
+    /// `0` a `100` e sem restrições.
+    pub fn with_program<P>(program: P) -> Self
+    where
+        P: Into<Arc<Program>>,
+    {
+        Self::new(Reference::Program(program.into()))
+    }
+
+    fn new(reference: Reference) -> Self {
+        Self {
+            reference,
+            inputs: (0..=100u8).map(BigUint::from).collect(),
+            fuel: 1_000_000,
+            forbidden: Vec::new(),
+            max_registers: None,
+        }
+    }
+
+    /// Roda a referência para as entradas do exercício. Entradas para as
+    /// quais o programa de referência não termina ficam sem saída esperada.
+    fn expected(
+        &self,
+        config: &batch::Config,
+    ) -> (Vec<Option<BigUint>>, Table) {
+        match &self.reference {
+            Reference::Function(function) => {
+                let expected =
+                    self.inputs.iter().map(|input| Some(function(input)));
+                (expected.collect(), Table::default())
+            }
+            Reference::Program(program) => {
+                let table =
+                    batch::run(program.clone(), self.inputs.clone(), config);
+                let expected = table
+                    .rows
+                    .iter()
+                    .map(|row| row.outcome.output().cloned())
+                    .collect();
+                (expected, table)
+            }
+        }
+    }
+}
+
+/// Violação de uma restrição do enunciado.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Violation {
+    /// Uso de uma operação ou teste proibido.
+    Forbidden {
+        /// Nome da operação ou teste.
+        name: String,
+        /// Localização do uso no código-fonte.
+        location: String,
+    },
+    /// O programa compilado usa registradores demais.
+    TooManyRegisters {
+        /// Máximo permitido.
+        max: usize,
+        /// Quantidade usada.
+        found: usize,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Violation::Forbidden { name, location } => {
+                write!(fmtr, "uso proibido de {}, {}", name, location)
+            }
+            Violation::TooManyRegisters { max, found } => write!(
+                fmtr,
+                "usa {} registradores, mas o máximo é {}",
+                found, max
+            ),
+        }
+    }
+}
+
+/// Entrada para a qual a submissão terminou com a saída errada.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WrongOutput {
+    /// Entrada (AKA registrador X).
+    #[serde(with = "decimal")]
+    pub input: BigUint,
+    /// Saída esperada.
+    #[serde(with = "decimal")]
+    pub expected: BigUint,
+    /// Saída obtida.
+    #[serde(with = "decimal")]
+    pub found: BigUint,
+}
+
+/// Entrada para a qual a submissão não terminou dentro do limite de passos.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Timeout {
+    /// Entrada (AKA registrador X).
+    #[serde(with = "decimal")]
+    pub input: BigUint,
+    /// Passos dados até o combustível acabar.
+    #[serde(with = "decimal")]
+    pub steps: BigUint,
+}
+
+/// Comparação de eficiência com o programa de referência, somando os passos
+/// das entradas em que ambos terminaram com a saída esperada.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Efficiency {
+    /// Passos da submissão.
+    #[serde(with = "decimal")]
+    pub steps: BigUint,
+    /// Passos da referência.
+    #[serde(with = "decimal")]
+    pub reference_steps: BigUint,
+}
+
+impl Efficiency {
+    /// Razão entre os passos da submissão e os da referência: abaixo de `1`,
+    /// a submissão é mais eficiente.
+    pub fn ratio(&self) -> f64 {
+        let steps = self.steps.to_f64().unwrap_or(f64::INFINITY);
+        let reference_steps =
+            self.reference_steps.to_f64().unwrap_or(f64::INFINITY);
+        steps / reference_steps
+    }
+}
+
+/// Relatório da correção de uma submissão.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Default)]
+pub struct Report {
+    /// Identificação do aluno ou da submissão.
+    pub student: String,
+    /// Erros de compilação. Se houver algum, nada mais é avaliado.
+    pub diagnostics: Vec<String>,
+    /// Restrições do enunciado violadas.
+    pub violations: Vec<Violation>,
+    /// Entradas avaliadas, isto é, para as quais há saída esperada.
+    pub total: usize,
+    /// Entradas para as quais a saída foi a esperada.
+    pub passed: usize,
+    /// Entradas com saída errada.
+    pub failures: Vec<WrongOutput>,
+    /// Entradas para as quais a submissão não terminou.
+    pub timeouts: Vec<Timeout>,
+    /// Comparação com o programa de referência, se a referência for um
+    /// programa.
+    pub efficiency: Option<Efficiency>,
+}
+
+impl Report {
+    /// Retorna se a submissão compilou, respeita as restrições e acertou
+    /// todas as entradas.
+    pub fn accepted(&self) -> bool {
+        self.diagnostics.is_empty()
+            && self.violations.is_empty()
+            && self.passed == self.total
+    }
+
+    /// Exporta o relatório em JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("report is always serializable")
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        let verdict = if self.accepted() { "aceita" } else { "rejeitada" };
+        writeln!(fmtr, "Submissão de {}: {}", self.student, verdict)?;
+
+        if !self.diagnostics.is_empty() {
+            writeln!(fmtr, "Erros de compilação:")?;
+            for diagnostic in &self.diagnostics {
+                writeln!(fmtr, "- {}", diagnostic)?;
+            }
+            return Ok(());
+        }
+
+        for violation in &self.violations {
+            writeln!(fmtr, "Restrição violada: {}", violation)?;
+        }
+
+        writeln!(fmtr, "Acertos: {} de {}", self.passed, self.total)?;
+        for failure in self.failures.iter().take(LISTED_FAILURES) {
+            writeln!(
+                fmtr,
+                "- X = {}: esperado Y = {}, obtido Y = {}",
+                failure.input, failure.expected, failure.found
+            )?;
+        }
+        if self.failures.len() > LISTED_FAILURES {
+            writeln!(
+                fmtr,
+                "- e mais {} entradas erradas",
+                self.failures.len() - LISTED_FAILURES
+            )?;
+        }
+        for timeout in &self.timeouts {
+            writeln!(
+                fmtr,
+                "- X = {}: não terminou em {} passos",
+                timeout.input, timeout.steps
+            )?;
+        }
+
+        if let Some(efficiency) = &self.efficiency {
+            writeln!(
+                fmtr,
+                "Passos: {} (referência: {}, {:.2}x)",
+                efficiency.steps,
+                efficiency.reference_steps,
+                efficiency.ratio()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Corrige a submissão de um aluno.
+///
+/// - `exercise`: enunciado do exercício
+/// - `student`: identificação do aluno ou da submissão
+/// - `source`: código-fonte submetido
+pub fn grade(exercise: &Exercise, student: &str, source: &str) -> Report {
+    let config =
+        batch::Config { fuel: exercise.fuel, ..batch::Config::default() };
+    let (expected, reference_table) = exercise.expected(&config);
+    grade_with(exercise, &expected, &reference_table, &config, student, source)
+}
+
+/// Corrige várias submissões, rodando a referência uma única vez. Recebe
+/// pares `(aluno, código-fonte)` e retorna os relatórios na mesma ordem.
+pub fn grade_all<I, S, T>(exercise: &Exercise, submissions: I) -> Vec<Report>
+where
+    I: IntoIterator<Item = (S, T)>,
+    S: AsRef<str>,
+    T: AsRef<str>,
+{
+    let config =
+        batch::Config { fuel: exercise.fuel, ..batch::Config::default() };
+    let (expected, reference_table) = exercise.expected(&config);
+    submissions
+        .into_iter()
+        .map(|(student, source)| {
+            grade_with(
+                exercise,
+                &expected,
+                &reference_table,
+                &config,
+                student.as_ref(),
+                source.as_ref(),
+            )
+        })
+        .collect()
+}
+
+fn grade_with(
+    exercise: &Exercise,
+    expected: &[Option<BigUint>],
+    reference_table: &Table,
+    config: &batch::Config,
+    student: &str,
+    source: &str,
+) -> Report {
+    let mut report =
+        Report { student: student.to_owned(), ..Report::default() };
+
+    let mut diagnostics = Diagnostics::new();
+    let tokens = generate_tokens(source, &mut diagnostics);
+    let maybe_ast = parse(tokens, &mut diagnostics);
+    let maybe_prog =
+        maybe_ast.as_ref().and_then(|ast| expand(ast, &mut diagnostics));
+    let (ast, program) = match (maybe_ast, maybe_prog) {
+        (Some(ast), Some(program)) if diagnostics.is_ok() => (ast, program),
+        _ => {
+            report.diagnostics =
+                diagnostics.iter().map(ToString::to_string).collect();
+            return report;
+        }
+    };
+
+    report.violations = check_forbidden(&ast, &exercise.forbidden);
+    if let Some(max) = exercise.max_registers {
+        let mut registers = IndexSet::new();
+        program.collect_registers(|register| {
+            registers.insert(register.to_owned());
+        });
+        if registers.len() > max {
+            report.violations.push(Violation::TooManyRegisters {
+                max,
+                found: registers.len(),
+            });
+        }
+    }
+
+    let table = batch::run(program, exercise.inputs.clone(), config);
+    let mut steps = BigUint::zero();
+    let mut reference_steps = BigUint::zero();
+
+    for (index, (row, expected)) in table.rows.iter().zip(expected).enumerate()
+    {
+        let expected = match expected {
+            Some(expected) => expected,
+            None => continue,
+        };
+        report.total += 1;
+
+        match &row.outcome {
+            Outcome::Halted(found) if found == expected => {
+                report.passed += 1;
+                if let Some(reference) = reference_table.rows.get(index) {
+                    steps += &row.steps;
+                    reference_steps += &reference.steps;
+                }
+            }
+            Outcome::Halted(found) => report.failures.push(WrongOutput {
+                input: row.input.clone(),
+                expected: expected.clone(),
+                found: found.clone(),
+            }),
+            Outcome::OutOfFuel => report.timeouts.push(Timeout {
+                input: row.input.clone(),
+                steps: row.steps.clone(),
+            }),
+        }
+    }
+
+    if let Reference::Program(_) = exercise.reference {
+        report.efficiency = Some(Efficiency { steps, reference_steps });
+    }
+
+    report
+}
+
+/// Procura usos de operações e testes proibidos na main e nas macros.
+fn check_forbidden(ast: &ast::Program, forbidden: &[String]) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    if forbidden.is_empty() {
+        return violations;
+    }
+
+    let bodies = Some(&ast.main.code)
+        .into_iter()
+        .chain(ast.macros.values().map(|macro_def| &macro_def.instr));
+    for instruction in bodies.flat_map(|body| body.values()) {
+        let (name, span) = match &instruction.instruction_type {
+            ast::InstructionType::Operation(operation) => {
+                let name = match &operation.oper_type {
+                    ast::OperationType::BuiltIn(oper, _) => oper.to_string(),
+                    ast::OperationType::Macro(name, _) => name.content.clone(),
+                };
+                (name, operation.span)
+            }
+            ast::InstructionType::Test(test) => {
+                let name = match &test.test_type {
+                    ast::TestType::BuiltIn(test, _) => test.to_string(),
+                    ast::TestType::Macro(name, _) => name.content.clone(),
+                };
+                (name, test.span)
+            }
+        };
+
+        if forbidden.contains(&name) {
+            violations.push(Violation::Forbidden {
+                name,
+                location: span.to_string(),
+            });
+        }
+    }
+
+    violations
+}
@@ -0,0 +1,162 @@
+use super::{
+    grade, grade_all, Efficiency, Exercise, Timeout, Violation, WrongOutput,
+};
+use crate::compiler::compile;
+use num_bigint::BigUint;
+
+/// Y := 2 * X.
+const REFERENCE: &str = "main {
+    1: if zero X then goto 0 else goto 2
+    2: do dec X goto 3
+    3: do inc Y goto 4
+    4: do inc Y goto 1
+}";
+
+/// Y := 2 * X, com uma macro e um registrador a mais.
+const SLOW: &str = "main {
+    1: if zero X then goto 0 else goto 2
+    2: do dec X goto 3
+    3: do inc X goto 4
+    4: do dec X goto 5
+    5: do incTwice (Y) goto 1
+}
+
+operation incTwice (R) {
+    1: do inc R goto 2
+    2: do inc R goto 0
+}";
+
+fn exercise() -> Exercise {
+    let mut exercise = Exercise::with_program(compile(REFERENCE).unwrap());
+    exercise.inputs = (0..6u8).map(BigUint::from).collect();
+    exercise.fuel = 1000;
+    exercise
+}
+
+#[test]
+fn accepted_submission_with_efficiency() {
+    let report = grade(&exercise(), "ana", SLOW);
+
+    assert!(report.accepted());
+    assert_eq!((report.passed, report.total), (6, 6));
+    assert_eq!(
+        report.efficiency,
+        Some(Efficiency {
+            steps: BigUint::from(96u8),
+            reference_steps: BigUint::from(66u8),
+        })
+    );
+    assert_eq!(
+        report.to_string(),
+        "Submissão de ana: aceita
+Acertos: 6 de 6
+Passos: 96 (referência: 66, 1.45x)
+"
+    );
+}
+
+#[test]
+fn wrong_outputs_and_timeouts() {
+    let source = "main {
+    1: if zero X then goto 5 else goto 2
+    2: do dec X goto 3
+    3: do inc Y goto 4
+    4: do inc Y goto 6
+    5: do inc Y goto 0
+    6: if zero X then goto 0 else goto 6
+}";
+    let report = grade(&exercise(), "bia", source);
+
+    assert!(!report.accepted());
+    assert_eq!((report.passed, report.total), (1, 6));
+    assert_eq!(
+        report.failures,
+        vec![WrongOutput {
+            input: BigUint::from(0u8),
+            expected: BigUint::from(0u8),
+            found: BigUint::from(1u8),
+        }]
+    );
+    let timeouts: Vec<_> =
+        report.timeouts.iter().map(|timeout| timeout.input.clone()).collect();
+    assert_eq!(timeouts, (2..6u8).map(BigUint::from).collect::<Vec<_>>());
+    assert_eq!(
+        report.timeouts[0],
+        Timeout { input: BigUint::from(2u8), steps: BigUint::from(1000u16) }
+    );
+}
+
+#[test]
+fn compile_errors() {
+    let report = grade(&exercise(), "caio", "main { 1: do inc X }");
+
+    assert!(!report.accepted());
+    assert_eq!(report.diagnostics.len(), 1);
+    assert_eq!(report.total, 0);
+    assert!(report.to_string().starts_with(
+        "Submissão de caio: rejeitada
+Erros de compilação:
+- Token inesperado"
+    ));
+}
+
+#[test]
+fn forbidden_constructs_and_registers() {
+    let mut exercise = exercise();
+    exercise.forbidden = vec!["incTwice".to_owned()];
+    exercise.max_registers = Some(2);
+
+    let report = grade(&exercise, "davi", SLOW);
+    assert!(!report.accepted());
+    assert_eq!(report.passed, report.total);
+    assert_eq!(
+        report.violations,
+        vec![Violation::Forbidden {
+            name: "incTwice".to_owned(),
+            location: "da linha 6 e coluna 11, até a coluna 22".to_owned(),
+        }]
+    );
+
+    exercise.forbidden = vec!["dec".to_owned()];
+    let report = grade(
+        &exercise,
+        "davi",
+        "main {
+    1: if zero X then goto 0 else goto 2
+    2: do dec X goto 3
+    3: do inc Y goto 4
+    4: do inc A goto 5
+    5: do inc Y goto 1
+}",
+    );
+    assert_eq!(
+        report.violations,
+        vec![
+            Violation::Forbidden {
+                name: "dec".to_owned(),
+                location: "da linha 3 e coluna 11, até a coluna 15".to_owned(),
+            },
+            Violation::TooManyRegisters { max: 2, found: 3 },
+        ]
+    );
+}
+
+#[test]
+fn function_reference_and_json() {
+    let mut exercise = Exercise::with_function(|x| x * 2u8);
+    exercise.inputs = vec![BigUint::from(1u8), BigUint::from(7u8)];
+
+    let reports =
+        grade_all(&exercise, vec![("ana", REFERENCE), ("caio", "main {")]);
+
+    assert_eq!(reports.len(), 2);
+    assert!(reports[0].accepted());
+    assert_eq!(reports[0].efficiency, None);
+    assert_eq!(
+        reports[0].to_json(),
+        "{\"student\":\"ana\",\"diagnostics\":[],\"violations\":[],\
+         \"total\":2,\"passed\":2,\"failures\":[],\"timeouts\":[],\
+         \"efficiency\":null}"
+    );
+    assert!(!reports[1].accepted());
+}
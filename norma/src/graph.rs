@@ -0,0 +1,413 @@
+//! Grafo de fluxo de controle (fluxograma) de programas da Norma, exportável
+//! para Graphviz (DOT) e Mermaid.
+//!
+//! Cada instrução vira um nó; testes têm uma aresta `then` e uma `else`, e
+//! todo rótulo de destino que não existe no programa vira um nó de parada.
+
+#[cfg(test)]
+mod test;
+
+pub mod svg;
+
+use crate::{
+    compiler::parser::ast,
+    interpreter::program::{InstructionKind, Program},
+};
+use indexmap::IndexMap;
+use std::fmt;
+
+/// Tipo de um nó do fluxograma.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    /// Uma instrução de operação, desenhada como retângulo.
+    Operation,
+    /// Uma instrução de teste, desenhada como losango.
+    Test,
+    /// Um rótulo de destino inexistente, onde o programa para.
+    Halt,
+}
+
+/// Uma chamada de macro da qual uma instrução expandida se originou.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Call {
+    /// Rótulo da instrução que chamou a macro.
+    pub label: String,
+    /// Nome da macro chamada.
+    pub name: String,
+}
+
+impl fmt::Display for Call {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmtr, "{} ({})", self.name, self.label)
+    }
+}
+
+/// Um nó do fluxograma.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    /// Rótulo da instrução, ou o rótulo de destino inexistente.
+    pub label: String,
+    /// Tipo do nó.
+    pub kind: NodeKind,
+    /// Texto da operação ou do teste, vazio para nós de parada.
+    pub text: String,
+    /// Chamadas de macro que originaram a instrução, da mais externa para a
+    /// mais interna. Vazio para instruções da main e nós de parada.
+    pub origin: Vec<Call>,
+}
+
+impl Node {
+    /// Texto exibido dentro do nó.
+    fn caption(&self) -> String {
+        if self.text.is_empty() {
+            self.label.clone()
+        } else {
+            format!("{}: {}", self.label, self.text)
+        }
+    }
+}
+
+/// Uma aresta do fluxograma, entre índices de nós.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edge {
+    /// Índice do nó de origem.
+    pub from: usize,
+    /// Índice do nó de destino.
+    pub to: usize,
+    /// `Some(true)` para `then`, `Some(false)` para `else` e `None` para
+    /// operações.
+    pub branch: Option<bool>,
+}
+
+impl Edge {
+    /// Nome do ramo, se a aresta sai de um teste.
+    fn caption(&self) -> Option<&'static str> {
+        match self.branch {
+            Some(true) => Some("then"),
+            Some(false) => Some("else"),
+            None => None,
+        }
+    }
+}
+
+/// Fluxograma de um programa.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Graph {
+    /// Os nós: primeiro as instruções, na ordem, e depois os nós de parada,
+    /// na ordem em que são referenciados.
+    pub nodes: Vec<Node>,
+    /// As arestas, na ordem das instruções.
+    pub edges: Vec<Edge>,
+}
+
+impl Graph {
+    /// Constrói o fluxograma de um programa já expandido. Instruções vindas de
+    /// macros guardam a chamada de origem, recuperada dos rótulos gerados na
+    /// expansão (`rótulo.macro.rótulo`).
+    pub fn from_program(program: &Program) -> Self {
+        let mut builder = Builder::default();
+        for instruction in program.instructions() {
+            let (kind, text, targets) = match &instruction.kind {
+                InstructionKind::Operation(oper) => (
+                    NodeKind::Operation,
+                    oper.kind.to_string(),
+                    vec![(None, oper.next.as_str())],
+                ),
+                InstructionKind::Test(test) => (
+                    NodeKind::Test,
+                    test.kind.to_string(),
+                    vec![
+                        (Some(true), test.next_then.as_str()),
+                        (Some(false), test.next_else.as_str()),
+                    ],
+                ),
+            };
+            let label = instruction.label();
+            builder.node(label, kind, text, origin(label), targets);
+        }
+        builder.finish()
+    }
+
+    /// Constrói o fluxograma da main antes da expansão, com chamadas de macro
+    /// como instruções comuns.
+    pub fn from_main(main: &ast::Main) -> Self {
+        Self::from_ast(&main.code)
+    }
+
+    /// Constrói o fluxograma de uma macro antes da expansão. Os rótulos de
+    /// saída, como `true` e `false` em testes, viram nós de parada.
+    pub fn from_macro(macro_def: &ast::Macro) -> Self {
+        Self::from_ast(&macro_def.instr)
+    }
+
+    /// Constrói o fluxograma de um trecho de código da `ast`.
+    fn from_ast(code: &IndexMap<String, ast::Instruction>) -> Self {
+        let mut builder = Builder::default();
+        for instruction in code.values() {
+            let (kind, text, targets) = match &instruction.instruction_type {
+                ast::InstructionType::Operation(oper) => {
+                    let text = match &oper.oper_type {
+                        ast::OperationType::BuiltIn(builtin, register) => {
+                            format!("{} {}", builtin, register.content)
+                        }
+                        ast::OperationType::Macro(name, arguments) => {
+                            macro_call(name, arguments)
+                        }
+                    };
+                    let targets =
+                        vec![(None, oper.next_label.content.as_str())];
+                    (NodeKind::Operation, text, targets)
+                }
+                ast::InstructionType::Test(test) => {
+                    let text = match &test.test_type {
+                        ast::TestType::BuiltIn(builtin, register) => {
+                            format!("{} {}", builtin, register.content)
+                        }
+                        ast::TestType::Macro(name, arguments) => {
+                            macro_call(name, arguments)
+                        }
+                    };
+                    let targets = vec![
+                        (Some(true), test.next_true_label.content.as_str()),
+                        (Some(false), test.next_false_label.content.as_str()),
+                    ];
+                    (NodeKind::Test, text, targets)
+                }
+            };
+            builder.node(
+                &instruction.label.content,
+                kind,
+                text,
+                Vec::new(),
+                targets,
+            );
+        }
+        builder.finish()
+    }
+
+    /// Exporta para a linguagem DOT do Graphviz. Com `clustered`, as
+    /// instruções de cada chamada de macro ficam agrupadas num `subgraph
+    /// cluster`.
+    pub fn to_dot(&self, clustered: bool) -> String {
+        let mut output = String::from("digraph programa {\n");
+        let nodes: Vec<_> = (0..self.nodes.len()).collect();
+        self.write_group(&mut output, &nodes, 0, clustered, &mut 0, &DOT);
+        for edge in &self.edges {
+            output.push_str(&format!("    n{} -> n{}", edge.from, edge.to));
+            if let Some(caption) = edge.caption() {
+                output.push_str(&format!(" [label=\"{}\"]", caption));
+            }
+            output.push_str(";\n");
+        }
+        output.push_str("}\n");
+        output
+    }
+
+    /// Exporta para um fluxograma Mermaid. Com `clustered`, as instruções de
+    /// cada chamada de macro ficam agrupadas num `subgraph`.
+    pub fn to_mermaid(&self, clustered: bool) -> String {
+        let mut output = String::from("flowchart TD\n");
+        let nodes: Vec<_> = (0..self.nodes.len()).collect();
+        self.write_group(&mut output, &nodes, 0, clustered, &mut 0, &MERMAID);
+        for edge in &self.edges {
+            let line = match edge.caption() {
+                Some(caption) => {
+                    format!(
+                        "    n{} -->|{}| n{}\n",
+                        edge.from, caption, edge.to
+                    )
+                }
+                None => format!("    n{} --> n{}\n", edge.from, edge.to),
+            };
+            output.push_str(&line);
+        }
+        output
+    }
+
+    /// Escreve as declarações de um grupo de nós, agrupando recursivamente
+    /// pela chamada de origem na profundidade `depth`.
+    fn write_group(
+        &self,
+        output: &mut String,
+        nodes: &[usize],
+        depth: usize,
+        clustered: bool,
+        clusters: &mut usize,
+        syntax: &Syntax,
+    ) {
+        let indent = "    ".repeat(depth + 1);
+        let mut groups: IndexMap<&Call, Vec<usize>> = IndexMap::new();
+
+        for &index in nodes {
+            let node = &self.nodes[index];
+            match node.origin.get(depth) {
+                Some(call) if clustered => {
+                    groups.entry(call).or_default().push(index)
+                }
+                _ => {
+                    let caption = escape(&node.caption());
+                    output.push_str(&indent);
+                    output.push_str(&(syntax.node)(index, node.kind, &caption));
+                    output.push('\n');
+                }
+            }
+        }
+
+        for (call, members) in groups {
+            let title = escape(&call.to_string());
+            output.push_str(&indent);
+            output.push_str(&(syntax.open)(*clusters, &title));
+            output.push('\n');
+            *clusters += 1;
+            self.write_group(
+                output,
+                &members,
+                depth + 1,
+                clustered,
+                clusters,
+                syntax,
+            );
+            output.push_str(&indent);
+            output.push_str(syntax.close);
+            output.push('\n');
+        }
+    }
+}
+
+/// Sintaxe de declaração de nós e grupos de um formato de exportação.
+struct Syntax {
+    /// Declara um nó, dado o índice, o tipo e o texto já escapado.
+    node: fn(usize, NodeKind, &str) -> String,
+    /// Abre um grupo, dado o índice do grupo e o título já escapado.
+    open: fn(usize, &str) -> String,
+    /// Fecha um grupo.
+    close: &'static str,
+}
+
+/// Sintaxe do Graphviz.
+const DOT: Syntax = Syntax {
+    node: |index, kind, caption| {
+        let shape = match kind {
+            NodeKind::Operation => "box",
+            NodeKind::Test => "diamond",
+            NodeKind::Halt => "doublecircle",
+        };
+        format!("n{} [label=\"{}\", shape={}];", index, caption, shape)
+    },
+    open: |index, title| {
+        format!("subgraph cluster_{} {{ label=\"{}\";", index, title)
+    },
+    close: "}",
+};
+
+/// Sintaxe do Mermaid.
+const MERMAID: Syntax = Syntax {
+    node: |index, kind, caption| match kind {
+        NodeKind::Operation => format!("n{}[\"{}\"]", index, caption),
+        NodeKind::Test => format!("n{}{{\"{}\"}}", index, caption),
+        NodeKind::Halt => format!("n{}((\"{}\"))", index, caption),
+    },
+    open: |index, title| format!("subgraph c{} [\"{}\"]", index, title),
+    close: "end",
+};
+
+/// Destinos de uma instrução, com o ramo de cada um.
+type Targets<'label> = Vec<(Option<bool>, &'label str)>;
+
+/// Construtor incremental do grafo, que cria os nós de parada conforme são
+/// referenciados.
+#[derive(Debug, Default)]
+struct Builder<'label> {
+    /// Índice de cada rótulo de instrução.
+    indices: IndexMap<&'label str, usize>,
+    /// Instruções, com suas arestas ainda como rótulos.
+    instructions: Vec<(Node, Targets<'label>)>,
+}
+
+impl<'label> Builder<'label> {
+    /// Acrescenta o nó de uma instrução.
+    fn node(
+        &mut self,
+        label: &'label str,
+        kind: NodeKind,
+        text: String,
+        origin: Vec<Call>,
+        targets: Targets<'label>,
+    ) {
+        self.indices.insert(label, self.instructions.len());
+        let node = Node { label: label.to_owned(), kind, text, origin };
+        self.instructions.push((node, targets));
+    }
+
+    /// Resolve as arestas e cria os nós de parada.
+    fn finish(mut self) -> Graph {
+        let mut graph = Graph::default();
+        let mut edges = Vec::new();
+
+        for (from, (node, targets)) in self.instructions.into_iter().enumerate()
+        {
+            graph.nodes.push(node);
+            for (branch, target) in targets {
+                edges.push((from, target, branch));
+            }
+        }
+
+        for (from, target, branch) in edges {
+            let to = match self.indices.get(target) {
+                Some(&to) => to,
+                None => {
+                    let to = graph.nodes.len();
+                    graph.nodes.push(Node {
+                        label: target.to_owned(),
+                        kind: NodeKind::Halt,
+                        text: String::new(),
+                        origin: Vec::new(),
+                    });
+                    self.indices.insert(target, to);
+                    to
+                }
+            };
+            graph.edges.push(Edge { from, to, branch });
+        }
+
+        graph
+    }
+}
+
+/// Recupera as chamadas de macro a partir de um rótulo expandido
+/// `rótulo.macro.rótulo.macro.rótulo`.
+fn origin(label: &str) -> Vec<Call> {
+    let parts: Vec<_> = label.split('.').collect();
+    let mut calls = Vec::new();
+    let mut prefix = String::new();
+
+    for pair in parts[..parts.len() - 1].chunks_exact(2) {
+        if !prefix.is_empty() {
+            prefix.push('.');
+        }
+        prefix.push_str(pair[0]);
+        calls.push(Call { label: prefix.clone(), name: pair[1].to_owned() });
+        prefix.push('.');
+        prefix.push_str(pair[1]);
+    }
+
+    calls
+}
+
+/// Texto de uma chamada de macro na `ast`.
+fn macro_call(name: &ast::Symbol, arguments: &[ast::MacroArgument]) -> String {
+    let arguments: Vec<_> = arguments
+        .iter()
+        .map(|argument| match argument {
+            ast::MacroArgument::Register(register) => register.content.clone(),
+            ast::MacroArgument::Number(number) => number.to_string(),
+        })
+        .collect();
+    format!("{} ({})", name.content, arguments.join(", "))
+}
+
+/// Troca aspas duplas por simples, já que o texto dos nós vai entre aspas
+/// duplas nos dois formatos.
+fn escape(text: &str) -> String {
+    text.replace('"', "'")
+}
@@ -0,0 +1,375 @@
+//! Layout em camadas e renderização em SVG do fluxograma, para quando não há
+//! Graphviz disponível, como no navegador.
+//!
+//! As camadas vêm de uma busca em largura a partir da primeira instrução; os
+//! nós de parada ficam sempre na última camada. Arestas para a camada logo
+//! abaixo são retas; arestas que pulam camadas contornam o desenho pela
+//! esquerda, e arestas para a mesma camada ou para cima (laços), pela direita.
+
+#[cfg(test)]
+mod test;
+
+use super::{Graph, NodeKind};
+use std::collections::VecDeque;
+
+/// Margem ao redor do desenho.
+const MARGIN: i64 = 20;
+/// Altura de uma camada.
+const LAYER_HEIGHT: i64 = 60;
+/// Espaço vertical entre camadas.
+const LAYER_GAP: i64 = 50;
+/// Espaço horizontal entre nós da mesma camada.
+const NODE_GAP: i64 = 40;
+/// Distância entre as rotas que contornam o desenho.
+const LOOP_GAP: i64 = 15;
+/// Largura aproximada de um caractere.
+const CHAR_WIDTH: i64 = 9;
+/// Cor de fundo dos nós.
+const FILL: &str = "white";
+/// Cor de fundo do nó destacado.
+const HIGHLIGHT: &str = "#ffd54f";
+
+/// Posição e tamanho de um nó. `x` e `y` são o centro do nó.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    /// Camada do nó, de cima para baixo.
+    pub layer: usize,
+    /// Coordenada horizontal do centro.
+    pub x: i64,
+    /// Coordenada vertical do centro.
+    pub y: i64,
+    /// Largura do nó.
+    pub width: i64,
+    /// Altura do nó.
+    pub height: i64,
+}
+
+impl Placement {
+    /// Coordenada do topo do nó.
+    fn top(&self) -> i64 {
+        self.y - self.height / 2
+    }
+
+    /// Coordenada da base do nó.
+    fn bottom(&self) -> i64 {
+        self.y + self.height / 2
+    }
+
+    /// Coordenada do lado esquerdo do nó.
+    fn left(&self) -> i64 {
+        self.x - self.width / 2
+    }
+
+    /// Coordenada do lado direito do nó.
+    fn right(&self) -> i64 {
+        self.x + self.width / 2
+    }
+}
+
+/// Como uma aresta é desenhada.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Route {
+    /// Reta para a camada logo abaixo.
+    Straight,
+    /// Contorno pela esquerda, para camadas mais abaixo.
+    Left,
+    /// Contorno pela direita, para a mesma camada ou para cima.
+    Right,
+}
+
+/// Decide como desenhar uma aresta entre nós das camadas dadas.
+fn route(from: usize, to: usize) -> Route {
+    if to == from + 1 {
+        Route::Straight
+    } else if to > from {
+        Route::Left
+    } else {
+        Route::Right
+    }
+}
+
+/// Layout de um fluxograma: a posição de cada nó, na ordem dos nós do grafo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    /// Posição de cada nó.
+    pub nodes: Vec<Placement>,
+    /// Largura do desenho, incluindo as rotas que o contornam.
+    pub width: i64,
+    /// Altura do desenho.
+    pub height: i64,
+}
+
+/// Calcula o layout em camadas de um fluxograma.
+pub fn layout(graph: &Graph) -> Layout {
+    let layers = assign_layers(graph);
+    let layer_count = layers.iter().max().map_or(0, |&max| max + 1);
+
+    let mut rows = vec![Vec::new(); layer_count];
+    for index in order(graph, &layers) {
+        rows[layers[index]].push(index);
+    }
+
+    let sizes: Vec<_> = graph
+        .nodes
+        .iter()
+        .map(|node| {
+            let text = CHAR_WIDTH * node.caption().chars().count() as i64;
+            match node.kind {
+                NodeKind::Operation => (text + 20, 40),
+                NodeKind::Test => (text + 60, 60),
+                NodeKind::Halt => ((text + 20).max(40), 40),
+            }
+        })
+        .collect();
+    let row_width = |row: &[usize]| {
+        let nodes: i64 = row.iter().map(|&index| sizes[index].0).sum();
+        nodes + NODE_GAP * (row.len() as i64 - 1).max(0)
+    };
+    let content_width =
+        rows.iter().map(|row| row_width(row)).max().unwrap_or(0);
+    let count = |kind| {
+        let edges = graph.edges.iter();
+        edges
+            .filter(|edge| route(layers[edge.from], layers[edge.to]) == kind)
+            .count() as i64
+    };
+    let (lefts, rights) = (count(Route::Left), count(Route::Right));
+
+    let mut nodes = vec![
+        Placement { layer: 0, x: 0, y: 0, width: 0, height: 0 };
+        graph.nodes.len()
+    ];
+    for (layer, row) in rows.iter().enumerate() {
+        let y = MARGIN
+            + layer as i64 * (LAYER_HEIGHT + LAYER_GAP)
+            + LAYER_HEIGHT / 2;
+        let mut left =
+            MARGIN + LOOP_GAP * lefts + (content_width - row_width(row)) / 2;
+        for &index in row {
+            let (width, height) = sizes[index];
+            nodes[index] =
+                Placement { layer, x: left + width / 2, y, width, height };
+            left += width + NODE_GAP;
+        }
+    }
+
+    let height = if layer_count == 0 {
+        2 * MARGIN
+    } else {
+        2 * MARGIN + layer_count as i64 * (LAYER_HEIGHT + LAYER_GAP) - LAYER_GAP
+    };
+
+    Layout {
+        nodes,
+        width: 2 * MARGIN + content_width + LOOP_GAP * (lefts + rights),
+        height,
+    }
+}
+
+/// Renderiza o fluxograma em SVG, destacando o nó com rótulo `current`, se
+/// houver. Cada nó é um `<g>` com as classes `node`, o tipo do nó
+/// (`operation`, `test` ou `halt`) e, se destacado, `current`, além do
+/// atributo `data-label` com o rótulo, para que a página possa trocar o
+/// destaque sem renderizar de novo.
+pub fn render(graph: &Graph, current: Option<&str>) -> String {
+    let layout = layout(graph);
+    let mut output = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" class=\"flowchart\" \
+         width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\" \
+         font-family=\"monospace\" font-size=\"14\">\n",
+        layout.width, layout.height
+    );
+    output.push_str(
+        "<defs><marker id=\"seta\" viewBox=\"0 0 10 10\" refX=\"10\" \
+         refY=\"5\" markerWidth=\"8\" markerHeight=\"8\" orient=\"auto\">\
+         <path d=\"M 0 0 L 10 5 L 0 10 z\"/></marker></defs>\n",
+    );
+
+    let routes: Vec<_> = graph
+        .edges
+        .iter()
+        .map(|edge| {
+            route(layout.nodes[edge.from].layer, layout.nodes[edge.to].layer)
+        })
+        .collect();
+    let (mut lefts, mut rights) = (
+        routes.iter().filter(|&&route| route == Route::Left).count() as i64,
+        routes.iter().filter(|&&route| route == Route::Right).count() as i64,
+    );
+
+    for (edge, route) in graph.edges.iter().zip(routes) {
+        let from = layout.nodes[edge.from];
+        let to = layout.nodes[edge.to];
+        let (path, label_x, label_y) = match route {
+            Route::Straight => {
+                let path = format!(
+                    "M {} {} L {} {}",
+                    from.x,
+                    from.bottom(),
+                    to.x,
+                    to.top()
+                );
+                let label_x = from.x + (to.x - from.x) * 3 / 10 + 4;
+                let label_y =
+                    from.bottom() + (to.top() - from.bottom()) * 3 / 10;
+                (path, label_x, label_y)
+            }
+            Route::Left => {
+                lefts -= 1;
+                let route_x = MARGIN + LOOP_GAP * lefts;
+                let path = format!(
+                    "M {} {} H {} V {} H {}",
+                    from.left(),
+                    from.y,
+                    route_x,
+                    to.y,
+                    to.left()
+                );
+                (path, route_x + 4, from.y + 16)
+            }
+            Route::Right => {
+                rights -= 1;
+                let path = format!(
+                    "M {} {} H {} V {} H {}",
+                    from.right(),
+                    from.y,
+                    layout.width - MARGIN - LOOP_GAP * rights,
+                    to.y,
+                    to.right()
+                );
+                (path, from.right() + 4, from.y - 4)
+            }
+        };
+
+        output.push_str(&format!(
+            "<g class=\"edge\"><path d=\"{}\" fill=\"none\" stroke=\"black\" \
+             marker-end=\"url(#seta)\"/>",
+            path
+        ));
+        if let Some(caption) = edge.caption() {
+            output.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\">{}</text>",
+                label_x, label_y, caption
+            ));
+        }
+        output.push_str("</g>\n");
+    }
+
+    for (node, place) in graph.nodes.iter().zip(&layout.nodes) {
+        let highlighted = current == Some(node.label.as_str());
+        let (class, shape) = match node.kind {
+            NodeKind::Operation => ("operation", "rect"),
+            NodeKind::Test => ("test", "polygon"),
+            NodeKind::Halt => ("halt", "ellipse"),
+        };
+        let fill = if highlighted { HIGHLIGHT } else { FILL };
+        let geometry = match node.kind {
+            NodeKind::Operation => format!(
+                "x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"",
+                place.x - place.width / 2,
+                place.top(),
+                place.width,
+                place.height
+            ),
+            NodeKind::Test => format!(
+                "points=\"{},{} {},{} {},{} {},{}\"",
+                place.x,
+                place.top(),
+                place.right(),
+                place.y,
+                place.x,
+                place.bottom(),
+                place.x - place.width / 2,
+                place.y
+            ),
+            NodeKind::Halt => format!(
+                "cx=\"{}\" cy=\"{}\" rx=\"{}\" ry=\"{}\"",
+                place.x,
+                place.y,
+                place.width / 2,
+                place.height / 2
+            ),
+        };
+
+        output.push_str(&format!(
+            "<g class=\"node {}{}\" data-label=\"{}\"><{} {} fill=\"{}\" \
+             stroke=\"black\"/><text x=\"{}\" y=\"{}\" text-anchor=\"middle\" \
+             dominant-baseline=\"central\">{}</text></g>\n",
+            class,
+            if highlighted { " current" } else { "" },
+            escape(&node.label),
+            shape,
+            geometry,
+            fill,
+            place.x,
+            place.y,
+            escape(&node.caption()),
+        ));
+    }
+
+    output.push_str("</svg>\n");
+    output
+}
+
+/// Atribui uma camada a cada nó por busca em largura a partir da primeira
+/// instrução. Instruções inalcançáveis ganham camadas abaixo das alcançáveis,
+/// e os nós de parada ficam todos na última camada.
+fn assign_layers(graph: &Graph) -> Vec<usize> {
+    let mut layers = vec![None; graph.nodes.len()];
+    let mut next_layer = 0;
+
+    for root in 0..graph.nodes.len() {
+        if layers[root].is_some() || graph.nodes[root].kind == NodeKind::Halt {
+            continue;
+        }
+        layers[root] = Some(next_layer);
+        let mut queue = VecDeque::from(vec![root]);
+        while let Some(index) = queue.pop_front() {
+            let layer = layers[index].unwrap_or(0);
+            next_layer = next_layer.max(layer + 1);
+            for edge in graph.edges.iter().filter(|edge| edge.from == index) {
+                let is_halt = graph.nodes[edge.to].kind == NodeKind::Halt;
+                if layers[edge.to].is_none() && !is_halt {
+                    layers[edge.to] = Some(layer + 1);
+                    queue.push_back(edge.to);
+                }
+            }
+        }
+    }
+
+    layers.into_iter().map(|layer| layer.unwrap_or(next_layer)).collect()
+}
+
+/// Ordem dos nós dentro das camadas: a ordem em que são alcançados seguindo
+/// as arestas, a partir de cada nó na ordem do grafo.
+fn order(graph: &Graph, layers: &[usize]) -> Vec<usize> {
+    let mut visited = vec![false; graph.nodes.len()];
+    let mut order = Vec::new();
+
+    for root in 0..graph.nodes.len() {
+        if visited[root] {
+            continue;
+        }
+        visited[root] = true;
+        let mut queue = VecDeque::from(vec![root]);
+        while let Some(index) = queue.pop_front() {
+            order.push(index);
+            for edge in graph.edges.iter().filter(|edge| edge.from == index) {
+                if !visited[edge.to] && layers[edge.to] > layers[index] {
+                    visited[edge.to] = true;
+                    queue.push_back(edge.to);
+                }
+            }
+        }
+    }
+
+    order
+}
+
+/// Escapa texto para uso em SVG.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
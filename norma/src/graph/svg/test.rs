@@ -0,0 +1,100 @@
+use super::{escape, layout, render};
+use crate::{compiler::compile, graph::Graph, interpreter::program::Program};
+
+/// Y := X.
+const SOURCE: &str = "main {
+    1: if zero X then goto 0 else goto 2
+    2: do dec X goto 3
+    3: do inc Y goto 1
+}";
+
+fn graph() -> Graph {
+    Graph::from_program(&compile(SOURCE).unwrap())
+}
+
+#[test]
+fn layered_layout() {
+    let layout = layout(&graph());
+
+    let layers: Vec<_> = layout.nodes.iter().map(|place| place.layer).collect();
+    assert_eq!(layers, vec![0, 1, 2, 3]);
+    let centers: Vec<_> =
+        layout.nodes.iter().map(|place| (place.x, place.y)).collect();
+    assert_eq!(centers, vec![(105, 50), (105, 160), (105, 270), (105, 380)]);
+    assert_eq!((layout.width, layout.height), (211, 430));
+}
+
+#[test]
+fn branches_share_layers() {
+    let graph = Graph::from_program(
+        &compile(
+            "main {
+    1: if zero X then goto 2 else goto 3
+    2: do inc Y goto 0
+    3: do dec X goto 0
+}",
+        )
+        .unwrap(),
+    );
+    let layout = layout(&graph);
+
+    let rows: Vec<_> =
+        layout.nodes.iter().map(|place| (place.layer, place.x)).collect();
+    assert_eq!(rows, vec![(0, 131), (1, 66), (1, 198), (2, 132)]);
+}
+
+#[test]
+fn svg_snapshot() {
+    assert_eq!(
+        render(&graph(), Some("2")),
+        r##"<svg xmlns="http://www.w3.org/2000/svg" class="flowchart" width="211" height="430" viewBox="0 0 211 430" font-family="monospace" font-size="14">
+<defs><marker id="seta" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="8" markerHeight="8" orient="auto"><path d="M 0 0 L 10 5 L 0 10 z"/></marker></defs>
+<g class="edge"><path d="M 35 50 H 20 V 380 H 85" fill="none" stroke="black" marker-end="url(#seta)"/><text x="24" y="66">then</text></g>
+<g class="edge"><path d="M 105 80 L 105 140" fill="none" stroke="black" marker-end="url(#seta)"/><text x="109" y="98">else</text></g>
+<g class="edge"><path d="M 105 180 L 105 250" fill="none" stroke="black" marker-end="url(#seta)"/></g>
+<g class="edge"><path d="M 151 270 H 191 V 50 H 175" fill="none" stroke="black" marker-end="url(#seta)"/></g>
+<g class="node test" data-label="1"><polygon points="105,20 175,50 105,80 35,50" fill="white" stroke="black"/><text x="105" y="50" text-anchor="middle" dominant-baseline="central">1: zero X</text></g>
+<g class="node operation current" data-label="2"><rect x="59" y="140" width="92" height="40" fill="#ffd54f" stroke="black"/><text x="105" y="160" text-anchor="middle" dominant-baseline="central">2: dec X</text></g>
+<g class="node operation" data-label="3"><rect x="59" y="250" width="92" height="40" fill="white" stroke="black"/><text x="105" y="270" text-anchor="middle" dominant-baseline="central">3: inc Y</text></g>
+<g class="node halt" data-label="0"><ellipse cx="105" cy="380" rx="20" ry="20" fill="white" stroke="black"/><text x="105" y="380" text-anchor="middle" dominant-baseline="central">0</text></g>
+</svg>
+"##
+    );
+}
+
+#[test]
+fn moving_highlight_clears_previous_node() {
+    let first = render(&graph(), Some("1"));
+    assert!(first.contains("<g class=\"node test current\" data-label=\"1\">"));
+
+    let second = render(&graph(), Some("2"));
+    assert!(second.contains("<g class=\"node test\" data-label=\"1\">"));
+    assert!(
+        second.contains("points=\"105,20 175,50 105,80 35,50\" fill=\"white\"")
+    );
+    assert_eq!(second.matches("current").count(), 1);
+    assert_eq!(second.matches("#ffd54f").count(), 1);
+
+    let plain = render(&graph(), None);
+    assert!(!plain.contains("#ffd54f"));
+}
+
+#[test]
+fn highlights_halt_and_escapes() {
+    let svg = render(&graph(), Some("0"));
+    assert!(svg.contains("<g class=\"node halt current\" data-label=\"0\">"));
+    assert!(!render(&graph(), None).contains("current"));
+
+    assert_eq!(escape("a<b & \"c\">"), "a&lt;b &amp; &quot;c&quot;&gt;");
+
+    let graph = Graph::from_program(&Program::empty());
+    assert!(graph.nodes.is_empty());
+    assert_eq!(
+        render(&graph, None).lines().next(),
+        Some(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" class=\"flowchart\" \
+             width=\"40\" height=\"40\" viewBox=\"0 0 40 40\" \
+             font-family=\"monospace\" font-size=\"14\">"
+        )
+    );
+}
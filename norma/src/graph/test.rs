@@ -0,0 +1,143 @@
+use super::{origin, Call, Edge, Graph, NodeKind};
+use crate::compiler::{compile, lexer::generate_tokens, parser::parse};
+use pinguim_language::error::Diagnostics;
+
+const SOURCE: &str = "main {
+    1: do clear (Y) goto 2
+    2: if zero X then goto 0 else goto 3
+    3: do dec X goto 4
+    4: do inc Y goto 2
+}
+
+operation clear (A) {
+    1: if zero A then goto 0 else goto 2
+    2: do dec A goto 1
+}
+
+test isZero (A) {
+    1: if zero A then goto true else goto false
+}";
+
+fn graph() -> Graph {
+    Graph::from_program(&compile(SOURCE).unwrap())
+}
+
+#[test]
+fn nodes_and_edges() {
+    let graph = graph();
+
+    let nodes: Vec<_> = graph
+        .nodes
+        .iter()
+        .map(|node| (node.label.as_str(), node.kind, node.text.as_str()))
+        .collect();
+    assert_eq!(
+        nodes,
+        vec![
+            ("1.clear.1", NodeKind::Test, "zero Y"),
+            ("1.clear.2", NodeKind::Operation, "dec Y"),
+            ("2", NodeKind::Test, "zero X"),
+            ("3", NodeKind::Operation, "dec X"),
+            ("4", NodeKind::Operation, "inc Y"),
+            ("0", NodeKind::Halt, ""),
+        ]
+    );
+    assert_eq!(
+        graph.nodes[0].origin,
+        vec![Call { label: "1".to_owned(), name: "clear".to_owned() }]
+    );
+    assert_eq!(graph.nodes[2].origin, Vec::new());
+    assert_eq!(graph.edges[3], Edge { from: 2, to: 5, branch: Some(true) });
+    assert_eq!(graph.edges[6], Edge { from: 4, to: 2, branch: None });
+}
+
+#[test]
+fn nested_origin() {
+    assert_eq!(origin("3"), Vec::new());
+    assert_eq!(
+        origin("1.double.3.clear.2"),
+        vec![
+            Call { label: "1".to_owned(), name: "double".to_owned() },
+            Call { label: "1.double.3".to_owned(), name: "clear".to_owned() },
+        ]
+    );
+}
+
+#[test]
+fn dot_export() {
+    let graph = graph();
+
+    assert_eq!(
+        graph.to_dot(false),
+        "digraph programa {
+    n0 [label=\"1.clear.1: zero Y\", shape=diamond];
+    n1 [label=\"1.clear.2: dec Y\", shape=box];
+    n2 [label=\"2: zero X\", shape=diamond];
+    n3 [label=\"3: dec X\", shape=box];
+    n4 [label=\"4: inc Y\", shape=box];
+    n5 [label=\"0\", shape=doublecircle];
+    n0 -> n2 [label=\"then\"];
+    n0 -> n1 [label=\"else\"];
+    n1 -> n0;
+    n2 -> n5 [label=\"then\"];
+    n2 -> n3 [label=\"else\"];
+    n3 -> n4;
+    n4 -> n2;
+}
+"
+    );
+    assert!(graph.to_dot(true).contains(
+        "    subgraph cluster_0 { label=\"clear (1)\";
+        n0 [label=\"1.clear.1: zero Y\", shape=diamond];
+        n1 [label=\"1.clear.2: dec Y\", shape=box];
+    }
+"
+    ));
+}
+
+#[test]
+fn mermaid_export() {
+    assert_eq!(
+        graph().to_mermaid(true),
+        "flowchart TD
+    n2{\"2: zero X\"}
+    n3[\"3: dec X\"]
+    n4[\"4: inc Y\"]
+    n5((\"0\"))
+    subgraph c0 [\"clear (1)\"]
+        n0{\"1.clear.1: zero Y\"}
+        n1[\"1.clear.2: dec Y\"]
+    end
+    n0 -->|then| n2
+    n0 -->|else| n1
+    n1 --> n0
+    n2 -->|then| n5
+    n2 -->|else| n3
+    n3 --> n4
+    n4 --> n2
+"
+    );
+}
+
+#[test]
+fn graphs_from_ast() {
+    let mut diagnostics = Diagnostics::new();
+    let tokens = generate_tokens(SOURCE, &mut diagnostics);
+    let ast = parse(tokens, &mut diagnostics).unwrap();
+
+    let main = Graph::from_main(&ast.main);
+    assert_eq!(main.nodes[0].text, "clear (Y)");
+    assert_eq!(main.nodes.len(), 5);
+
+    let is_zero = Graph::from_macro(&ast.macros["isZero"]);
+    assert_eq!(
+        is_zero.to_mermaid(false),
+        "flowchart TD
+    n0{\"1: zero A\"}
+    n1((\"true\"))
+    n2((\"false\"))
+    n0 -->|then| n1
+    n0 -->|else| n2
+"
+    );
+}
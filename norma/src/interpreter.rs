@@ -3,17 +3,21 @@
 #[cfg(test)]
 mod test;
 
+pub mod edit;
 pub mod program;
+pub mod snapshot;
 
+use edit::{Edit, EditError, EditRecord};
 use program::{
-    Instruction, InstructionKind, Operation, OperationKind, Program, Test,
-    TestKind,
+    InstructionKind, Operation, OperationKind, Program, ProgramError, Test,
+    TestKind, EXIT_LABEL,
 };
+use snapshot::{ProgramMismatch, SavedRegister, Snapshot};
 
-use crate::machine::Machine;
+use crate::machine::{Machine, MachineError, RegisterId};
 use num_bigint::BigUint;
 use num_traits::Zero;
-use std::{cmp::Ordering, ops::AddAssign};
+use std::{cmp::Ordering, error::Error, fmt, mem, ops::AddAssign, sync::Arc};
 
 // ("1.add.2", "do inc X goto 1.add.3")
 
@@ -32,18 +36,32 @@ pub fn run_once<'regs>(input: BigUint, program: Program) -> BigUint {
 pub struct Interpreter {
     /// Rótulo da instrução atual.
     current: String,
-    /// Programa com mapeamento de rótulos para instruções.
-    program: Program,
+    /// Programa com mapeamento de rótulos para instruções. Compartilhado, já
+    /// que o interpretador nunca o altera: clonar o interpretador, ou criar
+    /// vários interpretadores para o mesmo programa, não copia instruções.
+    program: Arc<Program>,
     /// Máquina sendo operada.
     machine: Machine,
     /// Passos dados.
     steps: BigUint,
+    /// Edições feitas à mão desde o início da execução.
+    edits: Vec<EditRecord>,
+    /// Identificadores na máquina dos registradores de cada instrução, na
+    /// ordem de [`Program::instructions`], ou o erro do primeiro registrador
+    /// da instrução que não existe na máquina. Calculado só quando a máquina
+    /// é trocada ou ganha registradores de fora da execução, para que cada
+    /// passo não precise pesquisar registradores pelo nome.
+    registers: Vec<Result<Vec<RegisterId>, MachineError>>,
 }
 
 impl Interpreter {
     /// Inicia o interpretador com o estado inicial do programa, a partir do
     /// programa com as instruções.
-    pub fn new<'regs>(program: Program) -> Self {
+    pub fn new<P>(program: P) -> Self
+    where
+        P: Into<Arc<Program>>,
+    {
+        let program = program.into();
         let start = program.first_label().to_string();
 
         let mut machine = Machine::default();
@@ -57,13 +75,40 @@ impl Interpreter {
     /// Cria um novo interpretador a partir de um dado estado: o rótulo da
     /// instrução atual, o programa com as instruções, a máquina sendo operada,
     /// e os passos dados.
-    pub fn from_state(
+    pub fn from_state<P>(
+        current: String,
+        program: P,
+        machine: Machine,
+        steps: BigUint,
+    ) -> Self
+    where
+        P: Into<Arc<Program>>,
+    {
+        let program = program.into();
+        let registers = resolve_registers(&program, &machine);
+        Self { current, program, machine, steps, edits: Vec::new(), registers }
+    }
+
+    /// Cria um novo interpretador a partir de um dado estado, como
+    /// [`Interpreter::from_state`], mas antes checa se todo registrador usado
+    /// pelo programa existe na máquina e se todo desvio vai para uma
+    /// instrução do programa ou para [`EXIT_LABEL`] (ver
+    /// [`Program::validate`]).
+    pub fn try_from_state<P>(
         current: String,
-        program: Program,
+        program: P,
         machine: Machine,
         steps: BigUint,
-    ) -> Self {
-        Self { current, program, machine, steps }
+    ) -> Result<Self, RunError>
+    where
+        P: Into<Arc<Program>>,
+    {
+        let interpreter = Self::from_state(current, program, machine, steps);
+        for registers in &interpreter.registers {
+            registers.as_ref().map_err(Clone::clone)?;
+        }
+        interpreter.program.validate(&[EXIT_LABEL], |_| true)?;
+        Ok(interpreter)
     }
 
     /// Retorna o rótulo da instrução sendo atualmente executada.
@@ -76,6 +121,12 @@ impl Interpreter {
         &self.program
     }
 
+    /// Retorna o programa sendo executado, compartilhado, para criar outros
+    /// interpretadores do mesmo programa sem copiá-lo.
+    pub fn shared_program(&self) -> Arc<Program> {
+        self.program.clone()
+    }
+
     /// Retorna uma referência imutável para a máquina sendo operada.
     pub fn machine(&self) -> &Machine {
         &self.machine
@@ -98,6 +149,70 @@ impl Interpreter {
         self.machine.clear_all();
         self.current = self.program.first_label().to_string();
         self.steps.set_zero();
+        self.edits.clear();
+    }
+
+    /// Define o valor de um registrador existente, com a execução pausada. A
+    /// edição é registrada em [`Interpreter::edits`] e não conta passos.
+    pub fn set_register(
+        &mut self,
+        reg_name: &str,
+        value: BigUint,
+    ) -> Result<(), EditError> {
+        let old = self
+            .machine
+            .try_get_value(reg_name)
+            .map_err(|_| EditError::UndefinedRegister(reg_name.to_owned()))?;
+        self.machine.insert_with_value(reg_name, value.clone());
+        self.record(Edit::SetRegister {
+            register: reg_name.to_owned(),
+            old,
+            new: value,
+        });
+        Ok(())
+    }
+
+    /// Cria um registrador auxiliar com o dado valor, com a execução pausada.
+    /// Falha se o nome for inválido ou se o registrador já existir. A edição
+    /// é registrada em [`Interpreter::edits`] e não conta passos.
+    pub fn create_register(
+        &mut self,
+        reg_name: &str,
+        value: BigUint,
+    ) -> Result<(), EditError> {
+        if !program::is_valid_register(reg_name) {
+            return Err(EditError::InvalidRegister(reg_name.to_owned()));
+        }
+        if self.machine.register_exists(reg_name) {
+            return Err(EditError::DuplicatedRegister(reg_name.to_owned()));
+        }
+        self.machine.insert_with_value(reg_name, value.clone());
+        if self.registers.iter().any(Result::is_err) {
+            self.registers = resolve_registers(&self.program, &self.machine);
+        }
+        self.record(Edit::CreateRegister {
+            register: reg_name.to_owned(),
+            value,
+        });
+        Ok(())
+    }
+
+    /// Troca o rótulo da instrução atual, com a execução pausada. O rótulo
+    /// deve ser de uma instrução do programa. A edição é registrada em
+    /// [`Interpreter::edits`] e não conta passos.
+    pub fn jump_to(&mut self, label: &str) -> Result<(), EditError> {
+        if !self.program.is_label_valid(label) {
+            return Err(EditError::UndefinedLabel(label.to_owned()));
+        }
+        let from = mem::replace(&mut self.current, label.to_owned());
+        self.record(Edit::Jump { from, to: label.to_owned() });
+        Ok(())
+    }
+
+    /// Retorna as edições feitas à mão desde o início da execução, na ordem
+    /// em que foram feitas.
+    pub fn edits(&self) -> &[EditRecord] {
+        &self.edits
     }
 
     /// Roda a instrução atual, mas somente essa, caso o rótulo da instrução
@@ -105,34 +220,72 @@ impl Interpreter {
     /// rótulo da instrução atual é atualizado de acordo com a instrução
     /// específica. Retorna `true` se o rótulo é válido e a instrução foi de
     /// fato executada.
+    ///
+    /// # Panics
+    /// Invoca `panic!` se a instrução usar um registrador inexistente.
     pub fn run_step(&mut self) -> bool {
-        let entry = self.program.instruction(&self.current).cloned();
-        match entry {
-            Some(instruction) => {
-                self.run_instruction(instruction);
-                true
-            }
-            None => false,
-        }
+        expect_machine(self.try_run_step())
+    }
+
+    /// Roda a instrução atual, como [`Interpreter::run_step`]. Falha, sem
+    /// alterar o interpretador, se a instrução usar um registrador
+    /// inexistente.
+    pub fn try_run_step(&mut self) -> Result<bool, MachineError> {
+        let (index, instruction) =
+            match self.program.instruction_full(&self.current) {
+                Some(entry) => entry,
+                None => return Ok(false),
+            };
+        let registers = self.registers[index].as_ref().map_err(Clone::clone)?;
+
+        let mut execution =
+            Execution { machine: &mut self.machine, steps: &mut self.steps };
+        let next = execution.run_instruction(&instruction.kind, registers);
+        self.current.clone_from(next);
+        Ok(true)
     }
 
     /// Roda no máximo `max_steps` instrções, a partir do rótulo atual (que é
     /// redefinido após cada instrução). Se chegarmos ao final do programa antes
     /// de `max_steps`, a execução para. Retorna `true` se ainda restam
     /// instruções para serem executadas.
+    ///
+    /// # Panics
+    /// Invoca `panic!` se alguma instrução usar um registrador inexistente.
     pub fn run_steps(&mut self, max_steps: u32) -> bool {
+        expect_machine(self.try_run_steps(max_steps))
+    }
+
+    /// Roda no máximo `max_steps` instruções, como
+    /// [`Interpreter::run_steps`]. Para na primeira instrução que usar um
+    /// registrador inexistente, retornando o erro.
+    pub fn try_run_steps(
+        &mut self,
+        max_steps: u32,
+    ) -> Result<bool, MachineError> {
         for _ in 0..max_steps {
-            if !self.run_step() {
-                return false;
+            if !self.try_run_step()? {
+                return Ok(false);
             }
         }
-        true
+        Ok(true)
     }
 
     /// Executa todas as instruções, partindo do rótulo atual, até chegar no
     /// final do programa (potencial loop infinito).
+    ///
+    /// # Panics
+    /// Invoca `panic!` se alguma instrução usar um registrador inexistente.
     pub fn run_all(&mut self) {
-        while self.run_step() {}
+        expect_machine(self.try_run_all())
+    }
+
+    /// Executa todas as instruções, como [`Interpreter::run_all`]. Para na
+    /// primeira instrução que usar um registrador inexistente, retornando o
+    /// erro.
+    pub fn try_run_all(&mut self) -> Result<(), MachineError> {
+        while self.try_run_step()? {}
+        Ok(())
     }
 
     /// Retorna quantos passos foram dados.
@@ -140,89 +293,176 @@ impl Interpreter {
         self.steps.clone()
     }
 
+    /// Salva o estado atual da execução: rótulo atual, todos os
+    /// registradores, passos dados e a impressão digital do programa.
+    pub fn snapshot(&self) -> Snapshot {
+        let registers = self
+            .machine
+            .registers()
+            .map(|(name, value)| SavedRegister {
+                name: name.to_owned(),
+                value: value.to_biguint(),
+            })
+            .collect();
+
+        Snapshot {
+            current: self.current.clone(),
+            registers,
+            steps: self.steps.clone(),
+            edits: self.edits.clone(),
+            fingerprint: self.program.fingerprint(),
+        }
+    }
+
+    /// Carrega de volta um estado salvo com [`Interpreter::snapshot`]. Falha,
+    /// sem alterar o interpretador, se o estado for de outro programa.
+    /// Registradores do programa ausentes no estado são criados zerados.
+    pub fn restore(
+        &mut self,
+        snapshot: Snapshot,
+    ) -> Result<(), ProgramMismatch> {
+        let expected = self.program.fingerprint();
+        if snapshot.fingerprint != expected {
+            return Err(ProgramMismatch {
+                expected,
+                found: snapshot.fingerprint,
+            });
+        }
+
+        let mut machine = Machine::from_registers(
+            snapshot
+                .registers
+                .into_iter()
+                .map(|register| (register.name, register.value)),
+        );
+        self.program.collect_registers(|reg_name| {
+            machine.create(reg_name);
+        });
+
+        self.registers = resolve_registers(&self.program, &machine);
+        self.machine = machine;
+        self.current = snapshot.current;
+        self.steps = snapshot.steps;
+        self.edits = snapshot.edits;
+        Ok(())
+    }
+
+    /// Registra uma edição feita à mão, junto com os passos dados até ela.
+    fn record(&mut self, edit: Edit) {
+        self.edits.push(EditRecord { steps: self.steps.clone(), edit });
+    }
+}
+
+/// Execução de uma instrução sobre a máquina, contando os passos dados.
+#[derive(Debug)]
+struct Execution<'interp> {
+    /// Máquina sendo operada.
+    machine: &'interp mut Machine,
+    /// Passos dados.
+    steps: &'interp mut BigUint,
+}
+
+impl<'interp> Execution<'interp> {
     /// Conta a dada quantidade de passos dados em uma determinada instrução,
     /// junto com os passos anteriores.
     fn count_steps<T>(&mut self, amount: T)
     where
         BigUint: AddAssign<T>,
     {
-        self.steps += amount;
+        *self.steps += amount;
     }
 
     /// Performa a execução de uma dada instrução (no parâmetro), de acordo com
-    /// o tipo específico de instrução (cada tipo também atualiza o rótulo atual
-    /// de uma forma própria).
-    fn run_instruction(&mut self, instruction: Instruction) {
-        match instruction.kind {
-            InstructionKind::Test(test) => self.run_test(test),
+    /// o tipo específico de instrução, e retorna o rótulo da próxima
+    /// instrução. `registers` são os identificadores dos registradores da
+    /// instrução, na ordem de [`InstructionKind::collect_registers`].
+    fn run_instruction<'prog>(
+        &mut self,
+        kind: &'prog InstructionKind,
+        registers: &[RegisterId],
+    ) -> &'prog String {
+        match kind {
+            InstructionKind::Test(test) => self.run_test(test, registers),
             InstructionKind::Operation(operation) => {
-                self.run_operation(operation)
+                self.run_operation(operation, registers)
             }
         }
     }
 
     /// Executa uma dada instrução do tipo operação. Além de executar a operação
-    /// em si, atualiza o rótulo para aquele indicado no `goto` da operação.
-    fn run_operation(&mut self, operation: Operation) {
-        match operation.kind {
-            OperationKind::Inc(register) => self.run_inc(&register),
-            OperationKind::Dec(register) => self.run_dec(&register),
-            OperationKind::Clear(register) => self.run_clear(&register),
-            OperationKind::Load(register, constant) => {
-                self.run_load(&register, &constant)
+    /// em si, retorna o rótulo indicado no `goto` da operação.
+    fn run_operation<'prog>(
+        &mut self,
+        operation: &'prog Operation,
+        registers: &[RegisterId],
+    ) -> &'prog String {
+        match &operation.kind {
+            OperationKind::Inc(_) => self.run_inc(registers[0]),
+            OperationKind::Dec(_) => self.run_dec(registers[0]),
+            OperationKind::Clear(_) => self.run_clear(registers[0]),
+            OperationKind::Load(_, constant) => {
+                self.run_load(registers[0], constant)
             }
-            OperationKind::AddConst(register, constant) => {
-                self.run_add_const(&register, &constant)
+            OperationKind::AddConst(_, constant) => {
+                self.run_add_const(registers[0], constant)
             }
-            OperationKind::Add(reg_dest, reg_src, reg_tmp) => {
-                self.run_add(&reg_dest, &reg_src, &reg_tmp)
+            OperationKind::Add(..) => {
+                self.run_add(registers[0], registers[1], registers[2])
             }
-            OperationKind::SubConst(register, constant) => {
-                self.run_sub_const(&register, &constant)
+            OperationKind::SubConst(_, constant) => {
+                self.run_sub_const(registers[0], constant)
             }
-            OperationKind::Sub(reg_dest, reg_src, reg_tmp) => {
-                self.run_sub(&reg_dest, &reg_src, &reg_tmp)
+            OperationKind::Sub(..) => {
+                self.run_sub(registers[0], registers[1], registers[2])
             }
         }
-        self.current = operation.next;
+        &operation.next
     }
 
     /// Executa uma dada instrução do tipo teste. Além de executar o teste em
-    /// si, atualiza o rótulo para aquele indicado no `then goto` ou `else goto`
-    /// do teste, de acordo com o resultado do teste.
-    fn run_test(&mut self, test: Test) {
-        let success = match test.kind {
-            TestKind::Zero(register) => self.test_zero(&register),
-            TestKind::EqualsConst(register, constant) => {
-                self.test_equals_const(&register, &constant)
+    /// si, retorna o rótulo indicado no `then goto` ou `else goto` do teste,
+    /// de acordo com o resultado do teste.
+    fn run_test<'prog>(
+        &mut self,
+        test: &'prog Test,
+        registers: &[RegisterId],
+    ) -> &'prog String {
+        let success = match &test.kind {
+            TestKind::Zero(_) => self.test_zero(registers[0]),
+            TestKind::EqualsConst(_, constant) => {
+                self.test_equals_const(registers[0], constant)
             }
-            TestKind::Equals(reg_left, reg_right, reg_tmp) => {
-                self.test_equals(&reg_left, &reg_right, &reg_tmp)
+            TestKind::Equals(..) => {
+                self.test_equals(registers[0], registers[1], registers[2])
             }
-            TestKind::LessThanConst(register, constant) => {
-                self.test_less_than_const(&register, &constant)
+            TestKind::LessThanConst(_, constant) => {
+                self.test_less_than_const(registers[0], constant)
             }
-            TestKind::LessThan(reg_left, reg_right, reg_tmp) => {
-                self.test_less_than(&reg_left, &reg_right, &reg_tmp)
+            TestKind::LessThan(..) => {
+                self.test_less_than(registers[0], registers[1], registers[2])
             }
         };
-        self.current = if success { test.next_then } else { test.next_else };
+        if success {
+            &test.next_then
+        } else {
+            &test.next_else
+        }
     }
 
     /// `inc A`
     ///
     /// `1` step
-    fn run_inc(&mut self, reg_name: &str) {
+    fn run_inc(&mut self, reg_name: RegisterId) {
         self.count_steps(1u8);
-        self.machine.inc(reg_name);
+        self.machine.inc_by_id(reg_name);
     }
 
     /// `dec A`
     ///
     /// `1` step
-    fn run_dec(&mut self, reg_name: &str) {
+    fn run_dec(&mut self, reg_name: RegisterId) {
         self.count_steps(1u8);
-        self.machine.dec(reg_name);
+        self.machine.dec_by_id(reg_name);
     }
 
     /// ```pre
@@ -233,12 +473,12 @@ impl Interpreter {
     /// ```
     ///
     /// `Tmp * 2 + 1` steps
-    fn run_clear(&mut self, reg_name: &str) {
-        let mut steps = self.machine.get_value(reg_name);
+    fn run_clear(&mut self, reg_name: RegisterId) {
+        let mut steps = self.machine.value_by_id(reg_name).to_biguint();
         steps *= 2u8;
         steps += 1u8;
         self.count_steps(steps);
-        self.machine.clear(reg_name);
+        self.machine.clear_by_id(reg_name);
     }
 
     /// ```pre
@@ -251,14 +491,14 @@ impl Interpreter {
     /// ```
     ///
     /// `Dest * 2 + 1 + N` steps
-    fn run_load(&mut self, reg_name: &str, constant: &BigUint) {
-        let mut steps = self.machine.get_value(reg_name);
+    fn run_load(&mut self, reg_name: RegisterId, constant: &BigUint) {
+        let mut steps = self.machine.value_by_id(reg_name).to_biguint();
         steps *= 2u8;
         steps += 1u8;
         steps += constant;
         self.count_steps(steps);
-        self.machine.clear(reg_name);
-        self.machine.add_const(reg_name, constant);
+        self.machine.clear_by_id(reg_name);
+        self.machine.add_const_by_id(reg_name, constant);
     }
 
     /// ```pre
@@ -271,9 +511,9 @@ impl Interpreter {
     /// ```
     ///
     /// `N` steps
-    fn run_add_const(&mut self, reg_name: &str, constant: &BigUint) {
+    fn run_add_const(&mut self, reg_name: RegisterId, constant: &BigUint) {
         self.count_steps(constant);
-        self.machine.add_const(reg_name, constant);
+        self.machine.add_const_by_id(reg_name, constant);
     }
 
     /// ```pre
@@ -295,17 +535,22 @@ impl Interpreter {
     /// ```
     ///
     /// `Tmp * 2 + Src * 7 + 3` steps
-    fn run_add(&mut self, reg_dest: &str, reg_src: &str, reg_tmp: &str) {
-        let mut tmp_steps = self.machine.get_value(reg_src);
+    fn run_add(
+        &mut self,
+        reg_dest: RegisterId,
+        reg_src: RegisterId,
+        reg_tmp: RegisterId,
+    ) {
+        let mut tmp_steps = self.machine.value_by_id(reg_src).to_biguint();
         tmp_steps *= 2u8;
 
-        let mut steps = self.machine.get_value(reg_src);
+        let mut steps = self.machine.value_by_id(reg_src).to_biguint();
         steps *= 7u8;
         steps += tmp_steps;
         steps += 3u8;
         self.count_steps(steps);
 
-        self.machine.add(reg_dest, reg_src, reg_tmp);
+        self.machine.add_by_id(reg_dest, reg_src, reg_tmp);
     }
 
     /// ```pre
@@ -318,9 +563,9 @@ impl Interpreter {
     /// ```
     ///
     /// `N` steps
-    fn run_sub_const(&mut self, reg_name: &str, constant: &BigUint) {
+    fn run_sub_const(&mut self, reg_name: RegisterId, constant: &BigUint) {
         self.count_steps(constant);
-        self.machine.sub_const(reg_name, constant);
+        self.machine.sub_const_by_id(reg_name, constant);
     }
 
     /// ```pre
@@ -342,25 +587,30 @@ impl Interpreter {
     /// ```
     ///
     /// `Tmp * 2 + Src * 7 + 3` steps
-    fn run_sub(&mut self, reg_dest: &str, reg_src: &str, reg_tmp: &str) {
-        let mut tmp_steps = self.machine.get_value(reg_src);
+    fn run_sub(
+        &mut self,
+        reg_dest: RegisterId,
+        reg_src: RegisterId,
+        reg_tmp: RegisterId,
+    ) {
+        let mut tmp_steps = self.machine.value_by_id(reg_src).to_biguint();
         tmp_steps *= 2u8;
 
-        let mut steps = self.machine.get_value(reg_src);
+        let mut steps = self.machine.value_by_id(reg_src).to_biguint();
         steps *= 7u8;
         steps += tmp_steps;
         steps += 3u8;
         self.count_steps(steps);
 
-        self.machine.sub(reg_dest, reg_src, reg_tmp);
+        self.machine.sub_by_id(reg_dest, reg_src, reg_tmp);
     }
 
     /// `zero A`
     ///
     /// `1` step
-    fn test_zero(&mut self, reg_name: &str) -> bool {
+    fn test_zero(&mut self, reg_name: RegisterId) -> bool {
         self.count_steps(1u8);
-        self.machine.is_zero(reg_name)
+        self.machine.is_zero_by_id(reg_name)
     }
 
     /// ```pre
@@ -387,13 +637,13 @@ impl Interpreter {
     /// `min(A, N) * 3 + 1` steps
     fn test_equals_const(
         &mut self,
-        register: &str,
+        register: RegisterId,
         constant: &BigUint,
     ) -> bool {
-        let ordering = self.machine.cmp_const(register, constant);
+        let ordering = self.machine.cmp_const_by_id(register, constant);
 
         let mut steps = if ordering <= Ordering::Equal {
-            self.machine.get_value(register)
+            self.machine.value_by_id(register).to_biguint()
         } else {
             constant.clone()
         };
@@ -434,17 +684,17 @@ impl Interpreter {
     /// `Tmp * 2 + min(L, R) * 9 + 4` steps
     fn test_equals(
         &mut self,
-        reg_left: &str,
-        reg_right: &str,
-        reg_tmp: &str,
+        reg_left: RegisterId,
+        reg_right: RegisterId,
+        reg_tmp: RegisterId,
     ) -> bool {
-        let ordering = self.machine.cmp(reg_left, reg_right, reg_tmp);
+        let ordering = self.machine.cmp_by_id(reg_left, reg_right, reg_tmp);
 
-        let mut tmp_steps = self.machine.get_value(reg_tmp);
+        let mut tmp_steps = self.machine.value_by_id(reg_tmp).to_biguint();
         tmp_steps *= 2u8;
         let minimum =
             if ordering <= Ordering::Equal { reg_left } else { reg_right };
-        let mut steps = self.machine.get_value(minimum);
+        let mut steps = self.machine.value_by_id(minimum).to_biguint();
 
         steps *= 9u8;
         steps += tmp_steps;
@@ -478,13 +728,15 @@ impl Interpreter {
     /// `min(A, N - 1) * 3 + 1` steps
     fn test_less_than_const(
         &mut self,
-        register: &str,
+        register: RegisterId,
         constant: &BigUint,
     ) -> bool {
-        let ordering = self.machine.cmp_const(register, constant);
+        let ordering = self.machine.cmp_const_by_id(register, constant);
 
         let mut steps = if ordering < Ordering::Equal {
-            self.machine.get_value(register)
+            self.machine.value_by_id(register).to_biguint()
+        } else if constant.is_zero() {
+            BigUint::zero()
         } else {
             constant - 1u8
         };
@@ -525,17 +777,17 @@ impl Interpreter {
     /// `Tmp * 2 + min(L, R) * 9 + 4` steps
     fn test_less_than(
         &mut self,
-        reg_left: &str,
-        reg_right: &str,
-        reg_tmp: &str,
+        reg_left: RegisterId,
+        reg_right: RegisterId,
+        reg_tmp: RegisterId,
     ) -> bool {
-        let ordering = self.machine.cmp(reg_left, reg_right, reg_tmp);
+        let ordering = self.machine.cmp_by_id(reg_left, reg_right, reg_tmp);
 
-        let mut tmp_steps = self.machine.get_value(reg_tmp);
+        let mut tmp_steps = self.machine.value_by_id(reg_tmp).to_biguint();
         tmp_steps *= 2u8;
         let minimum =
             if ordering <= Ordering::Equal { reg_left } else { reg_right };
-        let mut steps = self.machine.get_value(minimum);
+        let mut steps = self.machine.value_by_id(minimum).to_biguint();
 
         steps *= 9u8;
         steps += tmp_steps;
@@ -545,3 +797,86 @@ impl Interpreter {
         ordering == Ordering::Less
     }
 }
+
+/// Troca os registradores de cada instrução do programa pelos seus
+/// identificadores na máquina, na ordem de [`Program::instructions`]. Uma
+/// instrução com um registrador que não existe na máquina fica com o erro do
+/// primeiro deles.
+fn resolve_registers(
+    program: &Program,
+    machine: &Machine,
+) -> Vec<Result<Vec<RegisterId>, MachineError>> {
+    program
+        .instructions()
+        .map(|instruction| {
+            let mut registers = Vec::new();
+            let mut missing = None;
+            instruction.collect_registers(|reg_name| {
+                match machine.register_id(reg_name) {
+                    Some(id) => registers.push(id),
+                    None => {
+                        missing.get_or_insert_with(|| reg_name.to_owned());
+                    }
+                }
+            });
+            match missing {
+                Some(reg_name) => {
+                    Err(MachineError::UndefinedRegister(reg_name))
+                }
+                None => Ok(registers),
+            }
+        })
+        .collect()
+}
+
+/// Extrai o resultado de uma execução que não pode falhar.
+///
+/// # Panics
+/// Invoca `panic!` se a execução falhou por usar um registrador inexistente.
+fn expect_machine<T>(result: Result<T, MachineError>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(MachineError::UndefinedRegister(reg_name)) => {
+            panic!("Register {} does not exist", reg_name)
+        }
+    }
+}
+
+/// Erro ao preparar ou executar um programa no interpretador.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunError {
+    /// Erro na máquina, como um registrador inexistente.
+    Machine(MachineError),
+    /// Erro no programa, como um rótulo inválido.
+    Program(ProgramError),
+}
+
+impl From<MachineError> for RunError {
+    fn from(error: MachineError) -> Self {
+        RunError::Machine(error)
+    }
+}
+
+impl From<ProgramError> for RunError {
+    fn from(error: ProgramError) -> Self {
+        RunError::Program(error)
+    }
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RunError::Machine(error) => write!(fmtr, "{}", error),
+            RunError::Program(error) => write!(fmtr, "{}", error),
+        }
+    }
+}
+
+impl Error for RunError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RunError::Machine(error) => Some(error),
+            RunError::Program(error) => Some(error),
+        }
+    }
+}
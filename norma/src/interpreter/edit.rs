@@ -0,0 +1,101 @@
+//! Este módulo define as edições que podem ser feitas no estado do
+//! interpretador com a execução pausada, e o registro delas.
+
+use super::program::decimal;
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fmt};
+
+/// Uma edição feita à mão no estado do interpretador, fora da execução do
+/// programa.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Edit {
+    /// O valor de um registrador existente foi alterado.
+    SetRegister {
+        /// Nome do registrador.
+        register: String,
+        /// Valor anterior à edição.
+        #[serde(with = "decimal")]
+        old: BigUint,
+        /// Valor posterior à edição.
+        #[serde(with = "decimal")]
+        new: BigUint,
+    },
+    /// Um registrador auxiliar, que não aparece no programa, foi criado.
+    CreateRegister {
+        /// Nome do registrador.
+        register: String,
+        /// Valor inicial do registrador.
+        #[serde(with = "decimal")]
+        value: BigUint,
+    },
+    /// O rótulo da instrução atual foi trocado.
+    Jump {
+        /// Rótulo antes da edição.
+        from: String,
+        /// Rótulo depois da edição.
+        to: String,
+    },
+}
+
+impl fmt::Display for Edit {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Edit::SetRegister { register, old, new } => {
+                write!(fmtr, "{} := {} (era {})", register, new, old)
+            }
+            Edit::CreateRegister { register, value } => {
+                write!(fmtr, "novo registrador {} := {}", register, value)
+            }
+            Edit::Jump { from, to } => {
+                write!(fmtr, "goto {} (era {})", to, from)
+            }
+        }
+    }
+}
+
+/// Uma edição junto com o número de passos dados quando ela foi feita.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EditRecord {
+    /// Passos dados até a edição. Edições não contam passos.
+    #[serde(with = "decimal")]
+    pub steps: BigUint,
+    /// A edição feita.
+    pub edit: Edit,
+}
+
+/// Erro ao editar o estado do interpretador.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditError {
+    /// O registrador a ser alterado não existe.
+    UndefinedRegister(String),
+    /// O registrador a ser criado já existe.
+    DuplicatedRegister(String),
+    /// O nome do registrador a ser criado é vazio ou tem caracteres não
+    /// permitidos.
+    InvalidRegister(String),
+    /// Não há instrução com o rótulo de destino.
+    UndefinedLabel(String),
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EditError::UndefinedRegister(reg_name) => {
+                write!(fmtr, "Registrador {} não existe", reg_name)
+            }
+            EditError::DuplicatedRegister(reg_name) => {
+                write!(fmtr, "Registrador {} já existe", reg_name)
+            }
+            EditError::InvalidRegister(reg_name) => {
+                write!(fmtr, "Registrador \"{}\" é inválido", reg_name)
+            }
+            EditError::UndefinedLabel(label) => {
+                write!(fmtr, "Rótulo {} não existe no programa", label)
+            }
+        }
+    }
+}
+
+impl Error for EditError {}
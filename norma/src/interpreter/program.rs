@@ -1,11 +1,29 @@
 //! Este módulo exporta itens necessários para construir um programa da norma.
 
+#[cfg(test)]
+mod test;
+
+#[cfg(test)]
+pub(crate) mod fixture;
+
+pub mod canonical;
+pub mod equivalence;
+pub mod file;
+
 use indexmap::{map, IndexMap};
 use num_bigint::BigUint;
-use std::fmt;
+use serde::{Deserialize, Serialize};
+use std::{convert::TryFrom, error::Error, fmt};
+
+/// Rótulo de saída dos programas: desviar para ele termina a execução.
+pub const EXIT_LABEL: &str = "0";
 
 /// Um programa da Norma.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// Serializado como a lista de suas instruções, na ordem. A desserialização
+/// rejeita rótulos duplicados.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<Instruction>", into = "Vec<Instruction>")]
 pub struct Program {
     instructions: IndexMap<String, Instruction>,
 }
@@ -30,7 +48,7 @@ impl Program {
     pub fn first_label(&self) -> &str {
         match self.instructions.first() {
             Some((label, _)) => label,
-            None => "0",
+            None => EXIT_LABEL,
         }
     }
 
@@ -40,13 +58,69 @@ impl Program {
     ///
     /// Invoca `panic!()` caso o rótulo esteja duplicado.
     pub fn insert(&mut self, instruction: Instruction) {
+        if let Err(error) = self.try_insert(instruction) {
+            match error {
+                ProgramError::DuplicatedLabel(label) => {
+                    panic!("Duplicated label {}", label)
+                }
+                error => panic!("{}", error),
+            }
+        }
+    }
+
+    /// Insere uma dada instrução no programa. Falha, sem alterar o programa,
+    /// caso o rótulo esteja duplicado.
+    pub fn try_insert(
+        &mut self,
+        instruction: Instruction,
+    ) -> Result<(), ProgramError> {
         match self.instructions.entry(instruction.label().to_owned()) {
             map::Entry::Vacant(entry) => {
                 entry.insert(instruction);
+                Ok(())
+            }
+            map::Entry::Occupied(entry) => {
+                Err(ProgramError::DuplicatedLabel(entry.key().clone()))
+            }
+        }
+    }
+
+    /// Valida as referências do programa antes de executá-lo: todo desvio
+    /// deve ir para uma instrução do programa ou para um dos rótulos de saída
+    /// `exits`, e todo registrador usado deve ser aceito por `is_register`
+    /// (e.g. existir na máquina que vai rodar o programa).
+    ///
+    /// Na execução, qualquer rótulo sem instrução termina o programa; esta
+    /// validação serve para pegar desvios para rótulos digitados errado.
+    pub fn validate<F>(
+        &self,
+        exits: &[&str],
+        mut is_register: F,
+    ) -> Result<(), ProgramError>
+    where
+        F: FnMut(&str) -> bool,
+    {
+        let mut error = None;
+
+        self.collect_labels(|label| {
+            if error.is_none()
+                && !self.is_label_valid(label)
+                && !exits.contains(&label)
+            {
+                error = Some(ProgramError::UndefinedLabel(label.to_owned()));
             }
-            map::Entry::Occupied(_) => {
-                panic!("Duplicated label {}", instruction.label)
+        });
+
+        self.collect_registers(|reg_name| {
+            if error.is_none() && !is_register(reg_name) {
+                error =
+                    Some(ProgramError::UndefinedRegister(reg_name.to_owned()));
             }
+        });
+
+        match error {
+            Some(error) => Err(error),
+            None => Ok(()),
         }
     }
 
@@ -62,6 +136,18 @@ impl Program {
         self.instructions.get(label)
     }
 
+    /// Busca a instrução associada com o dado rótulo, junto com a sua posição
+    /// na ordem de [`Program::instructions`]. Retorna `None` caso o rótulo
+    /// seja inválido (fora do programa).
+    pub fn instruction_full(
+        &self,
+        label: &str,
+    ) -> Option<(usize, &Instruction)> {
+        self.instructions
+            .get_full(label)
+            .map(|(index, _, instruction)| (index, instruction))
+    }
+
     /// Busca a instrução associada com o dado rótulo, e retorna uma referência
     /// mutável para ela. Retorna `None` caso o rótulo seja inválido (fora
     /// do programa).
@@ -103,6 +189,19 @@ impl Program {
         }
     }
 
+    /// Calcula uma impressão digital do programa: o hash FNV-1a de 64 bits da
+    /// sua representação textual. Programas iguais têm a mesma impressão
+    /// digital, o que permite reconhecer se um estado salvo pertence a um
+    /// programa.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hash = 0xcbf29ce484222325u64;
+        for byte in self.to_string().bytes() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        hash
+    }
+
     /// Exporta todas as instruções do programa para serem usadas com JS, no
     /// formato `(label, instruction-data)`. TODO: substituir tuplas por um tipo
     /// próprio da comunicação.
@@ -111,6 +210,24 @@ impl Program {
     }
 }
 
+impl TryFrom<Vec<Instruction>> for Program {
+    type Error = ProgramError;
+
+    fn try_from(instructions: Vec<Instruction>) -> Result<Self, Self::Error> {
+        let mut program = Self::empty();
+        for instruction in instructions {
+            program.try_insert(instruction)?;
+        }
+        Ok(program)
+    }
+}
+
+impl From<Program> for Vec<Instruction> {
+    fn from(program: Program) -> Self {
+        program.instructions.into_iter().map(|(_, instr)| instr).collect()
+    }
+}
+
 impl fmt::Display for Program {
     fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
         for instruction in self.instructions.values() {
@@ -189,8 +306,46 @@ impl<'prog> DoubleEndedIterator for InstructionsMut<'prog> {
 
 impl<'prog> ExactSizeIterator for InstructionsMut<'prog> {}
 
-/// Uma instrução genérica da Norma.
+/// Testa se um nome serve como registrador: não-vazio, formado por letras,
+/// dígitos e `_`.
+pub(crate) fn is_valid_register(reg_name: &str) -> bool {
+    !reg_name.is_empty()
+        && reg_name.chars().all(|character| {
+            character.is_ascii_alphanumeric() || character == '_'
+        })
+}
+
+/// Erro na construção ou validação de um programa.
 #[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgramError {
+    /// Mais de uma instrução com o mesmo rótulo.
+    DuplicatedLabel(String),
+    /// Desvio para um rótulo que não é de instrução nem de saída.
+    UndefinedLabel(String),
+    /// Registrador usado pelo programa que não existe.
+    UndefinedRegister(String),
+}
+
+impl fmt::Display for ProgramError {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProgramError::DuplicatedLabel(label) => {
+                write!(fmtr, "Rótulo {} está duplicado", label)
+            }
+            ProgramError::UndefinedLabel(label) => {
+                write!(fmtr, "Rótulo {} não existe", label)
+            }
+            ProgramError::UndefinedRegister(reg_name) => {
+                write!(fmtr, "Registrador {} não existe", reg_name)
+            }
+        }
+    }
+}
+
+impl Error for ProgramError {}
+
+/// Uma instrução genérica da Norma.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Instruction {
     /// O rótulo identificado essa instrução.
     label: String,
@@ -242,7 +397,8 @@ impl fmt::Display for Instruction {
 }
 
 /// Um tipo específico de instrução.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum InstructionKind {
     /// Uma instrução de operação.
     Operation(Operation),
@@ -305,7 +461,7 @@ impl InstructionKind {
 }
 
 /// Dados de uma instrução de operação.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Operation {
     /// O "core" da operação em si, o tipo específico de operação.
     pub kind: OperationKind,
@@ -348,8 +504,10 @@ impl Operation {
     }
 }
 
-/// O tipo específico do "core" da operação.
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// O tipo específico do "core" da operação. Constantes são serializadas como
+/// strings com o número em decimal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum OperationKind {
     /// Incrementa o registrador do primeiro parâmetro.
     Inc(String),
@@ -359,16 +517,16 @@ pub enum OperationKind {
     Clear(String),
     /// Carrega uma constante (segundo parâmetro) no registrador do primeiro
     /// parâmetro.
-    Load(String, BigUint),
+    Load(String, #[serde(with = "decimal")] BigUint),
     /// Adiciona uma constante (segundo parâmetro) ao registrador do primeiro
     /// parâmetro.
-    AddConst(String, BigUint),
+    AddConst(String, #[serde(with = "decimal")] BigUint),
     /// Adiciona os dois primeiros registradores no primeiro, usando o terceiro
     /// registrador como temporário, que será zerado.
     Add(String, String, String),
     /// Subtrai uma constante (segundo parâmetro) do registrador do primeiro
     /// parâmetro.
-    SubConst(String, BigUint),
+    SubConst(String, #[serde(with = "decimal")] BigUint),
     /// Subtraí o segundo registrador do primeiro e atualiza o primeiro, usando
     /// o terceiro registrador como temporário, que será zerado.
     Sub(String, String, String),
@@ -476,7 +634,7 @@ impl fmt::Display for OperationKind {
 }
 
 /// Dados de uma instrução de teste.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Test {
     /// O "core" do teste, o tipo específico do teste.
     pub kind: TestKind,
@@ -527,20 +685,22 @@ impl Test {
     }
 }
 
-/// O tipo específico do "core" do teste.
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// O tipo específico do "core" do teste. Constantes são serializadas como
+/// strings com o número em decimal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TestKind {
     /// Testa se o primeiro parâmetro (registrador) é zero.
     Zero(String),
     /// Testa se o dado registrador (primeiro parâmetro) é igual a dada
     /// constante (segundo parâmetro).
-    EqualsConst(String, BigUint),
+    EqualsConst(String, #[serde(with = "decimal")] BigUint),
     /// Teste se os dois primeiros registradores são iguais, usando o terceiro
     /// registrador como temporário, que será zerado.
     Equals(String, String, String),
     /// Testa se o dado registrador (primeiro parâmetro) é menor que a dada
     /// constante (segundo parâmetro).
-    LessThanConst(String, BigUint),
+    LessThanConst(String, #[serde(with = "decimal")] BigUint),
     /// Teste se o primeiro registrador é menor que o segundo, usando o
     /// terceiro registrador como temporário, que será zerado.
     LessThan(String, String, String),
@@ -627,3 +787,29 @@ impl fmt::Display for TestKind {
         }
     }
 }
+
+/// Serialização de constantes `BigUint` como strings em decimal, para que
+/// números de qualquer tamanho sejam legíveis e portáveis entre formatos.
+pub(crate) mod decimal {
+    use num_bigint::BigUint;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use std::str::FromStr;
+
+    pub fn serialize<S>(
+        number: &BigUint,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(number)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<BigUint, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let string = String::deserialize(deserializer)?;
+        BigUint::from_str(&string).map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,105 @@
+//! Forma canônica de programas já expandidos, para comparar programas
+//! independentemente dos nomes escolhidos e da ordem em que as instruções
+//! foram escritas.
+//!
+//! A forma canônica:
+//!
+//! - mantém só as instruções alcançáveis a partir da primeira;
+//! - renomeia os rótulos para `1`, `2`, ... na ordem de uma busca em
+//!   profundidade a partir da primeira instrução, seguindo o `goto` de
+//!   operações e, em testes, primeiro o `then` e depois o `else`;
+//! - renomeia todo destino fora do programa para `0`, já que qualquer um
+//!   deles termina a execução;
+//! - renomeia os registradores para `R1`, `R2`, ... na ordem em que aparecem
+//!   pela primeira vez, exceto `X` e `Y`, que são a entrada e a saída.
+//!
+//! Programas que só diferem em nomes, na ordem das instruções ou em código
+//! morto têm a mesma forma canônica, e a forma canônica computa a mesma
+//! função que o programa original.
+
+use super::{Instruction, InstructionKind, Program};
+use indexmap::IndexMap;
+use std::collections::HashMap;
+
+/// Rótulo que substitui todo destino fora do programa.
+const EXIT_LABEL: &str = "0";
+
+/// Calcula a forma canônica do programa.
+pub fn canonicalize(program: &Program) -> Program {
+    let labels = relabel(program);
+    let mut registers = HashMap::new();
+    let mut canonical = Program::empty();
+
+    for (old_label, new_label) in &labels {
+        let instruction = match program.instruction(old_label) {
+            Some(instruction) => instruction,
+            None => continue,
+        };
+
+        let mut kind = map_registers(&instruction.kind, |register| {
+            if register == "X" || register == "Y" {
+                return register.to_owned();
+            }
+            let next = registers.len() + 1;
+            registers
+                .entry(register.to_owned())
+                .or_insert_with(|| format!("R{}", next))
+                .clone()
+        });
+        kind.rename_labels(|label| {
+            *label = match labels.get(label.as_str()) {
+                Some(new_label) => new_label.clone(),
+                None => EXIT_LABEL.to_owned(),
+            };
+        });
+
+        canonical.insert(Instruction::new(new_label.clone(), kind));
+    }
+
+    canonical
+}
+
+/// Numera os rótulos das instruções alcançáveis, na ordem da busca em
+/// profundidade, mapeando cada rótulo original ao novo.
+fn relabel(program: &Program) -> IndexMap<String, String> {
+    let mut labels = IndexMap::new();
+    let mut stack = vec![program.first_label().to_owned()];
+
+    while let Some(label) = stack.pop() {
+        if labels.contains_key(&label) {
+            continue;
+        }
+        let instruction = match program.instruction(&label) {
+            Some(instruction) => instruction,
+            None => continue,
+        };
+
+        let number = labels.len() + 1;
+        labels.insert(label, number.to_string());
+
+        let mut successors = Vec::new();
+        instruction.collect_labels(|next| successors.push(next.to_owned()));
+        stack.extend(successors.into_iter().rev());
+    }
+
+    labels
+}
+
+/// Mapeia os registradores de um tipo de instrução.
+fn map_registers<F>(kind: &InstructionKind, mapper: F) -> InstructionKind
+where
+    F: FnMut(&str) -> String,
+{
+    match kind {
+        InstructionKind::Operation(oper) => {
+            let mut oper = oper.clone();
+            oper.kind = oper.kind.map_registers(mapper);
+            InstructionKind::Operation(oper)
+        }
+        InstructionKind::Test(test) => {
+            let mut test = test.clone();
+            test.kind = test.kind.map_registers(mapper);
+            InstructionKind::Test(test)
+        }
+    }
+}
@@ -0,0 +1,413 @@
+//! Equivalência forte de programas monolíticos, decidida através de
+//! instruções rotuladas compostas.
+//!
+//! Operações e testes são tratados como símbolos não interpretados: duas
+//! instruções só são consideradas iguais se forem exatamente a mesma operação
+//! ou o mesmo teste, com os mesmos registradores. O procedimento segue o visto
+//! em aula:
+//!
+//! 1. cada instrução vira uma instrução rotulada composta `r: (F, r', r'')`,
+//!    com `r' = r''` no caso de operações, e todo destino fora do programa
+//!    vira o rótulo de parada `ε`;
+//! 2. a cadeia de conjuntos `A0 = {ε}`, `Ak+1 = Ak ∪ {r | r: (F, r', r'')` com
+//!    `r' ∈ Ak` ou `r'' ∈ Ak}` identifica os rótulos que nunca alcançam `ε`,
+//!    que são substituídos pelo ciclo infinito `ω: (ciclo, ω, ω)`;
+//! 3. a cadeia de pares `B0 = {(1, 1)}`, `Bk+1 = Bk ∪` pares de sucessores,
+//!    visita os pares de instruções que executam "ao mesmo tempo" nos dois
+//!    programas. Os programas são equivalentes se todo par tem o mesmo
+//!    símbolo, ou se os dois rótulos são `ε`.
+//!
+//! Todas as tabelas intermediárias ficam expostas em [`Analysis`], para
+//! conferir exercícios resolvidos à mão.
+
+#[cfg(test)]
+mod test;
+
+use super::{InstructionKind, OperationKind, Program, TestKind};
+use indexmap::{IndexMap, IndexSet};
+use std::fmt;
+
+/// Rótulo de uma instrução rotulada composta.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Label {
+    /// Rótulo de uma instrução do programa original.
+    Named(String),
+    /// Rótulo de parada, `ε`.
+    Halt,
+    /// Rótulo do ciclo infinito, `ω`.
+    Cycle,
+}
+
+impl fmt::Display for Label {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Label::Named(label) => write!(fmtr, "{}", label),
+            Label::Halt => write!(fmtr, "ε"),
+            Label::Cycle => write!(fmtr, "ω"),
+        }
+    }
+}
+
+/// Símbolo de uma instrução rotulada composta.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Symbol {
+    /// Uma operação, não interpretada.
+    Operation(OperationKind),
+    /// Um teste, não interpretado.
+    Test(TestKind),
+    /// O símbolo `ciclo`, da instrução `ω`.
+    Cycle,
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Symbol::Operation(kind) => write!(fmtr, "{}", kind),
+            Symbol::Test(kind) => write!(fmtr, "{}", kind),
+            Symbol::Cycle => write!(fmtr, "ciclo"),
+        }
+    }
+}
+
+/// Uma instrução rotulada composta, `(F, r', r'')`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Composite {
+    /// O símbolo da instrução.
+    pub symbol: Symbol,
+    /// O próximo rótulo caso o teste seja verdadeiro, ou o próximo rótulo da
+    /// operação.
+    pub first: Label,
+    /// O próximo rótulo caso o teste seja falso, ou o próximo rótulo da
+    /// operação.
+    pub second: Label,
+}
+
+impl fmt::Display for Composite {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmtr, "({}, {}, {})", self.symbol, self.first, self.second)
+    }
+}
+
+/// Um conjunto de instruções rotuladas compostas.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompositeProgram {
+    start: Label,
+    instructions: IndexMap<Label, Composite>,
+}
+
+impl CompositeProgram {
+    /// Traduz um programa já expandido para instruções rotuladas compostas,
+    /// sem ainda identificar ciclos infinitos.
+    pub fn new(program: &Program) -> Self {
+        let to_label = |label: &str| {
+            if program.instruction(label).is_some() {
+                Label::Named(label.to_owned())
+            } else {
+                Label::Halt
+            }
+        };
+
+        let instructions = program
+            .instructions()
+            .map(|instruction| {
+                let composite = match &instruction.kind {
+                    InstructionKind::Operation(oper) => Composite {
+                        symbol: Symbol::Operation(oper.kind.clone()),
+                        first: to_label(&oper.next),
+                        second: to_label(&oper.next),
+                    },
+                    InstructionKind::Test(test) => Composite {
+                        symbol: Symbol::Test(test.kind.clone()),
+                        first: to_label(&test.next_then),
+                        second: to_label(&test.next_else),
+                    },
+                };
+                (Label::Named(instruction.label().to_owned()), composite)
+            })
+            .collect();
+
+        Self { start: to_label(program.first_label()), instructions }
+    }
+
+    /// O rótulo inicial.
+    pub fn start(&self) -> &Label {
+        &self.start
+    }
+
+    /// Busca a instrução com o dado rótulo. `ε` não tem instrução.
+    pub fn instruction(&self, label: &Label) -> Option<&Composite> {
+        self.instructions.get(label)
+    }
+
+    /// Itera sobre as instruções, na ordem.
+    pub fn instructions(&self) -> impl Iterator<Item = (&Label, &Composite)> {
+        self.instructions.iter()
+    }
+
+    /// Calcula a cadeia de conjuntos `A0, A1, ...` até o limite, que é o
+    /// último conjunto. Cada conjunto é listado inteiro, na ordem em que os
+    /// rótulos entraram.
+    pub fn halting_chain(&self) -> Vec<Vec<Label>> {
+        let mut current = IndexSet::new();
+        current.insert(Label::Halt);
+        let mut chain = vec![current.iter().cloned().collect::<Vec<_>>()];
+
+        loop {
+            let next: Vec<_> = self
+                .instructions
+                .iter()
+                .filter(|(label, composite)| {
+                    !current.contains(*label)
+                        && (current.contains(&composite.first)
+                            || current.contains(&composite.second))
+                })
+                .map(|(label, _)| label.clone())
+                .collect();
+            if next.is_empty() {
+                break chain;
+            }
+            current.extend(next);
+            chain.push(current.iter().cloned().collect());
+        }
+    }
+
+    /// Substitui os rótulos fora do limite da cadeia de
+    /// [`halting_chain`](CompositeProgram::halting_chain) por `ω`,
+    /// acrescentando a instrução `ω: (ciclo, ω, ω)` quando necessário.
+    pub fn remove_cycles(&self) -> Self {
+        let limit: IndexSet<_> = self
+            .halting_chain()
+            .pop()
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        let resolve = |label: &Label| {
+            if limit.contains(label) {
+                label.clone()
+            } else {
+                Label::Cycle
+            }
+        };
+
+        let mut instructions: IndexMap<_, _> = self
+            .instructions
+            .iter()
+            .filter(|(label, _)| limit.contains(*label))
+            .map(|(label, composite)| {
+                let composite = Composite {
+                    symbol: composite.symbol.clone(),
+                    first: resolve(&composite.first),
+                    second: resolve(&composite.second),
+                };
+                (label.clone(), composite)
+            })
+            .collect();
+        let start = resolve(&self.start);
+
+        let has_cycle = start == Label::Cycle
+            || instructions.values().any(|composite| {
+                composite.first == Label::Cycle
+                    || composite.second == Label::Cycle
+            });
+        if has_cycle {
+            instructions.insert(
+                Label::Cycle,
+                Composite {
+                    symbol: Symbol::Cycle,
+                    first: Label::Cycle,
+                    second: Label::Cycle,
+                },
+            );
+        }
+
+        Self { start, instructions }
+    }
+}
+
+impl fmt::Display for CompositeProgram {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        for (label, composite) in &self.instructions {
+            writeln!(fmtr, "{}: {}", label, composite)?;
+        }
+        Ok(())
+    }
+}
+
+/// Um passo de um caminho: o símbolo executado e, para testes, o resultado.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// O símbolo executado.
+    pub symbol: Symbol,
+    /// O resultado do teste, ou `None` para operações.
+    pub branch: Option<bool>,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match self.branch {
+            Some(true) => write!(fmtr, "{} verdadeiro", self.symbol),
+            Some(false) => write!(fmtr, "{} falso", self.symbol),
+            None => write!(fmtr, "{}", self.symbol),
+        }
+    }
+}
+
+/// Um caminho que distingue dois programas: depois de executar os mesmos
+/// passos, os programas chegam a instruções com símbolos diferentes, ou só
+/// um deles para.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Difference {
+    /// Os passos executados igualmente pelos dois programas.
+    pub path: Vec<Step>,
+    /// O rótulo alcançado no primeiro programa.
+    pub first: Label,
+    /// O rótulo alcançado no segundo programa.
+    pub second: Label,
+    /// O símbolo do rótulo alcançado no primeiro programa, `None` se é `ε`.
+    pub first_symbol: Option<Symbol>,
+    /// O símbolo do rótulo alcançado no segundo programa, `None` se é `ε`.
+    pub second_symbol: Option<Symbol>,
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(fmtr, "Os programas diferem no início")?;
+        } else {
+            write!(fmtr, "Os programas diferem após ")?;
+            for (i, step) in self.path.iter().enumerate() {
+                if i > 0 {
+                    write!(fmtr, "; ")?;
+                }
+                write!(fmtr, "{}", step)?;
+            }
+        }
+        write!(fmtr, ": o primeiro ")?;
+        describe(fmtr, &self.first, &self.first_symbol)?;
+        write!(fmtr, " e o segundo ")?;
+        describe(fmtr, &self.second, &self.second_symbol)
+    }
+}
+
+/// Descreve o que um programa faz ao chegar num rótulo.
+fn describe(
+    fmtr: &mut fmt::Formatter,
+    label: &Label,
+    symbol: &Option<Symbol>,
+) -> fmt::Result {
+    match symbol {
+        None => write!(fmtr, "para"),
+        Some(Symbol::Cycle) => write!(fmtr, "entra em ciclo infinito"),
+        Some(symbol) => write!(fmtr, "executa {} (rótulo {})", symbol, label),
+    }
+}
+
+/// Resultado da verificação de equivalência forte, com as tabelas
+/// intermediárias.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+    /// Instruções rotuladas compostas do primeiro programa, já sem ciclos
+    /// infinitos.
+    pub first: CompositeProgram,
+    /// Instruções rotuladas compostas do segundo programa, já sem ciclos
+    /// infinitos.
+    pub second: CompositeProgram,
+    /// Cadeia de conjuntos do primeiro programa.
+    pub first_chain: Vec<Vec<Label>>,
+    /// Cadeia de conjuntos do segundo programa.
+    pub second_chain: Vec<Vec<Label>>,
+    /// Cadeia de pares `B0, B1, ...`, listados inteiros. Termina no limite ou
+    /// no conjunto em que a diferença foi encontrada.
+    pub pairs: Vec<Vec<Pair>>,
+    /// Caminho que distingue os programas, se não forem equivalentes.
+    pub difference: Option<Difference>,
+}
+
+impl Analysis {
+    /// Retorna se os programas são fortemente equivalentes.
+    pub fn equivalent(&self) -> bool {
+        self.difference.is_none()
+    }
+}
+
+/// Decide se dois programas já expandidos são fortemente equivalentes.
+pub fn analyze(first: &Program, second: &Program) -> Analysis {
+    let first = CompositeProgram::new(first);
+    let second = CompositeProgram::new(second);
+    let first_chain = first.halting_chain();
+    let second_chain = second.halting_chain();
+    let first = first.remove_cycles();
+    let second = second.remove_cycles();
+
+    let start = (first.start.clone(), second.start.clone());
+    let mut parents = IndexMap::new();
+    parents.insert(start.clone(), None);
+    let mut pairs = vec![vec![start.clone()]];
+    let mut frontier = vec![start];
+    let mut difference = None;
+
+    'chain: while !frontier.is_empty() {
+        let mut next = Vec::new();
+        for pair in &frontier {
+            let (left, right) =
+                (first.instruction(&pair.0), second.instruction(&pair.1));
+            let (left, right) = match (left, right) {
+                (None, None) => continue,
+                (Some(left), Some(right)) if left.symbol == right.symbol => {
+                    (left, right)
+                }
+                _ => {
+                    difference = Some(Difference {
+                        path: path_to(&parents, pair),
+                        first: pair.0.clone(),
+                        second: pair.1.clone(),
+                        first_symbol: left.map(|left| left.symbol.clone()),
+                        second_symbol: right.map(|right| right.symbol.clone()),
+                    });
+                    break 'chain;
+                }
+            };
+
+            let successors = match left.symbol {
+                Symbol::Test(_) => vec![
+                    (Some(true), (left.first.clone(), right.first.clone())),
+                    (Some(false), (left.second.clone(), right.second.clone())),
+                ],
+                _ => vec![(None, (left.first.clone(), right.first.clone()))],
+            };
+            for (branch, successor) in successors {
+                if !parents.contains_key(&successor) {
+                    let step = Step { symbol: left.symbol.clone(), branch };
+                    parents
+                        .insert(successor.clone(), Some((pair.clone(), step)));
+                    next.push(successor);
+                }
+            }
+        }
+
+        if !next.is_empty() {
+            pairs.push(parents.keys().cloned().collect());
+        }
+        frontier = next;
+    }
+
+    Analysis { first, second, first_chain, second_chain, pairs, difference }
+}
+
+/// Par de rótulos, um de cada programa.
+pub type Pair = (Label, Label);
+
+/// Reconstrói o caminho do par inicial até o par dado.
+fn path_to(
+    parents: &IndexMap<Pair, Option<(Pair, Step)>>,
+    pair: &Pair,
+) -> Vec<Step> {
+    let mut path = Vec::new();
+    let mut current = pair;
+    while let Some(Some((parent, step))) = parents.get(current) {
+        path.push(step.clone());
+        current = parent;
+    }
+    path.reverse();
+    path
+}
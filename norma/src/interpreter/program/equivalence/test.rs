@@ -0,0 +1,141 @@
+use super::{analyze, CompositeProgram, Label, Step, Symbol};
+use crate::{
+    compiler::compile,
+    interpreter::program::{OperationKind, TestKind},
+};
+
+/// Zera X.
+const CLEAR: &str = "main {
+    1: if zero X then goto 0 else goto 2
+    2: do dec X goto 1
+}";
+
+/// Zera X, com o laço desenrolado uma vez.
+const UNROLLED: &str = "main {
+    1: if zero X then goto 0 else goto 2
+    2: do dec X goto 3
+    3: if zero X then goto 0 else goto 4
+    4: do dec X goto 1
+}";
+
+fn named(label: &str) -> Label {
+    Label::Named(label.to_owned())
+}
+
+#[test]
+fn composite_instructions_and_cycles() {
+    let program = compile(
+        "main {
+    1: if zero X then goto 0 else goto 2
+    2: do inc Y goto 3
+    3: if zero Y then goto 2 else goto 2
+}",
+    )
+    .unwrap();
+
+    let composite = CompositeProgram::new(&program);
+    assert_eq!(
+        composite.to_string(),
+        "1: (zero X, ε, 2)
+2: (inc Y, 3, 3)
+3: (zero Y, 2, 2)
+"
+    );
+    assert_eq!(
+        composite.halting_chain(),
+        vec![vec![Label::Halt], vec![Label::Halt, named("1")]]
+    );
+    assert_eq!(
+        composite.remove_cycles().to_string(),
+        "1: (zero X, ε, ω)
+ω: (ciclo, ω, ω)
+"
+    );
+}
+
+#[test]
+fn equivalent_programs() {
+    let analysis =
+        analyze(&compile(CLEAR).unwrap(), &compile(UNROLLED).unwrap());
+
+    assert!(analysis.equivalent());
+    assert_eq!(
+        analysis.pairs,
+        vec![
+            vec![(named("1"), named("1"))],
+            vec![
+                (named("1"), named("1")),
+                (Label::Halt, Label::Halt),
+                (named("2"), named("2")),
+            ],
+            vec![
+                (named("1"), named("1")),
+                (Label::Halt, Label::Halt),
+                (named("2"), named("2")),
+                (named("1"), named("3")),
+            ],
+            vec![
+                (named("1"), named("1")),
+                (Label::Halt, Label::Halt),
+                (named("2"), named("2")),
+                (named("1"), named("3")),
+                (named("2"), named("4")),
+            ],
+        ]
+    );
+}
+
+#[test]
+fn distinguishing_path() {
+    let analysis = analyze(
+        &compile(CLEAR).unwrap(),
+        &compile(
+            "main {
+    1: if zero X then goto 0 else goto 2
+    2: do dec X goto 3
+    3: do inc Y goto 1
+}",
+        )
+        .unwrap(),
+    );
+
+    assert!(!analysis.equivalent());
+    let difference = analysis.difference.unwrap();
+    assert_eq!(
+        difference.path,
+        vec![
+            Step {
+                symbol: Symbol::Test(TestKind::Zero("X".to_owned())),
+                branch: Some(false),
+            },
+            Step {
+                symbol: Symbol::Operation(OperationKind::Dec("X".to_owned())),
+                branch: None,
+            },
+        ]
+    );
+    assert_eq!(
+        difference.to_string(),
+        "Os programas diferem após zero X falso; dec X: o primeiro executa \
+         zero X (rótulo 1) e o segundo executa inc Y (rótulo 3)"
+    );
+}
+
+#[test]
+fn infinite_cycles_are_equivalent() {
+    let spin =
+        compile("main { 1: if zero X then goto 1 else goto 1 }").unwrap();
+    let count = compile("main { 1: do inc Y goto 1 }").unwrap();
+    let analysis = analyze(&spin, &count);
+    assert!(analysis.equivalent());
+    assert_eq!(analysis.first_chain, vec![vec![Label::Halt]]);
+    assert_eq!(analysis.first.start(), &Label::Cycle);
+
+    let halt = compile("main { 1: do inc Y goto 0 }").unwrap();
+    let difference = analyze(&halt, &count).difference.unwrap();
+    assert_eq!(
+        difference.to_string(),
+        "Os programas diferem no início: o primeiro executa inc Y (rótulo 1) \
+         e o segundo entra em ciclo infinito"
+    );
+}
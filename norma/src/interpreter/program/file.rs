@@ -0,0 +1,141 @@
+//! Formato de arquivo para programas da Norma já compilados.
+//!
+//! Compilar um programa com muitas macros é a parte lenta do processo; com
+//! este formato, o programa é compilado uma vez, salvo, e depois carregado
+//! diretamente em `Interpreter::new`.
+//!
+//! O arquivo é um objeto JSON com três campos:
+//!
+//! - `format`: sempre a string `"norma-program"`;
+//! - `version`: a versão do formato, atualmente `1`;
+//! - `program`: a lista de instruções, na ordem do programa (a primeira
+//!   instrução é o ponto de entrada).
+//!
+//! Cada instrução tem um `label` e um `kind`, que é `{"operation": {...}}` ou
+//! `{"test": {...}}`. Uma operação tem `kind` e `next`; um teste tem `kind`,
+//! `next_then` e `next_else`. O `kind` de operações e testes é um objeto com
+//! uma única chave, o nome do tipo em `snake_case`, cujo valor são os
+//! parâmetros: uma string para um único registrador, ou uma lista para vários
+//! parâmetros. Constantes são strings com o número em decimal, para não
+//! perderem precisão. Por exemplo:
+//!
+//! ```json
+//! {
+//!   "format": "norma-program",
+//!   "version": 1,
+//!   "program": [
+//!     {
+//!       "label": "1",
+//!       "kind": {
+//!         "test": {
+//!           "kind": { "zero": "X" },
+//!           "next_then": "0",
+//!           "next_else": "2"
+//!         }
+//!       }
+//!     },
+//!     {
+//!       "label": "2",
+//!       "kind": {
+//!         "operation": {
+//!           "kind": { "add_const": ["Y", "10"] },
+//!           "next": "0"
+//!         }
+//!       }
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Os tipos de operação são `inc`, `dec`, `clear`, `load`, `add_const`,
+//! `add`, `sub_const` e `sub`; os de teste são `zero`, `equals_const`,
+//! `equals`, `less_than_const` e `less_than`, com os mesmos parâmetros de
+//! [`OperationKind`](super::OperationKind) e [`TestKind`](super::TestKind).
+
+use super::Program;
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fmt};
+
+/// Identificador do formato, guardado no campo `format`.
+pub const FORMAT: &str = "norma-program";
+
+/// Versão atual do formato, guardada no campo `version`.
+pub const VERSION: u64 = 1;
+
+/// Cabeçalho do arquivo, lido antes do resto para checar formato e versão.
+#[derive(Debug, Clone, Deserialize)]
+struct Header {
+    format: String,
+    version: u64,
+}
+
+/// Arquivo completo, na versão atual.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct File<P> {
+    format: String,
+    version: u64,
+    program: P,
+}
+
+/// Serializa o programa no formato de arquivo, em JSON indentado.
+pub fn to_json(program: &Program) -> String {
+    let file = File { format: FORMAT.to_owned(), version: VERSION, program };
+    serde_json::to_string_pretty(&file)
+        .expect("programs should always be serializable")
+}
+
+/// Carrega um programa a partir do JSON no formato de arquivo, checando o
+/// formato e a versão.
+pub fn from_json(json: &str) -> Result<Program, FileError> {
+    let header: Header = serde_json::from_str(json).map_err(FileError::Json)?;
+
+    if header.format != FORMAT {
+        return Err(FileError::UnknownFormat(header.format));
+    }
+    if header.version != VERSION {
+        return Err(FileError::UnsupportedVersion(header.version));
+    }
+
+    let file: File<Program> =
+        serde_json::from_str(json).map_err(FileError::Json)?;
+    Ok(file.program)
+}
+
+/// Erro ao carregar um programa de um arquivo.
+#[derive(Debug)]
+pub enum FileError {
+    /// O JSON é inválido, ou não descreve um programa (incluindo programas
+    /// com rótulos duplicados).
+    Json(serde_json::Error),
+    /// O campo `format` não identifica um programa da Norma.
+    UnknownFormat(String),
+    /// A versão do formato não é suportada.
+    UnsupportedVersion(u64),
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FileError::Json(error) => {
+                write!(fmtr, "Arquivo de programa inválido: {}", error)
+            }
+            FileError::UnknownFormat(format) => {
+                write!(fmtr, "Formato de arquivo desconhecido: {}", format)
+            }
+            FileError::UnsupportedVersion(version) => write!(
+                fmtr,
+                "Versão {} do formato não é suportada, esperava-se {}",
+                version, VERSION
+            ),
+        }
+    }
+}
+
+impl Error for FileError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FileError::Json(error) => Some(error),
+            _ => None,
+        }
+    }
+}
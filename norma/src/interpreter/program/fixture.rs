@@ -0,0 +1,34 @@
+//! Construtores de instruções usados pelos testes de vários módulos.
+
+use super::{
+    Instruction, InstructionKind, Operation, OperationKind, Test, TestKind,
+};
+
+/// Nome de registrador a partir de um literal.
+pub fn reg(name: &str) -> String {
+    name.to_owned()
+}
+
+/// Instrução de operação com o rótulo e o destino dados.
+pub fn operation(label: &str, kind: OperationKind, next: &str) -> Instruction {
+    let next = next.to_owned();
+    Instruction::new(
+        label.to_owned(),
+        InstructionKind::Operation(Operation { kind, next }),
+    )
+}
+
+/// Instrução de teste com o rótulo e os destinos dados.
+pub fn test(
+    label: &str,
+    kind: TestKind,
+    then: &str,
+    otherwise: &str,
+) -> Instruction {
+    let test = Test {
+        kind,
+        next_then: then.to_owned(),
+        next_else: otherwise.to_owned(),
+    };
+    Instruction::new(label.to_owned(), InstructionKind::Test(test))
+}
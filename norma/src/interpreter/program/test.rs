@@ -0,0 +1,246 @@
+use super::{
+    canonical::canonicalize,
+    file::{self, FileError},
+    Instruction, InstructionKind, Operation, OperationKind, Program,
+    ProgramError, Test, TestKind,
+};
+use crate::{compiler::compile, interpreter::Interpreter};
+use num_bigint::BigUint;
+use std::{convert::TryFrom, str::FromStr};
+
+fn sample_program() -> Program {
+    let mut program = Program::empty();
+    program.insert(Instruction::new(
+        String::from("1"),
+        InstructionKind::Test(Test {
+            kind: TestKind::Zero(String::from("X")),
+            next_then: String::from("0"),
+            next_else: String::from("2"),
+        }),
+    ));
+    program.insert(Instruction::new(
+        String::from("2"),
+        InstructionKind::Operation(Operation {
+            kind: OperationKind::AddConst(
+                String::from("Y"),
+                BigUint::from_str("123456789012345678901234567890").unwrap(),
+            ),
+            next: String::from("3"),
+        }),
+    ));
+    program.insert(Instruction::new(
+        String::from("3"),
+        InstructionKind::Test(Test {
+            kind: TestKind::LessThan(
+                String::from("X"),
+                String::from("Y"),
+                String::from("T"),
+            ),
+            next_then: String::from("0"),
+            next_else: String::from("1"),
+        }),
+    ));
+    program
+}
+
+#[test]
+fn serialize_instructions() {
+    let json = serde_json::to_value(sample_program()).unwrap();
+
+    assert_eq!(
+        json,
+        serde_json::json!([
+            {
+                "label": "1",
+                "kind": {
+                    "test": {
+                        "kind": { "zero": "X" },
+                        "next_then": "0",
+                        "next_else": "2"
+                    }
+                }
+            },
+            {
+                "label": "2",
+                "kind": {
+                    "operation": {
+                        "kind": {
+                            "add_const": ["Y", "123456789012345678901234567890"]
+                        },
+                        "next": "3"
+                    }
+                }
+            },
+            {
+                "label": "3",
+                "kind": {
+                    "test": {
+                        "kind": { "less_than": ["X", "Y", "T"] },
+                        "next_then": "0",
+                        "next_else": "1"
+                    }
+                }
+            }
+        ])
+    );
+}
+
+#[test]
+fn file_round_trip() {
+    let program = sample_program();
+    let json = file::to_json(&program);
+
+    assert!(json.contains("\"format\": \"norma-program\""));
+    assert!(json.contains("\"version\": 1"));
+    assert_eq!(file::from_json(&json).unwrap(), program);
+}
+
+#[test]
+fn load_compiled_into_interpreter() {
+    let source = "operation clear (A) {
+        1: if zero A then goto 0 else goto 2
+        2: do dec A goto 1
+    }
+
+    main {
+        1: do clear (Y) goto 2
+        2: if zero X then goto 0 else goto 3
+        3: do dec X goto 4
+        4: do inc Y goto 2
+    }";
+    let program = compile(source).unwrap();
+    let loaded = file::from_json(&file::to_json(&program)).unwrap();
+    assert_eq!(loaded, program);
+
+    let mut interpreter = Interpreter::new(loaded);
+    interpreter.input(BigUint::from(5u8));
+    interpreter.run_all();
+    assert_eq!(interpreter.output(), BigUint::from(5u8));
+}
+
+#[test]
+fn reject_duplicated_label() {
+    let mut instructions: Vec<Instruction> = sample_program().into();
+    instructions.push(instructions[0].clone());
+
+    let error = Program::try_from(instructions).unwrap_err();
+    assert_eq!(error.to_string(), "Rótulo 1 está duplicado");
+
+    let json = r#"{
+        "format": "norma-program",
+        "version": 1,
+        "program": [
+            { "label": "1", "kind": { "operation": { "kind": { "inc": "X" }, "next": "1" } } },
+            { "label": "1", "kind": { "operation": { "kind": { "dec": "X" }, "next": "1" } } }
+        ]
+    }"#;
+    assert!(matches!(file::from_json(json), Err(FileError::Json(_))));
+}
+
+#[test]
+fn reject_unknown_format_and_version() {
+    let error =
+        file::from_json(r#"{"format": "other", "version": 1, "program": []}"#)
+            .unwrap_err();
+    assert_eq!(error.to_string(), "Formato de arquivo desconhecido: other");
+
+    let error = file::from_json(
+        r#"{"format": "norma-program", "version": 2, "program": []}"#,
+    )
+    .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Versão 2 do formato não é suportada, esperava-se 1"
+    );
+}
+
+#[test]
+fn reject_invalid_constant() {
+    let json = r#"{
+        "format": "norma-program",
+        "version": 1,
+        "program": [
+            { "label": "1", "kind": { "operation": { "kind": { "load": ["X", "-1"] }, "next": "0" } } }
+        ]
+    }"#;
+    assert!(matches!(file::from_json(json), Err(FileError::Json(_))));
+}
+
+#[test]
+fn try_insert_and_validate() {
+    let mut program = sample_program();
+    let duplicated = program.instructions().next().unwrap().clone();
+    assert_eq!(
+        program.try_insert(duplicated),
+        Err(ProgramError::DuplicatedLabel(String::from("1")))
+    );
+    let registers = ["X", "Y", "T"];
+    let is_register = |name: &str| registers.contains(&name);
+    assert_eq!(program.validate(&["0"], is_register), Ok(()));
+
+    // Nomes fora do que o código-fonte aceita são válidos se existirem.
+    program.insert(Instruction::new(
+        String::from("4 a"),
+        InstructionKind::Operation(Operation {
+            kind: OperationKind::Inc(String::from("A B")),
+            next: String::from("0"),
+        }),
+    ));
+    assert_eq!(program.validate(&["0"], |_| true), Ok(()));
+    let error = program.validate(&["0"], is_register).unwrap_err();
+    assert_eq!(error, ProgramError::UndefinedRegister(String::from("A B")));
+    assert_eq!(error.to_string(), "Registrador A B não existe");
+
+    let mut program = sample_program();
+    program.insert(Instruction::new(
+        String::from("4"),
+        InstructionKind::Operation(Operation {
+            kind: OperationKind::Inc(String::from("X")),
+            next: String::from("5"),
+        }),
+    ));
+    let error = program.validate(&["0"], is_register).unwrap_err();
+    assert_eq!(error, ProgramError::UndefinedLabel(String::from("5")));
+    assert_eq!(error.to_string(), "Rótulo 5 não existe");
+    assert_eq!(program.validate(&["0", "5"], is_register), Ok(()));
+}
+
+#[test]
+fn canonical_form() {
+    let original = compile(
+        "main {
+        1: if zero X then goto 0 else goto 2
+        2: do dec X goto 3
+        3: do inc A goto 4
+        4: do inc Y goto 1
+    }",
+    )
+    .unwrap();
+    let renamed = compile(
+        "main {
+        start: if zero X then goto end else goto loop
+        add: do inc Tmp goto output
+        dead: do inc Y goto start
+        loop: do dec X goto add
+        output: do inc Y goto start
+    }",
+    )
+    .unwrap();
+
+    let canonical = canonicalize(&original);
+    assert_eq!(canonical, canonicalize(&renamed));
+    assert_eq!(canonical, canonicalize(&canonical));
+    assert_eq!(
+        canonical.to_string(),
+        "1: if zero X then goto 0 else goto 2
+2: do dec X goto 3
+3: do inc R1 goto 4
+4: do inc Y goto 1
+"
+    );
+
+    let mut interpreter = Interpreter::new(canonicalize(&renamed));
+    interpreter.input(BigUint::from(3u8));
+    interpreter.run_all();
+    assert_eq!(interpreter.output(), BigUint::from(3u8));
+}
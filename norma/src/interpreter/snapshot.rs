@@ -0,0 +1,82 @@
+//! Este módulo define o estado salvo de uma execução, que pode ser
+//! serializado e depois carregado de volta no interpretador.
+
+use super::{edit::EditRecord, program::decimal};
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fmt};
+
+/// Estado salvo de uma execução: rótulo atual, registradores, passos dados,
+/// edições feitas à mão e a impressão digital do programa, para que o estado
+/// só seja carregado de volta no mesmo programa.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Rótulo da instrução atual.
+    pub current: String,
+    /// Todos os registradores da máquina, na ordem da máquina.
+    pub registers: Vec<SavedRegister>,
+    /// Passos dados.
+    #[serde(with = "decimal")]
+    pub steps: BigUint,
+    /// Edições feitas à mão durante a execução. Ausente em estados salvos
+    /// antes de existirem edições.
+    #[serde(default)]
+    pub edits: Vec<EditRecord>,
+    /// Impressão digital do programa (ver `Program::fingerprint`).
+    /// Serializada como string hexadecimal, já que JSON não representa
+    /// inteiros de 64 bits com precisão.
+    #[serde(with = "hex")]
+    pub fingerprint: u64,
+}
+
+/// Um registrador salvo.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedRegister {
+    /// Nome do registrador.
+    pub name: String,
+    /// Valor do registrador.
+    #[serde(with = "decimal")]
+    pub value: BigUint,
+}
+
+/// Erro ao carregar um estado salvo de outro programa.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramMismatch {
+    /// Impressão digital do programa sendo executado.
+    pub expected: u64,
+    /// Impressão digital guardada no estado salvo.
+    pub found: u64,
+}
+
+impl fmt::Display for ProgramMismatch {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmtr,
+            "Estado salvo pertence a outro programa (impressão digital \
+             {:016x}, esperava-se {:016x})",
+            self.found, self.expected
+        )
+    }
+}
+
+impl Error for ProgramMismatch {}
+
+/// Serialização de `u64` como string hexadecimal.
+mod hex {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(number: &u64, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(&format_args!("{:016x}", number))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<u64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let string = String::deserialize(deserializer)?;
+        u64::from_str_radix(&string, 16).map_err(de::Error::custom)
+    }
+}
@@ -1,11 +1,12 @@
 use super::{
+    edit::{Edit, EditError, EditRecord},
     program::{
-        Instruction, InstructionKind, Operation, OperationKind, Program, Test,
-        TestKind,
+        Instruction, InstructionKind, Operation, OperationKind, Program,
+        ProgramError, Test, TestKind,
     },
-    Interpreter,
+    Interpreter, RunError,
 };
-use crate::machine::Machine;
+use crate::machine::{Machine, MachineError};
 use num_bigint::BigUint;
 use num_traits::{One, Zero};
 
@@ -62,9 +63,8 @@ fn collect_registers() {
     assert_eq!(machine.get_value("C"), BigUint::zero());
     assert_eq!(machine.get_value("assim"), BigUint::zero());
 
-    let mut names = machine.register_names().collect::<Vec<_>>();
-    names.sort();
-    assert_eq!(names, &["A", "B", "C", "X", "Y", "assim"]);
+    let names = machine.register_names().collect::<Vec<_>>();
+    assert_eq!(names, &["X", "Y", "A", "B", "C", "assim"]);
 }
 
 #[test]
@@ -323,3 +323,344 @@ fn x_square() {
     interpreter.run_all();
     assert_eq!(interpreter.output(), BigUint::from(400u16));
 }
+
+fn double_program() -> Program {
+    let mut program = Program::empty();
+
+    program.insert(Instruction::new(
+        String::from("1"),
+        InstructionKind::Test(Test {
+            kind: TestKind::Zero(String::from("X")),
+            next_then: String::from("0"),
+            next_else: String::from("2"),
+        }),
+    ));
+    program.insert(Instruction::new(
+        String::from("2"),
+        InstructionKind::Operation(Operation {
+            kind: OperationKind::Dec(String::from("X")),
+            next: String::from("3"),
+        }),
+    ));
+    program.insert(Instruction::new(
+        String::from("3"),
+        InstructionKind::Operation(Operation {
+            kind: OperationKind::AddConst(
+                String::from("Y"),
+                BigUint::from(2u8),
+            ),
+            next: String::from("1"),
+        }),
+    ));
+
+    program
+}
+
+#[test]
+fn snapshot_and_restore() {
+    let mut interpreter = Interpreter::new(double_program());
+    interpreter.input(BigUint::from(5u8));
+    interpreter.run_steps(7);
+
+    let snapshot = interpreter.snapshot();
+    assert_eq!(snapshot.current, "2");
+    assert_eq!(snapshot.steps, BigUint::from(9u8));
+    let names: Vec<_> =
+        snapshot.registers.iter().map(|reg| reg.name.as_str()).collect();
+    assert_eq!(names, &["X", "Y"]);
+
+    let json = serde_json::to_string(&snapshot).unwrap();
+    let loaded = serde_json::from_str(&json).unwrap();
+    assert_eq!(snapshot, loaded);
+
+    let mut resumed = Interpreter::new(double_program());
+    resumed.restore(loaded).unwrap();
+    assert_eq!(resumed.current_label(), "2");
+    assert_eq!(resumed.machine().get_value("X"), BigUint::from(3u8));
+    assert_eq!(resumed.machine().get_value("Y"), BigUint::from(4u8));
+
+    resumed.run_all();
+    interpreter.run_all();
+    assert_eq!(resumed.output(), BigUint::from(10u8));
+    assert_eq!(resumed.steps(), interpreter.steps());
+}
+
+#[test]
+fn restore_rejects_other_program() {
+    let snapshot = Interpreter::new(double_program()).snapshot();
+
+    let mut program = double_program();
+    program.insert(Instruction::new(
+        String::from("4"),
+        InstructionKind::Operation(Operation {
+            kind: OperationKind::Inc(String::from("Y")),
+            next: String::from("0"),
+        }),
+    ));
+    let mut interpreter = Interpreter::new(program);
+    interpreter.input(BigUint::one());
+
+    let error = interpreter.restore(snapshot).unwrap_err();
+    assert_ne!(error.expected, error.found);
+    assert_eq!(interpreter.machine().get_value("X"), BigUint::one());
+}
+
+#[test]
+fn machine_from_registers() {
+    let mut machine = Machine::from_registers(vec![
+        (String::from("A"), BigUint::from(3u8)),
+        (String::from("X"), BigUint::from(2u8)),
+    ]);
+
+    let registers: Vec<_> = machine
+        .registers()
+        .map(|(name, value)| (name.to_owned(), value.to_biguint()))
+        .collect();
+    assert_eq!(
+        registers,
+        &[
+            (String::from("X"), BigUint::from(2u8)),
+            (String::from("Y"), BigUint::zero()),
+            (String::from("A"), BigUint::from(3u8)),
+        ]
+    );
+
+    machine.inc("A");
+    assert_eq!(machine.get_value("A"), BigUint::from(4u8));
+}
+
+fn counter_program() -> Program {
+    let mut program = Program::empty();
+
+    program.insert(Instruction::new(
+        String::from("1"),
+        InstructionKind::Operation(Operation {
+            kind: OperationKind::Dec(String::from("X")),
+            next: String::from("2"),
+        }),
+    ));
+    program.insert(Instruction::new(
+        String::from("2"),
+        InstructionKind::Operation(Operation {
+            kind: OperationKind::Inc(String::from("A")),
+            next: String::from("0"),
+        }),
+    ));
+
+    program
+}
+
+#[test]
+fn undefined_register_is_run_error() {
+    let mut machine = Machine::new(BigUint::from(2u8));
+    let error = Interpreter::try_from_state(
+        String::from("1"),
+        counter_program(),
+        machine.clone(),
+        BigUint::zero(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        error,
+        RunError::Machine(MachineError::UndefinedRegister(String::from("A")))
+    );
+
+    machine.insert("A");
+    let mut interpreter = Interpreter::try_from_state(
+        String::from("1"),
+        counter_program(),
+        machine,
+        BigUint::zero(),
+    )
+    .unwrap();
+    assert_eq!(interpreter.try_run_all(), Ok(()));
+    assert_eq!(interpreter.machine().get_value("A"), BigUint::one());
+}
+
+#[test]
+fn try_run_step_stops_before_undefined_register() {
+    let mut interpreter = Interpreter::from_state(
+        String::from("1"),
+        counter_program(),
+        Machine::new(BigUint::one()),
+        BigUint::zero(),
+    );
+
+    assert_eq!(interpreter.try_run_steps(1), Ok(true));
+    let error = interpreter.try_run_all().unwrap_err();
+    assert_eq!(error, MachineError::UndefinedRegister(String::from("A")));
+    assert_eq!(interpreter.current_label(), "2");
+    assert_eq!(interpreter.steps(), BigUint::one());
+
+    assert_eq!(interpreter.create_register("A", BigUint::zero()), Ok(()));
+    assert_eq!(interpreter.try_run_all(), Ok(()));
+}
+
+#[test]
+fn missing_register_is_run_error() {
+    let mut program = double_program();
+    program.insert(Instruction::new(
+        String::from("4"),
+        InstructionKind::Operation(Operation {
+            kind: OperationKind::Inc(String::from("Extra")),
+            next: String::from("0"),
+        }),
+    ));
+
+    let error = Interpreter::try_from_state(
+        String::from("1"),
+        program,
+        Machine::default(),
+        BigUint::zero(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        error,
+        RunError::Machine(MachineError::UndefinedRegister(String::from(
+            "Extra"
+        )))
+    );
+    assert_eq!(error.to_string(), "Registrador Extra não existe");
+}
+
+#[test]
+fn dangling_label_is_run_error() {
+    let mut program = double_program();
+    program.insert(Instruction::new(
+        String::from("4"),
+        InstructionKind::Operation(Operation {
+            kind: OperationKind::Inc(String::from("X")),
+            next: String::from("fim"),
+        }),
+    ));
+
+    let error = Interpreter::try_from_state(
+        String::from("1"),
+        program,
+        Machine::default(),
+        BigUint::zero(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        error,
+        RunError::Program(ProgramError::UndefinedLabel(String::from("fim")))
+    );
+}
+
+#[test]
+fn registers_in_source_order() {
+    let source = "main {
+        1: do inc Zeta goto 2
+        2: if zero Alpha then goto 3 else goto 0
+        3: do dec X goto 4
+        4: do inc Mid goto 0
+    }";
+    let interpreter =
+        Interpreter::new(crate::compiler::compile(source).unwrap());
+    let names: Vec<_> = interpreter.machine().register_names().collect();
+    assert_eq!(names, &["X", "Y", "Zeta", "Alpha", "Mid"]);
+}
+
+#[test]
+fn edit_paused_execution() {
+    let mut interpreter = Interpreter::new(double_program());
+    interpreter.input(BigUint::from(3u8));
+    interpreter.run_steps(3);
+    assert_eq!(interpreter.current_label(), "1");
+
+    interpreter.set_register("X", BigUint::zero()).unwrap();
+    interpreter.create_register("Scratch", BigUint::from(7u8)).unwrap();
+    interpreter.jump_to("3").unwrap();
+    assert_eq!(interpreter.steps(), BigUint::from(4u8));
+
+    assert_eq!(
+        interpreter.edits(),
+        &[
+            EditRecord {
+                steps: BigUint::from(4u8),
+                edit: Edit::SetRegister {
+                    register: String::from("X"),
+                    old: BigUint::from(2u8),
+                    new: BigUint::zero(),
+                },
+            },
+            EditRecord {
+                steps: BigUint::from(4u8),
+                edit: Edit::CreateRegister {
+                    register: String::from("Scratch"),
+                    value: BigUint::from(7u8),
+                },
+            },
+            EditRecord {
+                steps: BigUint::from(4u8),
+                edit: Edit::Jump {
+                    from: String::from("1"),
+                    to: String::from("3")
+                },
+            },
+        ]
+    );
+
+    let mut resumed = Interpreter::new(double_program());
+    resumed.restore(interpreter.snapshot()).unwrap();
+    assert_eq!(resumed.edits(), interpreter.edits());
+    assert_eq!(resumed.machine().get_value("Scratch"), BigUint::from(7u8));
+
+    interpreter.run_all();
+    assert_eq!(interpreter.output(), BigUint::from(4u8));
+
+    interpreter.reset();
+    assert!(interpreter.edits().is_empty());
+}
+
+#[test]
+fn reject_invalid_edits() {
+    let mut interpreter = Interpreter::new(double_program());
+
+    assert_eq!(
+        interpreter.set_register("A", BigUint::one()),
+        Err(EditError::UndefinedRegister(String::from("A")))
+    );
+    assert_eq!(
+        interpreter.create_register("Y", BigUint::one()),
+        Err(EditError::DuplicatedRegister(String::from("Y")))
+    );
+    assert_eq!(
+        interpreter.create_register("a b", BigUint::one()),
+        Err(EditError::InvalidRegister(String::from("a b")))
+    );
+    let error = interpreter.jump_to("0").unwrap_err();
+    assert_eq!(error, EditError::UndefinedLabel(String::from("0")));
+    assert_eq!(error.to_string(), "Rótulo 0 não existe no programa");
+
+    assert_eq!(interpreter.current_label(), "1");
+    assert!(interpreter.edits().is_empty());
+}
+
+#[test]
+fn less_than_zero_is_false() {
+    let mut program = Program::empty();
+    program.insert(Instruction::new(
+        String::from("1"),
+        InstructionKind::Test(Test {
+            kind: TestKind::LessThanConst(String::from("X"), BigUint::zero()),
+            next_then: String::from("0"),
+            next_else: String::from("2"),
+        }),
+    ));
+    program.insert(Instruction::new(
+        String::from("2"),
+        InstructionKind::Operation(Operation {
+            kind: OperationKind::Inc(String::from("Y")),
+            next: String::from("0"),
+        }),
+    ));
+
+    for input in 0..3u8 {
+        let mut interpreter = Interpreter::new(program.clone());
+        interpreter.input(BigUint::from(input));
+        interpreter.run_all();
+        assert_eq!(interpreter.output(), BigUint::one());
+        assert_eq!(interpreter.steps(), BigUint::from(2u8));
+    }
+}
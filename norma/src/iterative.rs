@@ -0,0 +1,249 @@
+//! Programas iterativos, escritos com composição sequencial (`;`), `se T
+//! então V senão W`, `enquanto T faça (V)` e `até T faça (V)`, e a conversão
+//! entre programas iterativos e monolíticos.
+//!
+//! As operações são `inc R` e `dec R` e o teste é `zero R`. Parênteses
+//! agrupam uma sequência, que pode ser vazia: `()`. O `senão` é opcional.
+//! Palavras-chave também podem ser escritas sem acento (`faca`, `entao`,
+//! `senao`, `ate`). Por exemplo, `Y := X`:
+//!
+//! ```text
+//! até zero X faça (
+//!     dec X;
+//!     inc Y
+//! )
+//! ```
+//!
+//! A compilação gera um [`Program`](crate::interpreter::program::Program)
+//! monolítico com rótulos `1`, `2`, ... na ordem do texto e rótulo de saída
+//! `0`. O caminho inverso, de monolítico para iterativo, fica em
+//! [`structure`].
+
+#[cfg(test)]
+mod test;
+
+pub mod error;
+pub mod lexer;
+pub mod structure;
+
+pub(crate) mod parser;
+
+use crate::{
+    block::{self, block_size, entry, Node},
+    interpreter::program::{
+        self as monolithic, Instruction, InstructionKind, Operation,
+        OperationKind, Test, TestKind,
+    },
+};
+use pinguim_language::error::Diagnostics;
+use std::fmt;
+
+/// Rótulo de saída dos programas compilados.
+const EXIT_LABEL: &str = "0";
+
+/// Uma instrução de um programa iterativo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Statement {
+    /// Uma operação.
+    Operation(OperationKind),
+    /// `se test então then senão otherwise`.
+    If {
+        /// O teste.
+        test: TestKind,
+        /// Executado se o teste for verdadeiro.
+        then: Vec<Statement>,
+        /// Executado se o teste for falso.
+        otherwise: Vec<Statement>,
+    },
+    /// `enquanto test faça (body)`: repete enquanto o teste for verdadeiro.
+    While {
+        /// O teste.
+        test: TestKind,
+        /// O corpo do laço.
+        body: Vec<Statement>,
+    },
+    /// `até test faça (body)`: repete até o teste ser verdadeiro.
+    Until {
+        /// O teste.
+        test: TestKind,
+        /// O corpo do laço.
+        body: Vec<Statement>,
+    },
+}
+
+impl Node for Statement {
+    fn size(&self) -> usize {
+        match self {
+            Statement::Operation(_) => 1,
+            Statement::If { then, otherwise, .. } => {
+                1 + block_size(then) + block_size(otherwise)
+            }
+            Statement::While { body, .. } | Statement::Until { body, .. } => {
+                1 + block_size(body)
+            }
+        }
+    }
+}
+
+impl Statement {
+    /// Escreve a instrução com a indentação dada, para o `Display` de
+    /// [`Program`].
+    fn write(&self, fmtr: &mut fmt::Formatter, indent: usize) -> fmt::Result {
+        match self {
+            Statement::Operation(kind) => write!(fmtr, "{}", kind),
+            Statement::If { test, then, otherwise } => {
+                write!(fmtr, "se {} então ", test)?;
+                write_branch(fmtr, then, indent)?;
+                if !otherwise.is_empty() {
+                    write!(fmtr, " senão ")?;
+                    write_branch(fmtr, otherwise, indent)?;
+                }
+                Ok(())
+            }
+            Statement::While { test, body } => {
+                write!(fmtr, "enquanto {} faça ", test)?;
+                write_block(fmtr, body, indent)
+            }
+            Statement::Until { test, body } => {
+                write!(fmtr, "até {} faça ", test)?;
+                write_block(fmtr, body, indent)
+            }
+        }
+    }
+}
+
+/// Um programa iterativo: uma sequência de instruções.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Program {
+    /// As instruções, compostas sequencialmente.
+    pub body: Vec<Statement>,
+}
+
+impl Program {
+    /// Converte para um programa monolítico, com rótulos `1`, `2`, ... na
+    /// ordem do texto e rótulo de saída `0`.
+    pub fn to_monolithic(&self) -> monolithic::Program {
+        let mut program = monolithic::Program::empty();
+        emit_block(&self.body, 1, EXIT_LABEL, &mut program);
+        program
+    }
+}
+
+impl fmt::Display for Program {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        block::write_sequence(fmtr, &self.body, 0, Statement::write)?;
+        if !self.body.is_empty() {
+            writeln!(fmtr)?;
+        }
+        Ok(())
+    }
+}
+
+/// Analisa o código-fonte de um programa iterativo.
+pub fn parse(source: &str) -> Result<Program, Diagnostics> {
+    let mut diagnostics = Diagnostics::new();
+    let tokens = lexer::generate_tokens(source, &mut diagnostics);
+    let maybe_program = parser::parse(tokens, &mut diagnostics);
+
+    match maybe_program {
+        Some(program) if diagnostics.is_ok() => Ok(program),
+        _ => Err(diagnostics),
+    }
+}
+
+/// Compila o código-fonte de um programa iterativo para um programa
+/// monolítico.
+pub fn compile(source: &str) -> Result<monolithic::Program, Diagnostics> {
+    parse(source).map(|program| program.to_monolithic())
+}
+
+/// Gera as instruções de uma sequência a partir do rótulo `start`, saindo
+/// para `next`.
+fn emit_block(
+    block: &[Statement],
+    start: usize,
+    next: &str,
+    program: &mut monolithic::Program,
+) {
+    block::emit_block(block, start, next, |statement, label, next| {
+        emit(statement, label, next, program)
+    });
+}
+
+/// Gera as instruções de uma instrução iterativa a partir do rótulo `start`,
+/// saindo para `next`.
+fn emit(
+    statement: &Statement,
+    start: usize,
+    next: &str,
+    program: &mut monolithic::Program,
+) {
+    let label = start.to_string();
+    let test = |kind: &TestKind, next_then: String, next_else: String| {
+        let test = Test { kind: kind.clone(), next_then, next_else };
+        Instruction::new(label.clone(), InstructionKind::Test(test))
+    };
+
+    match statement {
+        Statement::Operation(kind) => {
+            let oper = Operation { kind: kind.clone(), next: next.to_owned() };
+            program.insert(Instruction::new(
+                label.clone(),
+                InstructionKind::Operation(oper),
+            ));
+        }
+        Statement::If { test: kind, then, otherwise } => {
+            let else_start = start + 1 + block_size(then);
+            program.insert(test(
+                kind,
+                entry(then, start + 1, next),
+                entry(otherwise, else_start, next),
+            ));
+            emit_block(then, start + 1, next, program);
+            emit_block(otherwise, else_start, next, program);
+        }
+        Statement::While { test: kind, body } => {
+            program.insert(test(
+                kind,
+                entry(body, start + 1, &label),
+                next.to_owned(),
+            ));
+            emit_block(body, start + 1, &label, program);
+        }
+        Statement::Until { test: kind, body } => {
+            program.insert(test(
+                kind,
+                next.to_owned(),
+                entry(body, start + 1, &label),
+            ));
+            emit_block(body, start + 1, &label, program);
+        }
+    }
+}
+
+/// Escreve uma sequência entre parênteses.
+fn write_block(
+    fmtr: &mut fmt::Formatter,
+    block: &[Statement],
+    indent: usize,
+) -> fmt::Result {
+    if block.is_empty() {
+        return write!(fmtr, "()");
+    }
+    writeln!(fmtr, "(")?;
+    block::write_sequence(fmtr, block, indent + 1, Statement::write)?;
+    writeln!(fmtr)?;
+    write!(fmtr, "{:width$})", "", width = indent * 4)
+}
+
+/// Escreve um ramo de um `se`: uma operação sozinha fica sem parênteses.
+fn write_branch(
+    fmtr: &mut fmt::Formatter,
+    block: &[Statement],
+    indent: usize,
+) -> fmt::Result {
+    match block {
+        [Statement::Operation(kind)] => write!(fmtr, "{}", kind),
+        _ => write_block(fmtr, block, indent),
+    }
+}
@@ -0,0 +1,67 @@
+use std::{error::Error, fmt};
+
+#[derive(Debug, Clone)]
+/// Erro em que uma palavra ou símbolo diferente do esperado foi encontrado
+pub struct UnexpectedToken {
+    ///
+    /// - `expected`: descrição do que era esperado
+    pub expected: String,
+    ///
+    /// - `found`: o que foi encontrado
+    pub found: String,
+}
+
+impl fmt::Display for UnexpectedToken {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "Esperava-se {}, encontrado \"{}\"",
+            self.expected, self.found
+        )
+    }
+}
+
+impl Error for UnexpectedToken {}
+
+#[derive(Debug, Clone)]
+/// Erro em que o código termina antes do esperado
+pub struct UnexpectedEnd {
+    ///
+    /// - `expected`: descrição do que era esperado
+    pub expected: String,
+}
+
+impl fmt::Display for UnexpectedEnd {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "Esperava-se {}, mas o código terminou",
+            self.expected
+        )
+    }
+}
+
+impl Error for UnexpectedEnd {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Erro em que o fluxograma de um programa monolítico não pode ser escrito
+/// como programa iterativo, só com `se`, `enquanto` e `até`
+pub struct NotStructured {
+    ///
+    /// - `labels`: rótulos das instruções da região que não pôde ser
+    ///   estruturada
+    pub labels: Vec<String>,
+}
+
+impl fmt::Display for NotStructured {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "O fluxograma não pode ser escrito como programa iterativo; \
+             região não estruturável: {}",
+            self.labels.join(", ")
+        )
+    }
+}
+
+impl Error for NotStructured {}
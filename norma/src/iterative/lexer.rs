@@ -0,0 +1,148 @@
+//! Análise léxica de programas iterativos e recursivos, de funções
+//! recursivas parciais e de programas LOOP/WHILE: palavras, parênteses, `;`,
+//! `=`, `,`, `:=`, `+` e `≠`, além de comentários de linha (`//`).
+
+use crate::compiler::lexer::error::InvalidChar;
+use pinguim_language::{
+    error::{Diagnostics, Error},
+    position::Span,
+};
+use std::{iter::Peekable, str};
+
+/// Tipo de um token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenType {
+    /// Uma palavra: palavra-chave, registrador ou identificador.
+    Word,
+    /// `(`.
+    OpenParen,
+    /// `)`.
+    CloseParen,
+    /// `;`.
+    Semicolon,
+    /// `=`, usado nas definições de programas recursivos.
+    Equals,
+    /// `,`, usado entre argumentos das funções recursivas parciais.
+    Comma,
+    /// `:=`, usado nas atribuições dos programas LOOP/WHILE.
+    Assign,
+    /// `+`, usado nas atribuições dos programas LOOP/WHILE.
+    Plus,
+    /// `≠` ou `!=`, usado nos laços `WHILE`.
+    NotEqual,
+}
+
+/// Um token do código-fonte.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// Tipo do token.
+    pub token_type: TokenType,
+    /// Conteúdo do token.
+    pub content: String,
+    /// Localização do token.
+    pub span: Span,
+}
+
+impl Token {
+    /// Retorna se o token é a palavra-chave dada, aceitando também a grafia
+    /// sem acentos.
+    pub fn is_keyword(&self, keyword: &str) -> bool {
+        self.token_type == TokenType::Word
+            && (self.content == keyword
+                || self.content == without_accents(keyword))
+    }
+}
+
+/// Gera os tokens do código-fonte, descartando espaços e comentários.
+pub fn generate_tokens(
+    source: &str,
+    diagnostics: &mut Diagnostics,
+) -> Vec<Token> {
+    let mut chars = source.chars().peekable();
+    let mut span = Span::default();
+    let mut tokens = Vec::new();
+
+    while let Some(&character) = chars.peek() {
+        span.finish();
+        let mut content = String::new();
+        let mut next_char = |chars: &mut Peekable<str::Chars>| {
+            if let Some(character) = chars.next() {
+                span.update(character);
+                content.push(character);
+            }
+        };
+
+        let token_type = if character.is_whitespace() {
+            next_char(&mut chars);
+            None
+        } else if character == '/' {
+            next_char(&mut chars);
+            if chars.peek() == Some(&'/') {
+                while !matches!(chars.peek(), None | Some('\n')) {
+                    next_char(&mut chars);
+                }
+                None
+            } else {
+                Some(Err(character))
+            }
+        } else if is_word_char(character) {
+            while matches!(chars.peek(), Some(&next) if is_word_char(next)) {
+                next_char(&mut chars);
+            }
+            Some(Ok(TokenType::Word))
+        } else {
+            next_char(&mut chars);
+            Some(match character {
+                '(' => Ok(TokenType::OpenParen),
+                ')' => Ok(TokenType::CloseParen),
+                ';' => Ok(TokenType::Semicolon),
+                '=' => Ok(TokenType::Equals),
+                ',' => Ok(TokenType::Comma),
+                '+' => Ok(TokenType::Plus),
+                '≠' => Ok(TokenType::NotEqual),
+                ':' | '!' if chars.peek() == Some(&'=') => {
+                    next_char(&mut chars);
+                    if character == ':' {
+                        Ok(TokenType::Assign)
+                    } else {
+                        Ok(TokenType::NotEqual)
+                    }
+                }
+                _ => Err(character),
+            })
+        };
+
+        match token_type {
+            Some(Ok(token_type)) => {
+                tokens.push(Token { token_type, content, span })
+            }
+            Some(Err(character)) => {
+                diagnostics.raise(Error::new(InvalidChar { character }, span))
+            }
+            None => (),
+        }
+    }
+
+    tokens
+}
+
+/// Retorna se o caractere pode fazer parte de uma palavra.
+fn is_word_char(character: char) -> bool {
+    character.is_alphanumeric() || character == '_'
+}
+
+/// Remove os acentos das palavras-chave.
+fn without_accents(keyword: &str) -> String {
+    keyword
+        .chars()
+        .map(|character| match character {
+            'á' | 'ã' | 'â' => 'a',
+            'é' | 'ê' => 'e',
+            'í' => 'i',
+            'ó' | 'õ' | 'ô' => 'o',
+            'ú' => 'u',
+            'ç' => 'c',
+            other => other,
+        })
+        .collect()
+}
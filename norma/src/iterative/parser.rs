@@ -0,0 +1,213 @@
+use super::{
+    error::{UnexpectedEnd, UnexpectedToken},
+    lexer::{Token, TokenType},
+    Program, Statement,
+};
+use crate::interpreter::program::{OperationKind, TestKind};
+use pinguim_language::error::{Diagnostics, Error};
+use std::error::Error as StdError;
+
+/// Palavras que não podem ser usadas como registrador.
+pub(crate) const RESERVED: &[&str] =
+    &["se", "então", "senão", "enquanto", "até", "faça", "inc", "dec", "zero"];
+
+/// Analisa os tokens de um programa iterativo. Para no primeiro erro.
+pub fn parse(
+    tokens: Vec<Token>,
+    diagnostics: &mut Diagnostics,
+) -> Option<Program> {
+    let mut parser = Parser { tokens, position: 0 };
+    let result = parser.parse_sequence().and_then(|body| {
+        match parser.tokens.get(parser.position) {
+            Some(token) => Err(parser.unexpected(token, "\";\" ou o fim")),
+            None => Ok(Program { body }),
+        }
+    });
+
+    match result {
+        Ok(program) => Some(program),
+        Err(error) => {
+            diagnostics.raise(error);
+            None
+        }
+    }
+}
+
+/// Analisador sintático de programas iterativos, por descida recursiva.
+pub(crate) struct Parser {
+    pub(crate) tokens: Vec<Token>,
+    pub(crate) position: usize,
+}
+
+impl Parser {
+    /// Analisa uma sequência de instruções separadas por `;`, possivelmente
+    /// vazia, até um `)` ou o fim.
+    fn parse_sequence(&mut self) -> Result<Vec<Statement>, Error> {
+        let mut block = Vec::new();
+        if self.at_block_end() {
+            return Ok(block);
+        }
+
+        loop {
+            block.extend(self.parse_statement()?);
+            if self.check(TokenType::Semicolon) {
+                self.position += 1;
+            } else {
+                break Ok(block);
+            }
+        }
+    }
+
+    /// Analisa uma instrução. Uma sequência entre parênteses conta como uma
+    /// instrução, por isso o retorno é uma sequência.
+    fn parse_statement(&mut self) -> Result<Vec<Statement>, Error> {
+        let token = self.next("uma instrução")?;
+
+        if token.token_type == TokenType::OpenParen {
+            let block = self.parse_sequence()?;
+            self.expect_type(TokenType::CloseParen, "\")\"")?;
+            Ok(block)
+        } else if token.is_keyword("se") {
+            let test = self.parse_test()?;
+            self.expect_keyword("então")?;
+            let then = self.parse_statement()?;
+            let otherwise = if self.check_keyword("senão") {
+                self.position += 1;
+                self.parse_statement()?
+            } else {
+                Vec::new()
+            };
+            Ok(vec![Statement::If { test, then, otherwise }])
+        } else if token.is_keyword("enquanto") {
+            let test = self.parse_test()?;
+            self.expect_keyword("faça")?;
+            let body = self.parse_statement()?;
+            Ok(vec![Statement::While { test, body }])
+        } else if token.is_keyword("até") {
+            let test = self.parse_test()?;
+            self.expect_keyword("faça")?;
+            let body = self.parse_statement()?;
+            Ok(vec![Statement::Until { test, body }])
+        } else if token.is_keyword("inc") {
+            let register = self.parse_register()?;
+            Ok(vec![Statement::Operation(OperationKind::Inc(register))])
+        } else if token.is_keyword("dec") {
+            let register = self.parse_register()?;
+            Ok(vec![Statement::Operation(OperationKind::Dec(register))])
+        } else {
+            Err(self.unexpected(&token, "uma instrução"))
+        }
+    }
+
+    /// Analisa um teste.
+    pub(crate) fn parse_test(&mut self) -> Result<TestKind, Error> {
+        let token = self.next("um teste")?;
+        if token.is_keyword("zero") {
+            Ok(TestKind::Zero(self.parse_register()?))
+        } else {
+            Err(self.unexpected(&token, "um teste"))
+        }
+    }
+
+    /// Analisa o nome de um registrador.
+    pub(crate) fn parse_register(&mut self) -> Result<String, Error> {
+        let token = self.next("um registrador")?;
+        let is_reserved =
+            RESERVED.iter().any(|&keyword| token.is_keyword(keyword));
+        if token.token_type == TokenType::Word && !is_reserved {
+            Ok(token.content)
+        } else {
+            Err(self.unexpected(&token, "um registrador"))
+        }
+    }
+
+    /// Retorna se a sequência atual terminou.
+    pub(crate) fn at_block_end(&self) -> bool {
+        match self.tokens.get(self.position) {
+            Some(token) => token.token_type == TokenType::CloseParen,
+            None => true,
+        }
+    }
+
+    /// Retorna se o próximo token é do tipo dado.
+    pub(crate) fn check(&self, token_type: TokenType) -> bool {
+        matches!(
+            self.tokens.get(self.position),
+            Some(token) if token.token_type == token_type
+        )
+    }
+
+    /// Retorna se o próximo token é a palavra-chave dada.
+    pub(crate) fn check_keyword(&self, keyword: &str) -> bool {
+        matches!(
+            self.tokens.get(self.position),
+            Some(token) if token.is_keyword(keyword)
+        )
+    }
+
+    /// Consome o próximo token.
+    pub(crate) fn next(&mut self, expected: &str) -> Result<Token, Error> {
+        match self.tokens.get(self.position) {
+            Some(token) => {
+                self.position += 1;
+                Ok(token.clone())
+            }
+            None => {
+                let expected = expected.to_owned();
+                Err(self.error(UnexpectedEnd { expected }))
+            }
+        }
+    }
+
+    /// Consome um token do tipo dado.
+    pub(crate) fn expect_type(
+        &mut self,
+        token_type: TokenType,
+        expected: &str,
+    ) -> Result<(), Error> {
+        let token = self.next(expected)?;
+        if token.token_type == token_type {
+            Ok(())
+        } else {
+            Err(self.unexpected(&token, expected))
+        }
+    }
+
+    /// Consome a palavra-chave dada.
+    pub(crate) fn expect_keyword(
+        &mut self,
+        keyword: &str,
+    ) -> Result<(), Error> {
+        let expected = format!("\"{}\"", keyword);
+        let token = self.next(&expected)?;
+        if token.is_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&token, &expected))
+        }
+    }
+
+    /// Cria um erro de token inesperado.
+    pub(crate) fn unexpected(&self, token: &Token, expected: &str) -> Error {
+        let cause = UnexpectedToken {
+            expected: expected.to_owned(),
+            found: token.content.clone(),
+        };
+        Error::new(cause, token.span)
+    }
+
+    /// Cria um erro localizado no fim do último token.
+    pub(crate) fn error<E>(&self, cause: E) -> Error
+    where
+        E: StdError + Send + Sync + 'static,
+    {
+        match self.tokens.last() {
+            Some(token) => {
+                let mut span = token.span;
+                span.finish();
+                Error::new(cause, span)
+            }
+            None => Error::with_no_span(cause),
+        }
+    }
+}
@@ -0,0 +1,357 @@
+//! Estruturação: recupera um programa iterativo a partir de um programa
+//! monolítico.
+//!
+//! O fluxograma é reduzido repetidamente por regras que trocam uma região
+//! por uma única instrução iterativa:
+//!
+//! - composição: `V` seguido de `W`, quando `W` só é alcançado por `V`, vira
+//!   `V; W`;
+//! - `se`: um teste cujos ramos são trechos alcançados só por ele e que se
+//!   juntam no mesmo destino (ou um dos ramos vai direto ao destino) vira
+//!   `se T então V senão W`;
+//! - laços: um teste cujo ramo verdadeiro (falso) é um trecho que volta para
+//!   o teste vira `enquanto T faça (V)` (`até T faça (V)`);
+//! - laços com o teste no fim: um trecho `V` seguido de um teste que volta
+//!   para `V` vira `V; enquanto T faça (V)` (ou `até`), duplicando `V`;
+//! - em último caso, um trecho que leva a um teste é copiado para o fim dos
+//!   trechos que o precedem, o que reduz laços com o teste no meio;
+//! - um teste com os dois ramos para o mesmo destino é descartado, já que
+//!   testes não alteram a memória.
+//!
+//! Se sobrar mais de uma região, o fluxograma não pode ser escrito com `se`,
+//! `enquanto` e `até` (por exemplo, um laço com duas saídas) e as instruções
+//! que sobraram são relatadas. Instruções inalcançáveis são ignoradas.
+//!
+//! A redução sempre termina: as demais regras diminuem o número de regiões
+//! ou o de testes, e a cópia de trechos não muda nenhum dos dois e diminui o
+//! número de arestas entre dois trechos.
+
+use super::{error::NotStructured, Program, Statement};
+use crate::interpreter::program::{
+    self as monolithic, InstructionKind, TestKind,
+};
+use std::{collections::HashMap, mem};
+
+/// Destino de uma aresta: o índice de um nó, ou `None` para a saída.
+type Target = Option<usize>;
+
+/// Tipo de uma região do fluxograma.
+#[derive(Debug, Clone)]
+enum Kind {
+    /// Um trecho já estruturado, com um único sucessor.
+    Block(Vec<Statement>, Target),
+    /// Um teste, com os sucessores verdadeiro e falso.
+    Branch(TestKind, Target, Target),
+}
+
+/// Uma região do fluxograma, identificada pelo rótulo da sua primeira
+/// instrução.
+#[derive(Debug, Clone)]
+struct Node {
+    label: String,
+    kind: Kind,
+}
+
+/// Fluxograma em redução. Nós reduzidos viram `None`.
+#[derive(Debug)]
+struct Graph {
+    nodes: Vec<Option<Node>>,
+}
+
+/// Recupera um programa iterativo equivalente ao programa monolítico, ou
+/// relata a região que não pôde ser reduzida.
+pub fn structure(
+    program: &monolithic::Program,
+) -> Result<Program, NotStructured> {
+    let mut graph = Graph::new(program);
+    if graph.nodes.is_empty() {
+        return Ok(Program::default());
+    }
+
+    while graph.reduce_once() {}
+
+    let live: Vec<_> = graph.nodes.iter().flatten().collect();
+    match live.as_slice() {
+        [Node { kind: Kind::Block(body, None), .. }] => {
+            Ok(Program { body: body.clone() })
+        }
+        _ => Err(NotStructured {
+            labels: live.iter().map(|node| node.label.clone()).collect(),
+        }),
+    }
+}
+
+impl Graph {
+    /// Constrói o fluxograma com as instruções alcançáveis. O nó `0` é a
+    /// entrada.
+    fn new(program: &monolithic::Program) -> Self {
+        let mut indices = HashMap::new();
+        let mut order = Vec::new();
+        let mut stack = vec![program.first_label().to_owned()];
+
+        while let Some(label) = stack.pop() {
+            if indices.contains_key(&label) {
+                continue;
+            }
+            if let Some(instruction) = program.instruction(&label) {
+                indices.insert(label, order.len());
+                order.push(instruction);
+                let mut successors = Vec::new();
+                instruction
+                    .collect_labels(|next| successors.push(next.to_owned()));
+                stack.extend(successors.into_iter().rev());
+            }
+        }
+
+        let target = |label: &str| indices.get(label).copied();
+        let nodes = order
+            .iter()
+            .map(|instruction| {
+                let kind = match &instruction.kind {
+                    InstructionKind::Operation(oper) => Kind::Block(
+                        vec![Statement::Operation(oper.kind.clone())],
+                        target(&oper.next),
+                    ),
+                    InstructionKind::Test(test) => Kind::Branch(
+                        test.kind.clone(),
+                        target(&test.next_then),
+                        target(&test.next_else),
+                    ),
+                };
+                Some(Node { label: instruction.label().to_owned(), kind })
+            })
+            .collect();
+
+        Self { nodes }
+    }
+
+    /// Aplica a primeira regra possível. Retorna se alguma foi aplicada.
+    fn reduce_once(&mut self) -> bool {
+        (0..self.nodes.len()).any(|index| self.reduce_node(index))
+            || (0..self.nodes.len()).any(|index| self.duplicate_tail(index))
+    }
+
+    /// Tenta aplicar uma regra com o nó dado como cabeça da região.
+    fn reduce_node(&mut self, index: usize) -> bool {
+        let kind = match &self.nodes[index] {
+            Some(node) => node.kind.clone(),
+            None => return false,
+        };
+
+        match kind {
+            Kind::Block(mut body, Some(next)) if next != index => {
+                match self.single_entry_block(next) {
+                    Some((tail, after)) => {
+                        body.extend(tail);
+                        self.replace(index, Kind::Block(body, after), &[next]);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            Kind::Block(..) => false,
+            Kind::Branch(test, then, otherwise) => {
+                self.reduce_branch(index, test, then, otherwise)
+            }
+        }
+    }
+
+    /// Tenta reduzir um teste e os seus ramos.
+    fn reduce_branch(
+        &mut self,
+        index: usize,
+        test: TestKind,
+        then: Target,
+        otherwise: Target,
+    ) -> bool {
+        let then_block = self.loop_body(index, then);
+        let else_block = self.loop_body(index, otherwise);
+
+        let (kind, removed) = if then == otherwise {
+            (Kind::Block(Vec::new(), then), None)
+        } else if let Some((body, removed)) = then_block {
+            let statement = Statement::While { test, body };
+            (Kind::Block(vec![statement], otherwise), removed)
+        } else if let Some((body, removed)) = else_block {
+            let statement = Statement::Until { test, body };
+            (Kind::Block(vec![statement], then), removed)
+        } else {
+            return self.reduce_conditional(index, test, then, otherwise)
+                || self.reduce_bottom_test(index);
+        };
+
+        let removed: Vec<_> = removed.into_iter().collect();
+        self.replace(index, kind, &removed);
+        true
+    }
+
+    /// Se o ramo `target` do teste `index` volta direto para o teste, retorna
+    /// o corpo do laço e o nó absorvido por ele, se houver.
+    fn loop_body(
+        &self,
+        index: usize,
+        target: Target,
+    ) -> Option<(Vec<Statement>, Option<usize>)> {
+        let next = target?;
+        if next == index {
+            return Some((Vec::new(), None));
+        }
+        match self.single_entry_block(next)? {
+            (body, Some(back)) if back == index => Some((body, Some(next))),
+            _ => None,
+        }
+    }
+
+    /// Tenta reduzir um teste cujos ramos se juntam num destino comum.
+    fn reduce_conditional(
+        &mut self,
+        index: usize,
+        test: TestKind,
+        then: Target,
+        otherwise: Target,
+    ) -> bool {
+        let then_block = then.and_then(|next| self.single_entry_block(next));
+        let else_block =
+            otherwise.and_then(|next| self.single_entry_block(next));
+
+        let (then_body, else_body, after, removed) =
+            match (then_block, else_block) {
+                (Some((then_body, a)), Some((else_body, b))) if a == b => {
+                    (then_body, else_body, a, vec![then, otherwise])
+                }
+                (Some((then_body, a)), _) if a == otherwise => {
+                    (then_body, Vec::new(), otherwise, vec![then])
+                }
+                (_, Some((else_body, b))) if b == then => {
+                    (Vec::new(), else_body, then, vec![otherwise])
+                }
+                _ => return false,
+            };
+
+        let statement =
+            Statement::If { test, then: then_body, otherwise: else_body };
+        let removed: Vec<_> = removed.into_iter().flatten().collect();
+        self.replace(index, Kind::Block(vec![statement], after), &removed);
+        true
+    }
+
+    /// Tenta reduzir um laço com o teste no fim: um trecho seguido do teste
+    /// `index`, que volta para o trecho por um dos ramos.
+    fn reduce_bottom_test(&mut self, index: usize) -> bool {
+        let (test, then, otherwise) = match &self.nodes[index] {
+            Some(Node {
+                kind: Kind::Branch(test, then, otherwise), ..
+            }) => (test.clone(), *then, *otherwise),
+            _ => return false,
+        };
+        if self.predecessors(index) != 1 {
+            return false;
+        }
+
+        for (back, exit, is_while) in
+            [(then, otherwise, true), (otherwise, then, false)]
+        {
+            let head = match back {
+                Some(head) => head,
+                None => continue,
+            };
+            let body = match &self.nodes[head] {
+                Some(Node { kind: Kind::Block(body, next), .. })
+                    if *next == Some(index) && self.predecessors(head) == 2 =>
+                {
+                    body.clone()
+                }
+                _ => continue,
+            };
+
+            let test = test.clone();
+            let loop_body = body.clone();
+            let statement = if is_while {
+                Statement::While { test, body: loop_body }
+            } else {
+                Statement::Until { test, body: loop_body }
+            };
+            let mut body = body;
+            body.push(statement);
+            self.replace(head, Kind::Block(body, exit), &[index]);
+            return true;
+        }
+
+        false
+    }
+
+    /// Copia para o trecho `index` o trecho seguinte, quando este é alcançado
+    /// por mais de uma aresta e leva a um teste. Só é usada quando nenhuma
+    /// outra regra se aplica, para expor laços com o teste no meio.
+    ///
+    /// A aresta de `index` para o trecho seguinte vira uma aresta para o
+    /// teste, e nenhuma outra muda; como nenhum nó é criado, a regra só pode
+    /// ser aplicada uma vez por aresta entre dois trechos antes que outra
+    /// regra reduza o fluxograma.
+    fn duplicate_tail(&mut self, index: usize) -> bool {
+        let (mut body, next) = match self.single_entry_block(index) {
+            Some((body, Some(next))) if next != index => (body, next),
+            _ => return false,
+        };
+        let (tail, after) = match &self.nodes[next] {
+            Some(Node { kind: Kind::Block(tail, Some(after)), .. }) => {
+                (tail.clone(), *after)
+            }
+            _ => return false,
+        };
+        if !matches!(
+            &self.nodes[after],
+            Some(Node { kind: Kind::Branch(..), .. })
+        ) {
+            return false;
+        }
+
+        body.extend(tail);
+        self.replace(index, Kind::Block(body, Some(after)), &[]);
+        true
+    }
+
+    /// Se o nó é um trecho alcançado só por uma aresta, retorna seu corpo e
+    /// seu sucessor.
+    fn single_entry_block(
+        &self,
+        index: usize,
+    ) -> Option<(Vec<Statement>, Target)> {
+        match &self.nodes[index] {
+            Some(Node { kind: Kind::Block(body, next), .. })
+                if self.predecessors(index) == 1 =>
+            {
+                Some((body.clone(), *next))
+            }
+            _ => None,
+        }
+    }
+
+    /// Conta as arestas que chegam no nó, contando a entrada como uma aresta.
+    fn predecessors(&self, index: usize) -> usize {
+        let entry = usize::from(index == 0);
+        let edges = self
+            .nodes
+            .iter()
+            .flatten()
+            .map(|node| match node.kind {
+                Kind::Block(_, next) => usize::from(next == Some(index)),
+                Kind::Branch(_, then, otherwise) => {
+                    usize::from(then == Some(index))
+                        + usize::from(otherwise == Some(index))
+                }
+            })
+            .sum::<usize>();
+        entry + edges
+    }
+
+    /// Troca o tipo do nó `index` e remove os nós absorvidos por ele.
+    fn replace(&mut self, index: usize, kind: Kind, removed: &[usize]) {
+        for &other in removed {
+            self.nodes[other] = None;
+        }
+        if let Some(node) = &mut self.nodes[index] {
+            let _ = mem::replace(&mut node.kind, kind);
+        }
+    }
+}
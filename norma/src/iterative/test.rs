@@ -0,0 +1,199 @@
+use super::{compile, parse, structure::structure, Program, Statement};
+use crate::{
+    compiler,
+    interpreter::{
+        program::{OperationKind, TestKind},
+        run_once,
+    },
+};
+use num_bigint::BigUint;
+
+const COPY: &str = "// Y := X
+até zero X faça (
+    dec X;
+    inc Y
+)";
+
+const DOUBLE: &str = "até zero X faça (
+    dec X;
+    inc Y;
+    inc Y
+);
+se zero Y então inc Y senão (
+    dec Y;
+    até zero A faça ()
+)
+";
+
+fn run(source: &str, input: u64) -> BigUint {
+    run_once(BigUint::from(input), compile(source).unwrap())
+}
+
+fn messages(source: &str) -> Vec<String> {
+    parse(source).unwrap_err().iter().map(|error| error.to_string()).collect()
+}
+
+#[test]
+fn parse_statements() {
+    let program = parse(COPY).unwrap();
+    let expected = Program {
+        body: vec![Statement::Until {
+            test: TestKind::Zero(String::from("X")),
+            body: vec![
+                Statement::Operation(OperationKind::Dec(String::from("X"))),
+                Statement::Operation(OperationKind::Inc(String::from("Y"))),
+            ],
+        }],
+    };
+    assert_eq!(program, expected);
+}
+
+#[test]
+fn parse_without_accents() {
+    let accented = parse(DOUBLE).unwrap();
+    let plain = parse(
+        "ate zero X faca (dec X; inc Y; inc Y);
+        se zero Y entao inc Y senao (dec Y; ate zero A faca ())",
+    )
+    .unwrap();
+    assert_eq!(accented, plain);
+}
+
+#[test]
+fn print_round_trip() {
+    let program = parse(DOUBLE).unwrap();
+    assert_eq!(program.to_string(), DOUBLE);
+    assert_eq!(parse(&program.to_string()).unwrap(), program);
+    assert_eq!(parse("").unwrap().to_string(), "");
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!(
+        messages("enquanto zero X (dec X)"),
+        vec![String::from(
+            "Esperava-se \"faça\", encontrado \"(\", na linha 1 e coluna 17"
+        )]
+    );
+    assert_eq!(
+        messages("se zero X então"),
+        vec![String::from("Esperava-se uma instrução, mas o código terminou, na linha 1 e coluna 16")]
+    );
+    assert_eq!(
+        messages("inc faça"),
+        vec![String::from("Esperava-se um registrador, encontrado \"faça\", da linha 1 e coluna 5, até a coluna 8")]
+    );
+    assert_eq!(
+        messages("inc X inc Y"),
+        vec![String::from("Esperava-se \";\" ou o fim, encontrado \"inc\", da linha 1 e coluna 7, até a coluna 9")]
+    );
+}
+
+#[test]
+fn compile_labels() {
+    let program = compile(COPY).unwrap();
+    assert_eq!(
+        program.to_string(),
+        "1: if zero X then goto 0 else goto 2\n\
+         2: do dec X goto 3\n\
+         3: do inc Y goto 1\n"
+    );
+}
+
+#[test]
+fn compile_and_run() {
+    assert_eq!(run(COPY, 7), BigUint::from(7u8));
+    assert_eq!(run(DOUBLE, 0), BigUint::from(1u8));
+    assert_eq!(run(DOUBLE, 4), BigUint::from(7u8));
+    assert_eq!(run("", 4), BigUint::from(0u8));
+}
+
+#[test]
+fn structure_compiled() {
+    for source in [COPY, DOUBLE] {
+        let program = parse(source).unwrap();
+        let structured = structure(&program.to_monolithic()).unwrap();
+        assert_eq!(structured, program);
+    }
+}
+
+#[test]
+fn structure_monolithic() {
+    let program = compiler::compile(
+        "main {
+            1: do clear (Y) goto 2
+            2: if zero X then goto 0 else goto 3
+            3: do dec X goto 4
+            4: do inc Y goto 2
+        }
+
+        operation clear (A) {
+            1: if zero A then goto 0 else goto 2
+            2: do dec A goto 1
+        }",
+    )
+    .unwrap();
+
+    let structured = structure(&program).unwrap();
+    assert_eq!(
+        structured.to_string(),
+        "até zero Y faça (\n    \
+             dec Y\n\
+         );\n\
+         até zero X faça (\n    \
+             dec X;\n    \
+             inc Y\n\
+         )\n"
+    );
+}
+
+#[test]
+fn structure_test_in_the_middle() {
+    let program = compiler::compile(
+        "main {
+            1: do inc Y goto 2
+            2: if zero X then goto 0 else goto 3
+            3: do dec X goto 1
+        }",
+    )
+    .unwrap();
+
+    let structured = structure(&program).unwrap();
+    assert_eq!(
+        structured.to_string(),
+        "inc Y;\n\
+         até zero X faça (\n    \
+             dec X;\n    \
+             inc Y\n\
+         )\n"
+    );
+    for input in 0..4 {
+        assert_eq!(
+            run(&structured.to_string(), input),
+            run_once(BigUint::from(input), program.clone())
+        );
+    }
+}
+
+#[test]
+fn structure_multiple_exits() {
+    let program = compiler::compile(
+        "main {
+            1: if zero X then goto 0 else goto 2
+            2: do dec X goto 3
+            3: if zero Y then goto 0 else goto 1
+        }",
+    )
+    .unwrap();
+
+    let error = structure(&program).unwrap_err();
+    assert_eq!(error.labels, vec!["1", "2", "3"]);
+    assert_eq!(
+        error.to_string(),
+        "O fluxograma não pode ser escrito como programa iterativo; região \
+         não estruturável: 1, 2, 3"
+    );
+
+    let program = compiler::compile("main { 1: do inc X goto 1 }").unwrap();
+    assert_eq!(structure(&program).unwrap_err().labels, vec!["1"]);
+}
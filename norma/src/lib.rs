@@ -1,5 +1,18 @@
+pub mod batch;
+mod block;
+pub mod busy_beaver;
 pub mod compiler;
+pub mod encoding;
+pub mod grader;
+pub mod graph;
+pub mod iterative;
+pub mod loop_while;
 pub mod machine;
+pub mod mu_recursive;
+pub mod recursive;
+pub mod similarity;
+pub mod testing;
+pub mod universal;
 pub mod interpreter;
 
 use interpreter::run_once;
@@ -0,0 +1,346 @@
+//! Programas LOOP e WHILE, as linguagens clássicas das funções recursivas
+//! primitivas e das funções computáveis, e a sua compilação para a Norma.
+//!
+//! As instruções são separadas por `;` e podem ser:
+//!
+//! - `x := x + 1`: incrementa `x`;
+//! - `x := 0`: zera `x`;
+//! - `LOOP x DO P END`: executa `P` tantas vezes quanto o valor de `x` na
+//!   entrada do laço, mesmo que `P` altere `x`;
+//! - `WHILE x ≠ 0 DO P END` (ou `!=`): executa `P` enquanto `x` não for zero.
+//!
+//! Um programa sem `WHILE` é um programa LOOP e sempre termina (ver
+//! [`Program::is_loop`]). Por exemplo, `Y := X + X`:
+//!
+//! ```text
+//! LOOP X DO
+//!     Y := Y + 1;
+//!     Y := Y + 1
+//! END
+//! ```
+//!
+//! A compilação gera um [`Program`](crate::interpreter::program::Program)
+//! monolítico com rótulos `1`, `2`, ... na ordem do texto e rótulo de saída
+//! `0`. Cada nível de aninhamento de `LOOP` usa um registrador contador
+//! próprio, e as cópias usam um registrador auxiliar; os nomes são escolhidos
+//! para não colidir com os registradores do programa. Os temporários sempre
+//! voltam a zero, por isso não precisam ser zerados antes de usados.
+
+#[cfg(test)]
+mod test;
+
+mod parser;
+
+use crate::{
+    block::{self, block_size, entry, fresh_register, Node},
+    interpreter::program::{
+        self as monolithic, Instruction, InstructionKind, Operation,
+        OperationKind, Test, TestKind,
+    },
+    iterative::lexer,
+};
+use pinguim_language::error::Diagnostics;
+use std::fmt;
+
+/// Número de instruções geradas por um `LOOP`, sem contar o corpo.
+const LOOP_SIZE: usize = 9;
+
+/// Rótulo de saída dos programas compilados.
+const EXIT_LABEL: &str = "0";
+
+/// Uma instrução de um programa LOOP/WHILE.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Statement {
+    /// `register := register + 1`.
+    Increment(String),
+    /// `register := 0`.
+    Clear(String),
+    /// `LOOP register DO body END`.
+    Loop {
+        /// O registrador que dá o número de repetições.
+        register: String,
+        /// O corpo do laço.
+        body: Vec<Statement>,
+    },
+    /// `WHILE register ≠ 0 DO body END`.
+    While {
+        /// O registrador testado.
+        register: String,
+        /// O corpo do laço.
+        body: Vec<Statement>,
+    },
+}
+
+impl Node for Statement {
+    fn size(&self) -> usize {
+        match self {
+            Statement::Increment(_) => 1,
+            Statement::Clear(_) => 2,
+            Statement::Loop { body, .. } => LOOP_SIZE + block_size(body),
+            Statement::While { body, .. } => 1 + block_size(body),
+        }
+    }
+}
+
+impl Statement {
+    /// Retorna se a instrução não contém `WHILE`.
+    fn is_loop(&self) -> bool {
+        match self {
+            Statement::Increment(_) | Statement::Clear(_) => true,
+            Statement::Loop { body, .. } => body.iter().all(Statement::is_loop),
+            Statement::While { .. } => false,
+        }
+    }
+
+    /// Maior número de `LOOP`s aninhados nesta instrução.
+    fn loop_depth(&self) -> usize {
+        match self {
+            Statement::Increment(_) | Statement::Clear(_) => 0,
+            Statement::Loop { body, .. } => 1 + block_depth(body),
+            Statement::While { body, .. } => block_depth(body),
+        }
+    }
+
+    /// Acumula os registradores usados, sem repetição.
+    fn collect_registers(&self, registers: &mut Vec<String>) {
+        let register = match self {
+            Statement::Increment(register) | Statement::Clear(register) => {
+                register
+            }
+            Statement::Loop { register, body }
+            | Statement::While { register, body } => {
+                for statement in body {
+                    statement.collect_registers(registers);
+                }
+                register
+            }
+        };
+        if !registers.contains(register) {
+            registers.push(register.clone());
+        }
+    }
+
+    /// Escreve a instrução, com os blocos indentados a partir de `indent`.
+    fn write(&self, fmtr: &mut fmt::Formatter, indent: usize) -> fmt::Result {
+        match self {
+            Statement::Increment(register) => {
+                write!(fmtr, "{} := {} + 1", register, register)
+            }
+            Statement::Clear(register) => write!(fmtr, "{} := 0", register),
+            Statement::Loop { register, body } => {
+                write!(fmtr, "LOOP {} DO", register)?;
+                write_body(fmtr, body, indent)
+            }
+            Statement::While { register, body } => {
+                write!(fmtr, "WHILE {} ≠ 0 DO", register)?;
+                write_body(fmtr, body, indent)
+            }
+        }
+    }
+}
+
+/// Um programa LOOP/WHILE: uma sequência de instruções.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Program {
+    /// As instruções, compostas sequencialmente.
+    pub body: Vec<Statement>,
+}
+
+impl Program {
+    /// Retorna se é um programa LOOP, isto é, sem `WHILE`. Isso certifica que
+    /// o programa termina para qualquer entrada: o número de repetições de
+    /// cada `LOOP` é fixado antes de executar o corpo.
+    pub fn is_loop(&self) -> bool {
+        self.body.iter().all(Statement::is_loop)
+    }
+
+    /// Maior número de `LOOP`s aninhados. Um programa LOOP com profundidade
+    /// `n` pertence ao nível `n` da hierarquia de Meyer-Ritchie das funções
+    /// recursivas primitivas.
+    pub fn loop_depth(&self) -> usize {
+        block_depth(&self.body)
+    }
+
+    /// Registradores usados pelo programa, na ordem em que são encontrados.
+    pub fn registers(&self) -> Vec<String> {
+        let mut registers = Vec::new();
+        for statement in &self.body {
+            statement.collect_registers(&mut registers);
+        }
+        registers
+    }
+
+    /// Converte para um programa monolítico, com rótulos `1`, `2`, ... na
+    /// ordem do texto e rótulo de saída `0`.
+    pub fn to_monolithic(&self) -> monolithic::Program {
+        let mut used = self.registers();
+        let aux = fresh_register("Auxiliar", &used);
+        used.push(aux.clone());
+        let mut counters = Vec::new();
+        for depth in 1..=self.loop_depth() {
+            let counter = fresh_register(&format!("Contador{}", depth), &used);
+            used.push(counter.clone());
+            counters.push(counter);
+        }
+
+        let mut emitter =
+            Emitter { aux, counters, program: monolithic::Program::empty() };
+        emitter.emit_block(&self.body, 1, EXIT_LABEL, 0);
+        emitter.program
+    }
+}
+
+impl fmt::Display for Program {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        block::write_sequence(fmtr, &self.body, 0, Statement::write)?;
+        if !self.body.is_empty() {
+            writeln!(fmtr)?;
+        }
+        Ok(())
+    }
+}
+
+/// Analisa o código-fonte de um programa LOOP/WHILE.
+pub fn parse(source: &str) -> Result<Program, Diagnostics> {
+    let mut diagnostics = Diagnostics::new();
+    let tokens = lexer::generate_tokens(source, &mut diagnostics);
+    let maybe_program = parser::parse(tokens, &mut diagnostics);
+
+    match maybe_program {
+        Some(program) if diagnostics.is_ok() => Ok(program),
+        _ => Err(diagnostics),
+    }
+}
+
+/// Compila o código-fonte de um programa LOOP/WHILE para um programa
+/// monolítico.
+pub fn compile(source: &str) -> Result<monolithic::Program, Diagnostics> {
+    parse(source).map(|program| program.to_monolithic())
+}
+
+/// Gerador das instruções monolíticas.
+#[derive(Debug)]
+struct Emitter {
+    /// Registrador auxiliar das cópias.
+    aux: String,
+    /// Contadores dos `LOOP`s, um por nível de aninhamento.
+    counters: Vec<String>,
+    /// O programa sendo gerado.
+    program: monolithic::Program,
+}
+
+impl Emitter {
+    /// Gera as instruções de uma sequência a partir do rótulo `start`, saindo
+    /// para `next`, dentro de `depth` `LOOP`s.
+    fn emit_block(
+        &mut self,
+        block: &[Statement],
+        start: usize,
+        next: &str,
+        depth: usize,
+    ) {
+        block::emit_block(block, start, next, |statement, label, next| {
+            self.emit(statement, label, next, depth)
+        });
+    }
+
+    /// Gera as instruções de uma instrução a partir do rótulo `start`, saindo
+    /// para `next`, dentro de `depth` `LOOP`s.
+    fn emit(
+        &mut self,
+        statement: &Statement,
+        start: usize,
+        next: &str,
+        depth: usize,
+    ) {
+        let label = |offset: usize| (start + offset).to_string();
+
+        match statement {
+            Statement::Increment(register) => {
+                self.operation(label(0), inc(register), next);
+            }
+            Statement::Clear(register) => {
+                self.test(label(0), register, next, &label(1));
+                self.operation(label(1), dec(register), &label(0));
+            }
+            Statement::Loop { register, body } => {
+                let counter = self.counters[depth].clone();
+                let aux = self.aux.clone();
+                // contador := registrador, usando o auxiliar para restaurar
+                // o registrador.
+                self.test(label(0), register, &label(4), &label(1));
+                self.operation(label(1), dec(register), &label(2));
+                self.operation(label(2), inc(&counter), &label(3));
+                self.operation(label(3), inc(&aux), &label(0));
+                self.test(label(4), &aux, &label(7), &label(5));
+                self.operation(label(5), dec(&aux), &label(6));
+                self.operation(label(6), inc(register), &label(4));
+                // Repete o corpo até o contador zerar.
+                self.test(label(7), &counter, next, &label(8));
+                let body_next = entry(body, start + LOOP_SIZE, &label(7));
+                self.operation(label(8), dec(&counter), &body_next);
+                self.emit_block(body, start + LOOP_SIZE, &label(7), depth + 1);
+            }
+            Statement::While { register, body } => {
+                let body_next = entry(body, start + 1, &label(0));
+                self.test(label(0), register, next, &body_next);
+                self.emit_block(body, start + 1, &label(0), depth);
+            }
+        }
+    }
+
+    /// Insere `label: do kind goto next`.
+    fn operation(&mut self, label: String, kind: OperationKind, next: &str) {
+        let oper = Operation { kind, next: next.to_owned() };
+        self.program
+            .insert(Instruction::new(label, InstructionKind::Operation(oper)));
+    }
+
+    /// Insere `label: if zero register then goto then else goto otherwise`.
+    fn test(
+        &mut self,
+        label: String,
+        register: &str,
+        then: &str,
+        otherwise: &str,
+    ) {
+        let test = Test {
+            kind: TestKind::Zero(register.to_owned()),
+            next_then: then.to_owned(),
+            next_else: otherwise.to_owned(),
+        };
+        self.program
+            .insert(Instruction::new(label, InstructionKind::Test(test)));
+    }
+}
+
+/// Operação `inc register`.
+fn inc(register: &str) -> OperationKind {
+    OperationKind::Inc(register.to_owned())
+}
+
+/// Operação `dec register`.
+fn dec(register: &str) -> OperationKind {
+    OperationKind::Dec(register.to_owned())
+}
+
+/// Maior número de `LOOP`s aninhados em uma sequência.
+fn block_depth(block: &[Statement]) -> usize {
+    block.iter().map(Statement::loop_depth).max().unwrap_or(0)
+}
+
+/// Escreve o corpo de um laço seguido de `END`; um corpo vazio fica na mesma
+/// linha.
+fn write_body(
+    fmtr: &mut fmt::Formatter,
+    body: &[Statement],
+    indent: usize,
+) -> fmt::Result {
+    if body.is_empty() {
+        return write!(fmtr, " END");
+    }
+    writeln!(fmtr)?;
+    block::write_sequence(fmtr, body, indent + 1, Statement::write)?;
+    writeln!(fmtr)?;
+    write!(fmtr, "{:width$}END", "", width = indent * 4)
+}
@@ -0,0 +1,133 @@
+use super::{Program, Statement};
+use crate::iterative::{
+    lexer::{Token, TokenType},
+    parser::Parser,
+};
+use pinguim_language::error::{Diagnostics, Error};
+
+/// Palavras que não podem ser usadas como registrador.
+const RESERVED: &[&str] = &["LOOP", "WHILE", "DO", "END"];
+
+/// Analisa os tokens de um programa LOOP/WHILE. Para no primeiro erro.
+pub fn parse(
+    tokens: Vec<Token>,
+    diagnostics: &mut Diagnostics,
+) -> Option<Program> {
+    let mut parser = LoopParser { parser: Parser { tokens, position: 0 } };
+    let result = parser.parse_sequence().and_then(|body| {
+        let parser = &parser.parser;
+        match parser.tokens.get(parser.position) {
+            Some(token) => Err(parser.unexpected(token, "\";\" ou o fim")),
+            None => Ok(Program { body }),
+        }
+    });
+
+    match result {
+        Ok(program) => Some(program),
+        Err(error) => {
+            diagnostics.raise(error);
+            None
+        }
+    }
+}
+
+/// Analisador sintático de programas LOOP/WHILE, por descida recursiva.
+struct LoopParser {
+    parser: Parser,
+}
+
+impl LoopParser {
+    /// Analisa uma sequência de instruções separadas por `;`, possivelmente
+    /// vazia, até um `END` ou o fim.
+    fn parse_sequence(&mut self) -> Result<Vec<Statement>, Error> {
+        let mut block = Vec::new();
+        if self.parser.position == self.parser.tokens.len()
+            || self.parser.check_keyword("END")
+        {
+            return Ok(block);
+        }
+
+        loop {
+            block.push(self.parse_statement()?);
+            if self.parser.check(TokenType::Semicolon) {
+                self.parser.position += 1;
+            } else {
+                break Ok(block);
+            }
+        }
+    }
+
+    /// Analisa uma instrução.
+    fn parse_statement(&mut self) -> Result<Statement, Error> {
+        let token = self.parser.next("uma instrução")?;
+
+        if token.is_keyword("LOOP") {
+            let register = self.parse_register()?;
+            let body = self.parse_body()?;
+            Ok(Statement::Loop { register, body })
+        } else if token.is_keyword("WHILE") {
+            let register = self.parse_register()?;
+            self.parser.expect_type(TokenType::NotEqual, "\"≠\"")?;
+            self.expect_word("0")?;
+            let body = self.parse_body()?;
+            Ok(Statement::While { register, body })
+        } else if is_register(&token) {
+            let register = token.content;
+            self.parser.expect_type(TokenType::Assign, "\":=\"")?;
+            let expected = format!("\"0\" ou \"{}\"", register);
+            let value = self.parser.next(&expected)?;
+            if value.token_type == TokenType::Word && value.content == "0" {
+                Ok(Statement::Clear(register))
+            } else if value.token_type == TokenType::Word
+                && value.content == register
+            {
+                self.parser.expect_type(TokenType::Plus, "\"+\"")?;
+                self.expect_word("1")?;
+                Ok(Statement::Increment(register))
+            } else {
+                Err(self.parser.unexpected(&value, &expected))
+            }
+        } else {
+            Err(self.parser.unexpected(&token, "uma instrução"))
+        }
+    }
+
+    /// Analisa `DO body END`.
+    fn parse_body(&mut self) -> Result<Vec<Statement>, Error> {
+        self.parser.expect_keyword("DO")?;
+        let body = self.parse_sequence()?;
+        self.parser.expect_keyword("END")?;
+        Ok(body)
+    }
+
+    /// Analisa o nome de um registrador.
+    fn parse_register(&mut self) -> Result<String, Error> {
+        let token = self.parser.next("um registrador")?;
+        if is_register(&token) {
+            Ok(token.content)
+        } else {
+            Err(self.parser.unexpected(&token, "um registrador"))
+        }
+    }
+
+    /// Consome a palavra dada, que não é uma palavra-chave (como `0` ou `1`).
+    fn expect_word(&mut self, word: &str) -> Result<(), Error> {
+        let expected = format!("\"{}\"", word);
+        let token = self.parser.next(&expected)?;
+        if token.token_type == TokenType::Word && token.content == word {
+            Ok(())
+        } else {
+            Err(self.parser.unexpected(&token, &expected))
+        }
+    }
+}
+
+/// Retorna se o token serve como registrador: uma palavra que não é
+/// reservada nem começa por dígito.
+fn is_register(token: &Token) -> bool {
+    token.token_type == TokenType::Word
+        && !RESERVED.iter().any(|&keyword| token.is_keyword(keyword))
+        && !token
+            .content
+            .starts_with(|character: char| character.is_ascii_digit())
+}
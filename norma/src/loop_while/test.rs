@@ -0,0 +1,160 @@
+use super::{compile, parse, Program, Statement};
+use crate::interpreter::{run_once, Interpreter};
+use num_bigint::BigUint;
+
+const DOUBLE: &str = "LOOP X DO
+    Y := Y + 1;
+    Y := Y + 1
+END
+";
+
+const SQUARE: &str = "// Y := X * X
+LOOP X DO LOOP X DO Y := Y + 1 END END";
+
+const PREDECESSOR: &str = "LOOP X DO
+    Y := 0;
+    LOOP A DO Y := Y + 1 END;
+    A := A + 1
+END";
+
+fn run(source: &str, input: u64) -> BigUint {
+    run_once(BigUint::from(input), compile(source).unwrap())
+}
+
+fn messages(source: &str) -> Vec<String> {
+    parse(source).unwrap_err().iter().map(|error| error.to_string()).collect()
+}
+
+#[test]
+fn parse_statements() {
+    let program = parse("X := 0; WHILE X != 0 DO X := X + 1 END").unwrap();
+    let expected = Program {
+        body: vec![
+            Statement::Clear(String::from("X")),
+            Statement::While {
+                register: String::from("X"),
+                body: vec![Statement::Increment(String::from("X"))],
+            },
+        ],
+    };
+    assert_eq!(program, expected);
+}
+
+#[test]
+fn print_round_trip() {
+    let program = parse(DOUBLE).unwrap();
+    assert_eq!(program.to_string(), DOUBLE);
+
+    let program = parse(PREDECESSOR).unwrap();
+    assert_eq!(parse(&program.to_string()).unwrap(), program);
+    assert_eq!(
+        parse("WHILE X ≠ 0 DO END").unwrap().to_string(),
+        "WHILE X ≠ 0 DO END\n"
+    );
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!(
+        messages("Y := X + 1"),
+        vec![String::from(
+            "Esperava-se \"0\" ou \"Y\", encontrado \"X\", na linha 1 e coluna 6"
+        )]
+    );
+    assert_eq!(
+        messages("WHILE X ≠ 1 DO END"),
+        vec![String::from(
+            "Esperava-se \"0\", encontrado \"1\", na linha 1 e coluna 11"
+        )]
+    );
+    assert_eq!(
+        messages("LOOP X DO Y := Y + 1"),
+        vec![String::from(
+            "Esperava-se \"END\", mas o código terminou, na linha 1 e coluna 21"
+        )]
+    );
+    assert_eq!(
+        messages("LOOP END DO END"),
+        vec![String::from(
+            "Esperava-se um registrador, encontrado \"END\", da linha 1 e \
+             coluna 6, até a coluna 8"
+        )]
+    );
+}
+
+#[test]
+fn certify_termination() {
+    for source in [DOUBLE, SQUARE, PREDECESSOR] {
+        assert!(parse(source).unwrap().is_loop());
+    }
+    assert!(!parse("LOOP X DO WHILE Y ≠ 0 DO END END").unwrap().is_loop());
+    assert_eq!(parse(SQUARE).unwrap().loop_depth(), 2);
+    assert_eq!(parse("WHILE X ≠ 0 DO X := 0 END").unwrap().loop_depth(), 0);
+}
+
+#[test]
+fn loop_programs() {
+    for input in 0..5 {
+        assert_eq!(run(DOUBLE, input), BigUint::from(2 * input));
+        assert_eq!(run(SQUARE, input), BigUint::from(input * input));
+        assert_eq!(run(PREDECESSOR, input), BigUint::from(input.max(1) - 1));
+    }
+}
+
+#[test]
+fn loop_count_is_fixed_on_entry() {
+    let source = "LOOP X DO X := X + 1; Y := Y + 1 END";
+    for input in 0..4 {
+        assert_eq!(run(source, input), BigUint::from(input));
+    }
+}
+
+#[test]
+fn while_programs() {
+    let source = "WHILE X ≠ 0 DO X := 0; Y := Y + 1 END";
+    for input in 0..3 {
+        assert_eq!(run(source, input), BigUint::from(input.min(1)));
+    }
+
+    let mut interpreter =
+        Interpreter::new(compile("WHILE X ≠ 0 DO END").unwrap());
+    interpreter.input(BigUint::from(1u8));
+    assert!(interpreter.run_steps(1000));
+}
+
+#[test]
+fn step_count() {
+    // Copiar X para o contador e restaurar X custa 7n + 2 passos, e cada
+    // repetição custa o teste, o decremento e os dois incrementos.
+    for input in 0..4u64 {
+        let mut interpreter = Interpreter::new(compile(DOUBLE).unwrap());
+        interpreter.input(BigUint::from(input));
+        interpreter.run_all();
+        assert_eq!(interpreter.steps(), BigUint::from(11 * input + 3));
+    }
+}
+
+#[test]
+fn temporaries_avoid_user_registers() {
+    let program =
+        compile("LOOP Auxiliar DO LOOP Contador1 DO Y := Y + 1 END END")
+            .unwrap();
+    let mut registers = Vec::new();
+    program.collect_registers(|register| {
+        if !registers.iter().any(|other| other == register) {
+            registers.push(register.to_owned());
+        }
+    });
+    registers.sort();
+    assert_eq!(
+        registers,
+        vec![
+            "Auxiliar",
+            "Auxiliar_",
+            "Contador1",
+            "Contador1_",
+            "Contador2",
+            "Y"
+        ]
+    );
+}
@@ -1,40 +1,157 @@
 #[cfg(test)]
 mod test;
 
+use indexmap::{map, IndexMap};
 use num_bigint::BigUint;
-use num_traits::identities::Zero;
-use std::{
-    cmp::Ordering,
-    collections::{hash_map, HashMap},
-};
+use num_traits::{identities::Zero, ToPrimitive};
+use std::{cmp::Ordering, collections::HashMap, error::Error, fmt};
+
+/// Valor de um registrador da Norma: um número natural arbitrário.
+///
+/// Valores que cabem em um `u64` são guardados diretamente, sem alocação, e
+/// só passam a usar `BigUint` quando ultrapassam `u64::MAX`. Como quase todo
+/// programa trabalha com valores pequenos, `inc`, `dec` e `is_zero` raramente
+/// precisam de aritmética de inteiros grandes.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Value {
+    /// Representação interna do valor.
+    repr: Repr,
+}
+
+/// Representação interna de um valor. Invariante: `Big` só guarda valores
+/// maiores que `u64::MAX`, de forma que cada número tem uma única
+/// representação (e a ordem derivada das variantes é a ordem numérica).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Repr {
+    /// Valor que cabe em um `u64`.
+    Small(u64),
+    /// Valor maior que `u64::MAX`.
+    Big(BigUint),
+}
+
+impl Value {
+    /// Cria um valor zerado.
+    pub fn zero() -> Self {
+        Self { repr: Repr::Small(0) }
+    }
+
+    /// Testa se o valor é zero.
+    pub fn is_zero(&self) -> bool {
+        matches!(self.repr, Repr::Small(0))
+    }
+
+    /// Retorna o valor como `u64`, se couber.
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.repr {
+            Repr::Small(number) => Some(number),
+            Repr::Big(_) => None,
+        }
+    }
+
+    /// Converte o valor para `BigUint`.
+    pub fn to_biguint(&self) -> BigUint {
+        match &self.repr {
+            Repr::Small(number) => BigUint::from(*number),
+            Repr::Big(number) => number.clone(),
+        }
+    }
+
+    /// Compara o valor a uma constante e retorna se ele é menor, igual ou
+    /// maior à ela.
+    pub fn cmp_biguint(&self, constant: &BigUint) -> Ordering {
+        match (&self.repr, constant.to_u64()) {
+            (Repr::Small(number), Some(constant)) => number.cmp(&constant),
+            (Repr::Small(_), None) => Ordering::Less,
+            (Repr::Big(_), Some(_)) => Ordering::Greater,
+            (Repr::Big(number), None) => number.cmp(constant),
+        }
+    }
+}
+
+impl Default for Value {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl From<u64> for Value {
+    fn from(number: u64) -> Self {
+        Self { repr: Repr::Small(number) }
+    }
+}
+
+impl From<BigUint> for Value {
+    fn from(number: BigUint) -> Self {
+        match number.to_u64() {
+            Some(small) => Self { repr: Repr::Small(small) },
+            None => Self { repr: Repr::Big(number) },
+        }
+    }
+}
+
+impl From<Value> for BigUint {
+    fn from(value: Value) -> Self {
+        match value.repr {
+            Repr::Small(number) => BigUint::from(number),
+            Repr::Big(number) => number,
+        }
+    }
+}
+
+impl PartialEq<BigUint> for Value {
+    fn eq(&self, other: &BigUint) -> bool {
+        self.cmp_biguint(other) == Ordering::Equal
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match &self.repr {
+            Repr::Small(number) => write!(fmtr, "{}", number),
+            Repr::Big(number) => write!(fmtr, "{}", number),
+        }
+    }
+}
 
 /// Um registrador da norma (sendo um  número natural arbitrário).
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 struct Register {
     /// Valor do registrador em número natural (tradicional da Norma).
-    value: BigUint,
+    value: Value,
 }
 
 impl Register {
     /// Cria um novo registrador com o valor desejado
     fn new(number: BigUint) -> Register {
-        Register { value: number }
+        Register { value: Value::from(number) }
     }
 
     /// Cria um novo registrador com valor zero.
     fn new_empty() -> Register {
-        Register { value: BigUint::zero() }
+        Register { value: Value::zero() }
     }
 
     /// Incrementa o valor do registrador.
     fn inc(&mut self) {
-        self.value += 1u8
+        match &mut self.value.repr {
+            Repr::Small(number) => match number.checked_add(1) {
+                Some(next) => *number = next,
+                None => {
+                    self.value.repr = Repr::Big(BigUint::from(*number) + 1u8)
+                }
+            },
+            Repr::Big(number) => *number += 1u8,
+        }
     }
 
     /// Decrementa o valor do registrador (caso seja maior que 0).
     fn dec(&mut self) {
-        if !self.is_zero() {
-            self.value -= 1u8
+        match &mut self.value.repr {
+            Repr::Small(number) => *number = number.saturating_sub(1),
+            Repr::Big(number) => {
+                *number -= 1u8;
+                self.normalize();
+            }
         }
     }
 
@@ -45,46 +162,99 @@ impl Register {
 
     /// Limpa o valor do registrador (define-o para zero).
     fn clear(&mut self) {
-        self.value.set_zero();
+        self.value = Value::zero();
     }
 
     /// Adiciona uma constante ao registrador.
     fn add(&mut self, constant: &BigUint) {
-        self.value += constant;
+        match (&mut self.value.repr, constant.to_u64()) {
+            (Repr::Small(number), Some(small)) => {
+                match number.checked_add(small) {
+                    Some(sum) => *number = sum,
+                    None => {
+                        self.value.repr =
+                            Repr::Big(BigUint::from(*number) + constant)
+                    }
+                }
+            }
+            (Repr::Small(number), None) => {
+                self.value.repr = Repr::Big(BigUint::from(*number) + constant)
+            }
+            (Repr::Big(number), _) => *number += constant,
+        }
     }
 
     /// Subtrai uma constante do registrador. A subtração satura no zero, caso a
     /// constante seja maior que o valor armazenado.
     fn sub(&mut self, constant: &BigUint) {
-        if self.value <= *constant {
-            self.value.set_zero();
-        } else {
-            self.value -= constant;
+        match (&mut self.value.repr, constant.to_u64()) {
+            (Repr::Small(number), Some(small)) => {
+                *number = number.saturating_sub(small)
+            }
+            (Repr::Small(number), None) => *number = 0,
+            (Repr::Big(number), _) => {
+                if *number <= *constant {
+                    self.clear();
+                } else {
+                    *number -= constant;
+                    self.normalize();
+                }
+            }
         }
     }
 
     /// Compara o registrador a uma constante e retorna se o valor armazenado é
     /// menor, igual ou maior à ela.
     fn cmp(&self, constant: &BigUint) -> Ordering {
-        self.value.cmp(constant)
+        self.value.cmp_biguint(constant)
     }
 
     /// Retorna o valor do registrador.
     fn get_value(&self) -> BigUint {
-        self.value.clone()
+        self.value.to_biguint()
     }
 
     /// Define o valor do registrador.
     fn set_value(&mut self, value: BigUint) {
-        self.value = value;
+        self.value = Value::from(value);
+    }
+
+    /// Volta a guardar o valor diretamente em um `u64` caso ele caiba,
+    /// mantendo o invariante de [`Repr`].
+    fn normalize(&mut self) {
+        if let Repr::Big(number) = &self.value.repr {
+            if let Some(small) = number.to_u64() {
+                self.value.repr = Repr::Small(small);
+            }
+        }
+    }
+}
+
+/// Identificador de um registrador em uma máquina: a posição do registrador
+/// no banco. Obtido com [`Machine::register_id`], permite acessar o
+/// registrador sem pesquisar pelo nome.
+///
+/// Como registradores nunca são removidos, o identificador continua válido
+/// enquanto a máquina existir, mas só faz sentido na máquina que o criou.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RegisterId(usize);
+
+impl RegisterId {
+    /// A posição do registrador no banco, começando em zero.
+    pub fn index(self) -> usize {
+        self.0
     }
 }
 
 /// Banco de registradores da Norma.
+///
+/// Os registradores são mantidos na ordem em que foram criados: X e Y
+/// primeiro, e depois os demais na ordem em que aparecem no programa. Cada
+/// nome é guardado uma única vez, e pode ser trocado por um [`RegisterId`].
 #[derive(Debug, Clone)]
 pub struct Machine {
-    /// Mapa de nomes de registradores para seus valores.
-    registers: HashMap<String, Register>,
+    /// Mapa ordenado de nomes de registradores para seus valores.
+    registers: IndexMap<String, Register>,
 }
 
 impl Default for Machine {
@@ -100,13 +270,14 @@ impl Machine {
     /// desejado Y: Registrador de saída, armazenará o valor retornado ao fim
     /// da execução
     pub fn new(input: BigUint) -> Machine {
-        let mut this = Self { registers: HashMap::new() };
+        let mut this = Self { registers: IndexMap::new() };
         this.insert_with_value("X", input);
         this.insert("Y");
         this
     }
 
-    /// Cria um iterador sobre nomes de registradores.
+    /// Cria um iterador sobre nomes de registradores, na ordem em que foram
+    /// criados.
     ///
     /// # Exemplo:
     /// ```ignore
@@ -118,9 +289,176 @@ impl Machine {
         RegisterNames { inner: self.registers.keys() }
     }
 
+    /// Cria um iterador sobre os registradores, com nome e valor de cada um,
+    /// na ordem em que foram criados.
+    pub fn registers(&self) -> Registers<'_> {
+        Registers { inner: self.registers.iter() }
+    }
+
+    /// Reconstrói um banco de registradores a partir de pares `(nome, valor)`,
+    /// como os produzidos por [`Machine::registers`]. X e Y são criados
+    /// zerados caso não estejam entre os pares.
+    pub fn from_registers<I>(registers: I) -> Self
+    where
+        I: IntoIterator<Item = (String, BigUint)>,
+    {
+        let mut this = Self::default();
+        for (reg_name, value) in registers {
+            this.insert_with_value(&reg_name, value);
+        }
+        this
+    }
+
+    /// Retorna o número de registradores no banco.
+    pub fn len(&self) -> usize {
+        self.registers.len()
+    }
+
+    /// Retorna se o banco está vazio. Nunca é o caso para máquinas criadas
+    /// com [`Machine::new`], que já têm X e Y.
+    pub fn is_empty(&self) -> bool {
+        self.registers.is_empty()
+    }
+
+    /// Busca o identificador do registrador de nome `reg_name`, se existir.
+    pub fn register_id(&self, reg_name: &str) -> Option<RegisterId> {
+        self.registers.get_index_of(reg_name).map(RegisterId)
+    }
+
+    /// Retorna o nome do registrador identificado por `id`.
+    ///
+    /// # Panics
+    /// Invoca `panic!` se o identificador não for desta máquina.
+    pub fn register_name(&self, id: RegisterId) -> &str {
+        self.entry(id).0
+    }
+
+    /// Retorna uma referência ao valor do registrador identificado por `id`,
+    /// sem cópia.
+    ///
+    /// # Panics
+    /// Invoca `panic!` se o identificador não for desta máquina.
+    pub fn value_by_id(&self, id: RegisterId) -> &Value {
+        &self.entry(id).1.value
+    }
+
+    /// Incrementa o valor do registrador identificado por `id`, sem pesquisar
+    /// pelo nome.
+    ///
+    /// # Panics
+    /// Invoca `panic!` se o identificador não for desta máquina.
+    pub fn inc_by_id(&mut self, id: RegisterId) {
+        self.entry_mut(id).inc();
+    }
+
+    /// Decrementa o valor do registrador identificado por `id`, sem pesquisar
+    /// pelo nome. Satura em zero.
+    ///
+    /// # Panics
+    /// Invoca `panic!` se o identificador não for desta máquina.
+    pub fn dec_by_id(&mut self, id: RegisterId) {
+        self.entry_mut(id).dec();
+    }
+
+    /// Testa se o valor do registrador identificado por `id` é zero, sem
+    /// pesquisar pelo nome.
+    ///
+    /// # Panics
+    /// Invoca `panic!` se o identificador não for desta máquina.
+    pub fn is_zero_by_id(&self, id: RegisterId) -> bool {
+        self.entry(id).1.is_zero()
+    }
+
+    /// Limpa o valor do registrador identificado por `id` (define-o para
+    /// zero), sem pesquisar pelo nome.
+    ///
+    /// # Panics
+    /// Invoca `panic!` se o identificador não for desta máquina.
+    pub fn clear_by_id(&mut self, id: RegisterId) {
+        self.entry_mut(id).clear();
+    }
+
+    /// Soma uma constante `constant` ao valor do registrador identificado por
+    /// `id`, sem pesquisar pelo nome.
+    ///
+    /// # Panics
+    /// Invoca `panic!` se o identificador não for desta máquina.
+    pub fn add_const_by_id(&mut self, id: RegisterId, constant: &BigUint) {
+        self.entry_mut(id).add(constant);
+    }
+
+    /// Performa uma adição entre registradores, como [`Machine::add`], com os
+    /// registradores identificados por `dest`, `src` e `tmp`.
+    ///
+    /// # Panics
+    /// Invoca `panic!` se algum identificador não for desta máquina.
+    pub fn add_by_id(
+        &mut self,
+        dest: RegisterId,
+        src: RegisterId,
+        tmp: RegisterId,
+    ) {
+        let operand = self.value_by_id(src).to_biguint();
+        self.entry_mut(dest).add(&operand);
+        self.entry_mut(tmp).clear();
+    }
+
+    /// Subtrai uma constante `constant` do valor do registrador identificado
+    /// por `id`, sem pesquisar pelo nome. Satura em zero.
+    ///
+    /// # Panics
+    /// Invoca `panic!` se o identificador não for desta máquina.
+    pub fn sub_const_by_id(&mut self, id: RegisterId, constant: &BigUint) {
+        self.entry_mut(id).sub(constant);
+    }
+
+    /// Performa uma subtração entre registradores, como [`Machine::sub`], com
+    /// os registradores identificados por `dest`, `src` e `tmp`.
+    ///
+    /// # Panics
+    /// Invoca `panic!` se algum identificador não for desta máquina.
+    pub fn sub_by_id(
+        &mut self,
+        dest: RegisterId,
+        src: RegisterId,
+        tmp: RegisterId,
+    ) {
+        let operand = self.value_by_id(src).to_biguint();
+        self.entry_mut(dest).sub(&operand);
+        self.entry_mut(tmp).clear();
+    }
+
+    /// Performa uma comparação entre registradores, como [`Machine::cmp`],
+    /// com os registradores identificados por `left`, `right` e `tmp`.
+    ///
+    /// # Panics
+    /// Invoca `panic!` se algum identificador não for desta máquina.
+    pub fn cmp_by_id(
+        &mut self,
+        left: RegisterId,
+        right: RegisterId,
+        tmp: RegisterId,
+    ) -> Ordering {
+        self.entry_mut(tmp).clear();
+        self.value_by_id(left).cmp(self.value_by_id(right))
+    }
+
+    /// Compara o valor do registrador identificado por `id` a uma constante
+    /// `constant`, sem pesquisar pelo nome.
+    ///
+    /// # Panics
+    /// Invoca `panic!` se o identificador não for desta máquina.
+    pub fn cmp_const_by_id(
+        &self,
+        id: RegisterId,
+        constant: &BigUint,
+    ) -> Ordering {
+        self.entry(id).1.cmp(constant)
+    }
+
     /// Define o valor de entrada (AKA valor do registrador X).
     pub fn input(&mut self, data: BigUint) {
-        self.get_register_mut("X").set_value(data);
+        expect_register(self.try_get_register_mut("X")).set_value(data);
     }
 
     /// Pega o valor de saída (AKA valor do registrador Y).
@@ -169,7 +507,14 @@ impl Machine {
     /// # Panics
     /// Invoca `panic!` se o registrador não existir.
     pub fn clear(&mut self, reg_name: &str) {
-        self.get_register_mut(reg_name).clear();
+        expect_register(self.try_clear(reg_name))
+    }
+
+    /// Limpa o valor do dado registrador (define-o para zero). Falha se o
+    /// registrador não existir.
+    pub fn try_clear(&mut self, reg_name: &str) -> Result<(), MachineError> {
+        self.try_get_register_mut(reg_name)?.clear();
+        Ok(())
     }
 
     /// Incrementa o valor de um registrador existente com nome `reg_name`.
@@ -177,7 +522,14 @@ impl Machine {
     /// # Panics
     /// Invoca `panic!` se o registrador não existir.
     pub fn inc(&mut self, reg_name: &str) {
-        self.get_register_mut(reg_name).inc();
+        expect_register(self.try_inc(reg_name))
+    }
+
+    /// Incrementa o valor de um registrador com nome `reg_name`. Falha se o
+    /// registrador não existir.
+    pub fn try_inc(&mut self, reg_name: &str) -> Result<(), MachineError> {
+        self.try_get_register_mut(reg_name)?.inc();
+        Ok(())
     }
 
     /// Decrementa o valor de um registrador existente com nome `reg_name`.
@@ -186,7 +538,14 @@ impl Machine {
     /// # Panics
     /// Invoca `panic!` se o registrador não existir.
     pub fn dec(&mut self, reg_name: &str) {
-        self.get_register_mut(reg_name).dec();
+        expect_register(self.try_dec(reg_name))
+    }
+
+    /// Decrementa o valor de um registrador com nome `reg_name`. Satura em
+    /// zero. Falha se o registrador não existir.
+    pub fn try_dec(&mut self, reg_name: &str) -> Result<(), MachineError> {
+        self.try_get_register_mut(reg_name)?.dec();
+        Ok(())
     }
 
     /// Performa uma adição entre registradores.
@@ -199,9 +558,23 @@ impl Machine {
     /// Invoca `panic!` se qualquer um dos registradores `dest`, `src` ou `tmp`
     /// não existir.
     pub fn add(&mut self, dest: &str, src: &str, tmp: &str) {
-        let operand = self.get_value(src);
-        self.get_register_mut(dest).add(&operand);
-        self.get_register_mut(tmp).clear();
+        expect_register(self.try_add(dest, src, tmp))
+    }
+
+    /// Performa uma adição entre registradores, como [`Machine::add`]. Falha,
+    /// sem alterar registrador algum, se qualquer um dos registradores `dest`,
+    /// `src` ou `tmp` não existir.
+    pub fn try_add(
+        &mut self,
+        dest: &str,
+        src: &str,
+        tmp: &str,
+    ) -> Result<(), MachineError> {
+        let operand = self.try_get_value(src)?;
+        self.try_get_register(tmp)?;
+        self.try_get_register_mut(dest)?.add(&operand);
+        self.try_get_register_mut(tmp)?.clear();
+        Ok(())
     }
 
     /// Soma uma constante `constant` ao valor de um registrador existente com
@@ -210,7 +583,18 @@ impl Machine {
     /// # Panics
     /// Invoca `panic!` se o registrador não existir.
     pub fn add_const(&mut self, reg_name: &str, constant: &BigUint) {
-        self.get_register_mut(reg_name).add(constant);
+        expect_register(self.try_add_const(reg_name, constant))
+    }
+
+    /// Soma uma constante `constant` ao valor de um registrador com nome
+    /// `reg_name`. Falha se o registrador não existir.
+    pub fn try_add_const(
+        &mut self,
+        reg_name: &str,
+        constant: &BigUint,
+    ) -> Result<(), MachineError> {
+        self.try_get_register_mut(reg_name)?.add(constant);
+        Ok(())
     }
 
     /// Performa uma subtração entre registradores.
@@ -223,9 +607,23 @@ impl Machine {
     /// Invoca `panic!` se qualquer um dos registradores `dest`, `src` ou `tmp`
     /// não existir.
     pub fn sub(&mut self, dest: &str, src: &str, tmp: &str) {
-        let operand = self.get_value(src);
-        self.get_register_mut(dest).sub(&operand);
-        self.get_register_mut(tmp).clear();
+        expect_register(self.try_sub(dest, src, tmp))
+    }
+
+    /// Performa uma subtração entre registradores, como [`Machine::sub`].
+    /// Falha, sem alterar registrador algum, se qualquer um dos registradores
+    /// `dest`, `src` ou `tmp` não existir.
+    pub fn try_sub(
+        &mut self,
+        dest: &str,
+        src: &str,
+        tmp: &str,
+    ) -> Result<(), MachineError> {
+        let operand = self.try_get_value(src)?;
+        self.try_get_register(tmp)?;
+        self.try_get_register_mut(dest)?.sub(&operand);
+        self.try_get_register_mut(tmp)?.clear();
+        Ok(())
     }
 
     /// Subtrai uma constante `constant` do valor de um registrador existente
@@ -234,14 +632,25 @@ impl Machine {
     /// # Panics
     /// Invoca `panic!` se o registrador não existir.
     pub fn sub_const(&mut self, reg_name: &str, constant: &BigUint) {
-        self.get_register_mut(reg_name).sub(constant);
+        expect_register(self.try_sub_const(reg_name, constant))
+    }
+
+    /// Subtrai uma constante `constant` do valor de um registrador com nome
+    /// `reg_name`. Satura em zero. Falha se o registrador não existir.
+    pub fn try_sub_const(
+        &mut self,
+        reg_name: &str,
+        constant: &BigUint,
+    ) -> Result<(), MachineError> {
+        self.try_get_register_mut(reg_name)?.sub(constant);
+        Ok(())
     }
 
     /// Performa uma comparação entre registradores.
     ///
     /// Retorna a ordem (menor/igual/maior) entre `left` e `right`, emulando
-    /// o uso do registrador `tmp` como temporário/auxiliar, que será
-    /// atualizado para zero.
+    /// o uso do registrador `tmp` como temporário/auxiliar, que é
+    /// atualizado para zero antes da comparação.
     ///
     /// # Panics
     /// Invoca `panic!` se qualquer um dos registradores `left`, `right` ou
@@ -252,8 +661,23 @@ impl Machine {
         reg_right: &str,
         reg_tmp: &str,
     ) -> Ordering {
-        self.get_register_mut(reg_tmp).clear();
-        self.get_register(reg_left).cmp(&self.get_register(&reg_right).value)
+        expect_register(self.try_cmp(reg_left, reg_right, reg_tmp))
+    }
+
+    /// Performa uma comparação entre registradores, como [`Machine::cmp`].
+    /// Falha, sem alterar registrador algum, se qualquer um dos registradores
+    /// `left`, `right` ou `tmp` não existir.
+    pub fn try_cmp(
+        &mut self,
+        reg_left: &str,
+        reg_right: &str,
+        reg_tmp: &str,
+    ) -> Result<Ordering, MachineError> {
+        self.try_get_register(reg_left)?;
+        self.try_get_register(reg_right)?;
+        self.try_get_register_mut(reg_tmp)?.clear();
+        let left = self.try_get_register(reg_left)?;
+        Ok(left.value.cmp(&self.try_get_register(reg_right)?.value))
     }
 
     /// Compara o valor do registrador existente de nome `reg_name` a uma
@@ -267,7 +691,17 @@ impl Machine {
         reg_name: &str,
         constant: &BigUint,
     ) -> Ordering {
-        self.get_register(reg_name).cmp(constant)
+        expect_register(self.try_cmp_const(reg_name, constant))
+    }
+
+    /// Compara o valor do registrador de nome `reg_name` a uma constante
+    /// `constant`. Falha se o registrador não existir.
+    pub fn try_cmp_const(
+        &self,
+        reg_name: &str,
+        constant: &BigUint,
+    ) -> Result<Ordering, MachineError> {
+        Ok(self.try_get_register(reg_name)?.cmp(constant))
     }
 
     /// Testa se o valor do registrador existente de nome `reg_name` é zero.
@@ -275,7 +709,13 @@ impl Machine {
     /// # Panics
     /// Invoca `panic!` se o registrador não existir.
     pub fn is_zero(&self, reg_name: &str) -> bool {
-        self.get_register(reg_name).is_zero()
+        expect_register(self.try_is_zero(reg_name))
+    }
+
+    /// Testa se o valor do registrador de nome `reg_name` é zero. Falha se o
+    /// registrador não existir.
+    pub fn try_is_zero(&self, reg_name: &str) -> Result<bool, MachineError> {
+        Ok(self.try_get_register(reg_name)?.is_zero())
     }
 
     /// Retorna o valor de um registrador existente pela sua chave.
@@ -283,7 +723,16 @@ impl Machine {
     /// # Panics
     /// Invoca `panic!` se o registrador não existir.
     pub fn get_value(&self, reg_name: &str) -> BigUint {
-        self.get_register(reg_name).get_value()
+        expect_register(self.try_get_value(reg_name))
+    }
+
+    /// Retorna o valor de um registrador pela sua chave. Falha se o
+    /// registrador não existir.
+    pub fn try_get_value(
+        &self,
+        reg_name: &str,
+    ) -> Result<BigUint, MachineError> {
+        Ok(self.try_get_register(reg_name)?.get_value())
     }
 
     /// Exporta os registradores em um mapa de
@@ -292,44 +741,95 @@ impl Machine {
     pub fn export_registers(&mut self) -> HashMap<String, String> {
         let mut exported: HashMap<String, String> = HashMap::new();
         for (reg_name, reg_obj) in &self.registers {
-            exported
-                .insert(reg_name.to_string(), reg_obj.value.to_str_radix(10));
+            exported.insert(reg_name.to_string(), reg_obj.value.to_string());
         }
         exported
     }
 
-    /// Pesquisa um registrador existente de nome `reg_name` e retorna uma
-    /// referência imutável a ele.
+    /// Busca a entrada (nome e registrador) identificada por `id`.
     ///
     /// # Panics
-    /// Invoca `panic!` se o registrador não existir.
-    fn get_register(&self, reg_name: &str) -> &Register {
-        match self.registers.get(reg_name) {
-            Some(register) => register,
-            None => panic!("Register {} does not exist", reg_name),
+    /// Invoca `panic!` se o identificador não for desta máquina.
+    fn entry(&self, id: RegisterId) -> (&str, &Register) {
+        match self.registers.get_index(id.0) {
+            Some((reg_name, register)) => (reg_name, register),
+            None => panic!("Register id {} does not exist", id.0),
         }
     }
 
-    /// Pesquisa um registrador existente de nome `reg_name` e retorna uma
-    /// referência Mutável a ele, ou seja, possibilita modificação.
+    /// Busca o registrador identificado por `id`, para alterá-lo.
     ///
     /// # Panics
-    /// Invoca `panic!` se o registrador não existir.
-    fn get_register_mut(&mut self, reg_name: &str) -> &mut Register {
-        match self.registers.get_mut(reg_name) {
-            Some(register) => register,
-            None => panic!("Register {} does not exist", reg_name),
+    /// Invoca `panic!` se o identificador não for desta máquina.
+    fn entry_mut(&mut self, id: RegisterId) -> &mut Register {
+        match self.registers.get_index_mut(id.0) {
+            Some((_, register)) => register,
+            None => panic!("Register id {} does not exist", id.0),
         }
     }
+
+    /// Pesquisa um registrador de nome `reg_name` e retorna uma referência
+    /// imutável a ele. Falha se o registrador não existir.
+    fn try_get_register(
+        &self,
+        reg_name: &str,
+    ) -> Result<&Register, MachineError> {
+        self.registers
+            .get(reg_name)
+            .ok_or_else(|| MachineError::UndefinedRegister(reg_name.to_owned()))
+    }
+
+    /// Pesquisa um registrador de nome `reg_name` e retorna uma referência
+    /// mutável a ele. Falha se o registrador não existir.
+    fn try_get_register_mut(
+        &mut self,
+        reg_name: &str,
+    ) -> Result<&mut Register, MachineError> {
+        self.registers
+            .get_mut(reg_name)
+            .ok_or_else(|| MachineError::UndefinedRegister(reg_name.to_owned()))
+    }
+}
+
+/// Extrai o resultado de uma operação da máquina que não pode falhar.
+///
+/// # Panics
+/// Invoca `panic!` se a operação falhou por usar um registrador inexistente.
+fn expect_register<T>(result: Result<T, MachineError>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(MachineError::UndefinedRegister(reg_name)) => {
+            panic!("Register {} does not exist", reg_name)
+        }
+    }
+}
+
+/// Erro em uma operação da máquina.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MachineError {
+    /// O registrador com o dado nome não existe.
+    UndefinedRegister(String),
 }
 
+impl fmt::Display for MachineError {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MachineError::UndefinedRegister(reg_name) => {
+                write!(fmtr, "Registrador {} não existe", reg_name)
+            }
+        }
+    }
+}
+
+impl Error for MachineError {}
+
 /// Iterador sobre nomes de registradores de uma máquina.
 ///
 /// Criado pelo método [`Machine::register_names`].
 #[derive(Debug, Clone)]
 pub struct RegisterNames<'machine> {
     /// Iterador sobre as chaves do mapa de registradores.
-    inner: hash_map::Keys<'machine, String, Register>,
+    inner: map::Keys<'machine, String, Register>,
 }
 
 impl<'machine> Iterator for RegisterNames<'machine> {
@@ -338,4 +838,49 @@ impl<'machine> Iterator for RegisterNames<'machine> {
     fn next(&mut self) -> Option<Self::Item> {
         self.inner.next().map(String::as_ref)
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'machine> DoubleEndedIterator for RegisterNames<'machine> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(String::as_ref)
+    }
+}
+
+impl<'machine> ExactSizeIterator for RegisterNames<'machine> {}
+
+/// Iterador sobre registradores de uma máquina, com nome e valor.
+///
+/// Criado pelo método [`Machine::registers`].
+#[derive(Debug, Clone)]
+pub struct Registers<'machine> {
+    /// Iterador sobre as entradas do mapa de registradores.
+    inner: map::Iter<'machine, String, Register>,
+}
+
+impl<'machine> Iterator for Registers<'machine> {
+    type Item = (&'machine str, &'machine Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|(name, register)| (name.as_str(), &register.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'machine> DoubleEndedIterator for Registers<'machine> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next_back()
+            .map(|(name, register)| (name.as_str(), &register.value))
+    }
 }
+
+impl<'machine> ExactSizeIterator for Registers<'machine> {}
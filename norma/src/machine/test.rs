@@ -1,4 +1,4 @@
-use super::Machine;
+use super::{Machine, MachineError, Value};
 use num_bigint::BigUint;
 use num_traits::identities::{One, Zero};
 use std::cmp::Ordering;
@@ -190,3 +190,168 @@ fn cmp_const() {
     );
     assert_eq!(machine.cmp_const("Y", &BigUint::from(9u64)), Ordering::Less);
 }
+
+#[test]
+fn try_operations() {
+    let mut machine = make_machine();
+    let missing = MachineError::UndefinedRegister(String::from("C"));
+
+    assert_eq!(machine.try_inc("A"), Ok(()));
+    assert_eq!(machine.try_get_value("A"), Ok(BigUint::one()));
+    assert_eq!(machine.try_dec("C"), Err(missing.clone()));
+    assert_eq!(machine.try_is_zero("C"), Err(missing.clone()));
+    assert_eq!(
+        machine.try_cmp_const("B", &BigUint::from(13u8)),
+        Ok(Ordering::Equal)
+    );
+    assert_eq!(missing.to_string(), "Registrador C não existe");
+}
+
+#[test]
+fn try_binary_operations_are_atomic() {
+    let mut machine = make_machine();
+    let missing = MachineError::UndefinedRegister(String::from("C"));
+
+    assert_eq!(machine.try_add("A", "B", "C"), Err(missing.clone()));
+    assert_eq!(machine.try_sub("C", "B", "A"), Err(missing.clone()));
+    assert_eq!(machine.try_cmp("A", "C", "Y"), Err(missing));
+    assert_eq!(machine.get_value("A"), BigUint::zero());
+    assert_eq!(machine.get_value("B"), BigUint::from(13u8));
+
+    assert_eq!(machine.try_add("A", "B", "Y"), Ok(()));
+    assert_eq!(machine.get_value("A"), BigUint::from(13u8));
+}
+
+#[test]
+fn cmp_clears_tmp_first() {
+    let mut machine = make_machine();
+    machine.insert_with_value("C", BigUint::from(13u8));
+
+    assert_eq!(machine.cmp("B", "C", "A"), Ordering::Equal);
+    assert_eq!(machine.cmp("B", "C", "C"), Ordering::Greater);
+    assert_eq!(machine.get_value("C"), BigUint::zero());
+    assert_eq!(machine.cmp("X", "B", "B"), Ordering::Greater);
+    assert_eq!(machine.cmp("A", "A", "A"), Ordering::Equal);
+}
+
+#[test]
+fn register_ids() {
+    let mut machine = make_machine();
+    let names: Vec<_> = machine.register_names().collect();
+    assert_eq!(names, &["X", "Y", "A", "B"]);
+    assert_eq!(machine.len(), 4);
+
+    let id = machine.register_id("B").unwrap();
+    assert_eq!(id.index(), 3);
+    assert_eq!(machine.register_name(id), "B");
+    assert_eq!(machine.value_by_id(id), &BigUint::from(13u8));
+    assert_eq!(machine.register_id("C"), None);
+
+    machine.insert_with_value("A", BigUint::one());
+    assert!(machine.create("C"));
+    assert_eq!(machine.register_id("A").unwrap().index(), 2);
+    assert_eq!(machine.register_id("C").unwrap().index(), 4);
+    assert_eq!(machine.value_by_id(id), &BigUint::from(13u8));
+
+    machine.inc_by_id(id);
+    assert_eq!(machine.get_value("B"), BigUint::from(14u8));
+    let c = machine.register_id("C").unwrap();
+    assert!(machine.is_zero_by_id(c));
+    machine.dec_by_id(c);
+    assert!(machine.is_zero_by_id(c));
+}
+
+#[test]
+fn operations_by_id() {
+    let mut machine = make_machine();
+    let id =
+        |machine: &Machine, reg_name| machine.register_id(reg_name).unwrap();
+    let (x, y, a, b) = (
+        id(&machine, "X"),
+        id(&machine, "Y"),
+        id(&machine, "A"),
+        id(&machine, "B"),
+    );
+    let mut by_name = machine.clone();
+
+    machine.add_by_id(y, b, a);
+    by_name.add("Y", "B", "A");
+    machine.sub_by_id(b, x, a);
+    by_name.sub("B", "X", "A");
+    machine.add_const_by_id(x, &BigUint::from(3u8));
+    by_name.add_const("X", &BigUint::from(3u8));
+    machine.sub_const_by_id(y, &BigUint::from(20u8));
+    by_name.sub_const("Y", &BigUint::from(20u8));
+    assert_eq!(
+        machine.registers().collect::<Vec<_>>(),
+        by_name.registers().collect::<Vec<_>>()
+    );
+
+    assert_eq!(machine.cmp_by_id(b, x, a), Ordering::Greater);
+    assert_eq!(
+        machine.cmp_const_by_id(b, &BigUint::from(9u8)),
+        Ordering::Equal
+    );
+    machine.clear_by_id(b);
+    assert!(machine.is_zero_by_id(b));
+}
+
+#[test]
+fn values_across_u64_boundary() {
+    let max = BigUint::from(u64::MAX);
+    let mut machine = Machine::new(max.clone());
+
+    machine.inc("X");
+    assert_eq!(machine.get_value("X"), &max + 1u8);
+    assert_eq!(
+        machine.value_by_id(machine.register_id("X").unwrap()).to_u64(),
+        None
+    );
+    machine.dec("X");
+    assert_eq!(machine.get_value("X"), max);
+    assert_eq!(
+        machine.value_by_id(machine.register_id("X").unwrap()).to_u64(),
+        Some(u64::MAX)
+    );
+
+    let constants =
+        [BigUint::zero(), BigUint::one(), max.clone(), &max + 1u8, &max * 3u8];
+    for start in &constants {
+        for constant in &constants {
+            let mut machine = Machine::new(start.clone());
+
+            machine.add_const("X", constant);
+            assert_eq!(machine.get_value("X"), start + constant);
+            assert_eq!(
+                machine.cmp_const("X", constant),
+                (start + constant).cmp(constant)
+            );
+
+            machine.input(start.clone());
+            machine.sub_const("X", constant);
+            let expected = if start <= constant {
+                BigUint::zero()
+            } else {
+                start - constant
+            };
+            assert_eq!(machine.get_value("X"), expected);
+            assert_eq!(machine.is_zero("X"), expected.is_zero());
+            assert_eq!(
+                machine.cmp_const("X", constant),
+                expected.cmp(constant)
+            );
+        }
+    }
+}
+
+#[test]
+fn value_conversions() {
+    let big = BigUint::from(u64::MAX) * 2u8;
+    let value = Value::from(big.clone());
+    assert_eq!(value.to_biguint(), big);
+    assert_eq!(value.to_string(), big.to_string());
+    assert!(Value::from(7u64) < value);
+    assert_eq!(Value::from(BigUint::from(7u8)), Value::from(7u64));
+    assert_eq!(BigUint::from(Value::from(7u64)), BigUint::from(7u8));
+    assert!(Value::default().is_zero());
+}
@@ -0,0 +1,280 @@
+//! Funções recursivas parciais (μ-recursivas) e a sua compilação para a
+//! Norma, mostrando de forma construtiva que toda função recursiva parcial é
+//! computável pela Norma.
+//!
+//! Um programa é uma lista de definições `nome = f`, onde `f` é formada por:
+//!
+//! - `zero(n)`: a função constante zero, de aridade `n`;
+//! - `suc`: o sucessor, de aridade 1;
+//! - `proj(n, i)`: a projeção do `i`-ésimo de `n` argumentos;
+//! - `comp(f, g1, ..., gm)`: `f(g1(x), ..., gm(x))`, onde `f` tem aridade `m`
+//!   e todas as `gj` têm a mesma aridade;
+//! - `rec(f, g)`: a recursão primitiva `h(x, 0) = f(x)` e
+//!   `h(x, y + 1) = g(x, y, h(x, y))`, onde `x` são os primeiros argumentos;
+//! - `mu(f)` (ou `μ(f)`): o menor `y` tal que `f(x, y) = 0`, que pode não
+//!   existir, tornando a função indefinida;
+//! - o nome de uma função definida antes, o que impede recursão direta.
+//!
+//! A última definição é a função principal. Por exemplo, a soma:
+//!
+//! ```text
+//! soma = rec(proj(1, 1), comp(suc, proj(3, 3)))
+//! ```
+//!
+//! Cada função vira uma macro de operação da Norma
+//! ([`ast::Macro`](crate::compiler::parser::ast::Macro)), que recebe os
+//! argumentos e o registrador do resultado; a `main` chama a função principal
+//! com os registradores de entrada (ver [`input_registers`]) e o resultado em
+//! `Y`. O programa gerado passa pelo mesmo expansor de macros dos programas da
+//! Norma.
+
+#[cfg(test)]
+mod test;
+
+pub mod error;
+
+mod generate;
+mod parser;
+
+use crate::{
+    compiler::{expansor::expand, parser::ast},
+    interpreter::program::Program as MonolithicProgram,
+    iterative::lexer,
+};
+use num_bigint::BigUint;
+use num_traits::Zero;
+use pinguim_language::error::Diagnostics;
+use std::fmt;
+
+/// Uma função recursiva parcial.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Function {
+    /// `zero(n)`, com a aridade `n`.
+    Zero(usize),
+    /// `suc`.
+    Successor,
+    /// `proj(arity, index)`, com `index` a partir de 1.
+    Projection {
+        /// Número de argumentos.
+        arity: usize,
+        /// Argumento projetado, a partir de 1.
+        index: usize,
+    },
+    /// `comp(outer, inner...)`.
+    Composition {
+        /// A função aplicada aos resultados.
+        outer: Box<Function>,
+        /// As funções aplicadas aos argumentos.
+        inner: Vec<Function>,
+    },
+    /// `rec(base, step)`.
+    Recursion {
+        /// O caso base, `h(x, 0)`.
+        base: Box<Function>,
+        /// O passo, que recebe `x`, `y` e `h(x, y)`.
+        step: Box<Function>,
+    },
+    /// `mu(f)`.
+    Minimization(Box<Function>),
+    /// Uma função definida antes, pelo nome.
+    Named(String),
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Function::Zero(arity) => write!(fmtr, "zero({})", arity),
+            Function::Successor => write!(fmtr, "suc"),
+            Function::Projection { arity, index } => {
+                write!(fmtr, "proj({}, {})", arity, index)
+            }
+            Function::Composition { outer, inner } => {
+                write!(fmtr, "comp({}", outer)?;
+                for function in inner {
+                    write!(fmtr, ", {}", function)?;
+                }
+                write!(fmtr, ")")
+            }
+            Function::Recursion { base, step } => {
+                write!(fmtr, "rec({}, {})", base, step)
+            }
+            Function::Minimization(function) => {
+                write!(fmtr, "mu({})", function)
+            }
+            Function::Named(name) => write!(fmtr, "{}", name),
+        }
+    }
+}
+
+/// Uma definição `name = function`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Definition {
+    /// O nome da função.
+    pub name: String,
+    /// A função.
+    pub function: Function,
+    /// A aridade da função.
+    pub arity: usize,
+}
+
+impl fmt::Display for Definition {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmtr, "{} = {}", self.name, self.function)
+    }
+}
+
+/// Um programa de funções recursivas parciais: uma lista de definições, em
+/// que cada uma só usa as anteriores. A última é a função principal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    /// As definições, na ordem do texto.
+    pub definitions: Vec<Definition>,
+}
+
+impl Program {
+    /// A função principal, a última definida.
+    pub fn main(&self) -> Option<&Definition> {
+        self.definitions.last()
+    }
+
+    /// Busca uma definição pelo nome.
+    pub fn definition(&self, name: &str) -> Option<&Definition> {
+        self.definitions.iter().find(|definition| definition.name == name)
+    }
+
+    /// Aridade de uma função deste programa.
+    ///
+    /// # Panics
+    ///
+    /// Invoca `panic!()` se a função usar um nome não definido.
+    pub fn arity(&self, function: &Function) -> usize {
+        match function {
+            Function::Zero(arity) | Function::Projection { arity, .. } => {
+                *arity
+            }
+            Function::Successor => 1,
+            Function::Composition { inner, .. } => {
+                inner.first().map_or(0, |function| self.arity(function))
+            }
+            Function::Recursion { base, .. } => self.arity(base) + 1,
+            Function::Minimization(function) => self.arity(function) - 1,
+            Function::Named(name) => self.named(name).arity,
+        }
+    }
+
+    /// Avalia diretamente uma função deste programa. Assim como a função, a
+    /// avaliação não termina se um `mu` não encontrar um zero.
+    ///
+    /// # Panics
+    ///
+    /// Invoca `panic!()` se o número de argumentos não for a aridade, ou se a
+    /// função usar um nome não definido.
+    pub fn apply(&self, function: &Function, args: &[BigUint]) -> BigUint {
+        match function {
+            Function::Zero(_) => BigUint::zero(),
+            Function::Successor => &args[0] + 1u8,
+            Function::Projection { index, .. } => args[index - 1].clone(),
+            Function::Composition { outer, inner } => {
+                let values: Vec<_> = inner
+                    .iter()
+                    .map(|function| self.apply(function, args))
+                    .collect();
+                self.apply(outer, &values)
+            }
+            Function::Recursion { base, step } => {
+                let (limit, args) = args.split_last().expect("aridade zero");
+                let mut value = self.apply(base, args);
+                let mut counter = BigUint::zero();
+                while &counter < limit {
+                    let mut step_args = args.to_vec();
+                    step_args.push(counter.clone());
+                    step_args.push(value);
+                    value = self.apply(step, &step_args);
+                    counter += 1u8;
+                }
+                value
+            }
+            Function::Minimization(function) => {
+                let mut candidate = BigUint::zero();
+                loop {
+                    let mut function_args = args.to_vec();
+                    function_args.push(candidate.clone());
+                    if self.apply(function, &function_args).is_zero() {
+                        break candidate;
+                    }
+                    candidate += 1u8;
+                }
+            }
+            Function::Named(name) => {
+                self.apply(&self.named(name).function, args)
+            }
+        }
+    }
+
+    /// Avalia diretamente a função principal.
+    ///
+    /// # Panics
+    ///
+    /// Invoca `panic!()` se o programa não tiver definições ou se o número de
+    /// argumentos não for a aridade.
+    pub fn evaluate(&self, args: &[BigUint]) -> BigUint {
+        let main = self.main().expect("programa sem definições");
+        assert_eq!(args.len(), main.arity, "número de argumentos errado");
+        self.apply(&main.function, args)
+    }
+
+    /// Gera o programa da Norma equivalente: uma macro de operação por função
+    /// e uma `main` que chama a função principal.
+    pub fn to_ast(&self) -> ast::Program {
+        generate::generate(self)
+    }
+
+    /// Busca uma definição que deve existir.
+    fn named(&self, name: &str) -> &Definition {
+        self.definition(name).expect("função deveria estar definida")
+    }
+}
+
+impl fmt::Display for Program {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        for definition in &self.definitions {
+            writeln!(fmtr, "{}", definition)?;
+        }
+        Ok(())
+    }
+}
+
+/// Registradores de entrada de uma função com a dada aridade: `X` se houver
+/// um único argumento, senão `X1`, `X2`, ...
+pub fn input_registers(arity: usize) -> Vec<String> {
+    if arity == 1 {
+        vec![String::from("X")]
+    } else {
+        (1..=arity).map(|index| format!("X{}", index)).collect()
+    }
+}
+
+/// Analisa o código-fonte de um programa de funções recursivas parciais.
+pub fn parse(source: &str) -> Result<Program, Diagnostics> {
+    let mut diagnostics = Diagnostics::new();
+    let tokens = lexer::generate_tokens(source, &mut diagnostics);
+    let maybe_program = parser::parse(tokens, &mut diagnostics);
+
+    match maybe_program {
+        Some(program) if diagnostics.is_ok() => Ok(program),
+        _ => Err(diagnostics),
+    }
+}
+
+/// Compila o código-fonte de um programa de funções recursivas parciais para
+/// um programa monolítico, passando pelo expansor de macros.
+pub fn compile(source: &str) -> Result<MonolithicProgram, Diagnostics> {
+    let program = parse(source)?;
+    let mut diagnostics = Diagnostics::new();
+    let maybe_program = expand(&program.to_ast(), &mut diagnostics);
+
+    match maybe_program {
+        Some(program) if diagnostics.is_ok() => Ok(program),
+        _ => Err(diagnostics),
+    }
+}
@@ -0,0 +1,84 @@
+use std::{error::Error, fmt};
+
+#[derive(Debug, Clone)]
+/// Erro em que uma função é usada com uma aridade diferente da esperada
+pub struct ArityMismatch {
+    ///
+    /// - `expected`: aridade esperada
+    pub expected: usize,
+    ///
+    /// - `found`: aridade da função encontrada
+    pub found: usize,
+}
+
+impl fmt::Display for ArityMismatch {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "Esperava-se uma função de aridade {}, encontrada uma de aridade {}",
+            self.expected, self.found
+        )
+    }
+}
+
+impl Error for ArityMismatch {}
+
+#[derive(Debug, Clone)]
+/// Erro em que uma projeção escolhe um argumento que não existe
+pub struct InvalidProjection {
+    ///
+    /// - `arity`: aridade da projeção
+    pub arity: usize,
+    ///
+    /// - `index`: argumento escolhido
+    pub index: usize,
+}
+
+impl fmt::Display for InvalidProjection {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "Projeção inválida: o argumento deve estar entre 1 e {}, \
+             encontrado {}",
+            self.arity, self.index
+        )
+    }
+}
+
+impl Error for InvalidProjection {}
+
+#[derive(Debug, Clone)]
+/// Erro em que uma função usada não foi definida antes
+pub struct UndefinedFunction {
+    ///
+    /// - `name`: nome da função
+    pub name: String,
+}
+
+impl fmt::Display for UndefinedFunction {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "Função \"{}\" não foi definida", self.name)
+    }
+}
+
+impl Error for UndefinedFunction {}
+
+#[derive(Debug, Clone)]
+/// Erro em que uma função é definida mais de uma vez
+pub struct DuplicateFunction {
+    ///
+    /// - `name`: nome da função
+    pub name: String,
+}
+
+impl fmt::Display for DuplicateFunction {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "Função \"{}\" foi definida mais de uma vez",
+            self.name
+        )
+    }
+}
+
+impl Error for DuplicateFunction {}
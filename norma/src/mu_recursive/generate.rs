@@ -0,0 +1,360 @@
+//! Geração das macros da Norma para as funções recursivas parciais.
+//!
+//! Cada função vira uma macro de operação `f (A1, ..., An, R)`, que guarda
+//! `f(A1, ..., An)` em `R` e preserva os argumentos. Os temporários de cada
+//! macro são registradores globais prefixados pelo nome da macro: como as
+//! funções não podem chamar a si mesmas, uma macro nunca está ativa duas vezes
+//! ao mesmo tempo.
+
+use super::{input_registers, Function, Program};
+use crate::compiler::{
+    lexer::token::{BuiltInOperation, BuiltInTest},
+    parser::ast,
+};
+use indexmap::IndexMap;
+use pinguim_language::position::Span;
+use std::collections::HashSet;
+
+/// Rótulo de saída das macros geradas.
+const EXIT_LABEL: &str = "0";
+
+/// Gera o programa da Norma equivalente.
+pub fn generate(program: &Program) -> ast::Program {
+    let mut used: HashSet<_> = program
+        .definitions
+        .iter()
+        .map(|definition| definition.name.clone())
+        .collect();
+    let mut helper = |base: &str| fresh_name(base, &mut used);
+    let helpers = Helpers {
+        clear: helper("clear"),
+        move_to: helper("move"),
+        copy: helper("copy"),
+    };
+
+    let mut generator =
+        Generator { program, helpers, used, macros: IndexMap::new() };
+    generator.generate_helpers();
+    for definition in &program.definitions {
+        generator.generate_macro(
+            definition.name.clone(),
+            &definition.name,
+            &definition.function,
+        );
+    }
+
+    let mut code = Code::default();
+    if let Some(main) = program.main() {
+        let mut args = input_registers(main.arity);
+        args.push(String::from("Y"));
+        code.call("1", &main.name, &args, EXIT_LABEL);
+    }
+    let main =
+        ast::Main { code: code.instr, docs: Vec::new(), tests: Vec::new() };
+
+    ast::Program { main, macros: generator.macros }
+}
+
+/// Nomes das macros auxiliares, escolhidos para não colidir com as funções.
+#[derive(Debug)]
+struct Helpers {
+    /// `clear (A)`: zera `A`.
+    clear: String,
+    /// `move (A, B)`: `B := A` e zera `A`.
+    move_to: String,
+    /// `copy (A, B, T)`: `B := A`, preservando `A` e zerando `T`.
+    copy: String,
+}
+
+/// Gerador das macros.
+#[derive(Debug)]
+struct Generator<'prog> {
+    program: &'prog Program,
+    helpers: Helpers,
+    /// Nomes de macros já usados.
+    used: HashSet<String>,
+    /// Macros geradas.
+    macros: IndexMap<String, ast::Macro>,
+}
+
+impl<'prog> Generator<'prog> {
+    /// Gera as macros auxiliares.
+    fn generate_helpers(&mut self) {
+        let Helpers { clear, move_to, copy } = &self.helpers;
+
+        let mut code = Code::default();
+        code.test_zero("1", "A", EXIT_LABEL, "2");
+        code.builtin("2", BuiltInOperation::Dec, "A", "1");
+        let clear_macro = make_macro(clear, &["A"], code);
+
+        let mut code = Code::default();
+        code.call("1", clear, &["B"], "2");
+        code.test_zero("2", "A", EXIT_LABEL, "3");
+        code.builtin("3", BuiltInOperation::Dec, "A", "4");
+        code.builtin("4", BuiltInOperation::Inc, "B", "2");
+        let move_macro = make_macro(move_to, &["A", "B"], code);
+
+        let mut code = Code::default();
+        code.call("1", clear, &["B"], "2");
+        code.call("2", clear, &["T"], "3");
+        code.test_zero("3", "A", "7", "4");
+        code.builtin("4", BuiltInOperation::Dec, "A", "5");
+        code.builtin("5", BuiltInOperation::Inc, "B", "6");
+        code.builtin("6", BuiltInOperation::Inc, "T", "3");
+        code.test_zero("7", "T", EXIT_LABEL, "8");
+        code.builtin("8", BuiltInOperation::Dec, "T", "9");
+        code.builtin("9", BuiltInOperation::Inc, "A", "7");
+        let copy_macro = make_macro(copy, &["A", "B", "T"], code);
+
+        for helper in [clear_macro, move_macro, copy_macro] {
+            self.macros.insert(helper.name.content.clone(), helper);
+        }
+    }
+
+    /// Retorna o nome da macro que calcula a função, gerando-a se preciso com
+    /// o nome dado. Subfunções anônimas ganham nomes derivados de `owner`.
+    fn generate_macro(
+        &mut self,
+        name: String,
+        owner: &str,
+        function: &Function,
+    ) -> String {
+        let arity = self.program.arity(function);
+        let params = parameters(arity);
+        let result = String::from("R");
+        let temp = |suffix: &str| format!("{}_{}", name, suffix);
+        let mut code = Code::default();
+
+        match function {
+            Function::Named(other) => {
+                let mut args = params.clone();
+                args.push(result.clone());
+                code.call("1", other, &args, EXIT_LABEL);
+            }
+            Function::Zero(_) => {
+                code.call("1", &self.helpers.clear, &[&result], EXIT_LABEL);
+            }
+            Function::Successor => {
+                let args = [params[0].as_str(), &result, &temp("T")];
+                code.call("1", &self.helpers.copy, &args, "2");
+                code.builtin("2", BuiltInOperation::Inc, &result, EXIT_LABEL);
+            }
+            Function::Projection { index, .. } => {
+                let args = [params[index - 1].as_str(), &result, &temp("T")];
+                code.call("1", &self.helpers.copy, &args, EXIT_LABEL);
+            }
+            Function::Composition { outer, inner } => {
+                let mut values = Vec::new();
+                for (i, function) in inner.iter().enumerate() {
+                    let callee = self.generate_sub_macro(owner, function);
+                    let value = temp(&format!("G{}", i + 1));
+                    let mut args = params.clone();
+                    args.push(value.clone());
+                    let (label, next) =
+                        ((i + 1).to_string(), (i + 2).to_string());
+                    code.call(&label, &callee, &args, &next);
+                    values.push(value);
+                }
+                let callee = self.generate_sub_macro(owner, outer);
+                values.push(result.clone());
+                let label = (inner.len() + 1).to_string();
+                code.call(&label, &callee, &values, EXIT_LABEL);
+            }
+            Function::Recursion { base, step } => {
+                let base = self.generate_sub_macro(owner, base);
+                let step = self.generate_sub_macro(owner, step);
+                let (value, next_value) = (temp("H"), temp("G"));
+                let (limit, counter) = (temp("K"), temp("C"));
+                let (args, last) = params.split_at(arity - 1);
+
+                let mut base_args = args.to_vec();
+                base_args.push(value.clone());
+                code.call("1", &base, &base_args, "2");
+                let limit_args = [last[0].as_str(), &limit, &temp("T")];
+                code.call("2", &self.helpers.copy, &limit_args, "3");
+                code.call("3", &self.helpers.clear, &[&counter], "4");
+                code.test_zero("4", &limit, "9", "5");
+                let mut step_args = args.to_vec();
+                step_args.extend([
+                    counter.clone(),
+                    value.clone(),
+                    next_value.clone(),
+                ]);
+                code.call("5", &step, &step_args, "6");
+                code.call(
+                    "6",
+                    &self.helpers.move_to,
+                    &[&next_value, &value],
+                    "7",
+                );
+                code.builtin("7", BuiltInOperation::Inc, &counter, "8");
+                code.builtin("8", BuiltInOperation::Dec, &limit, "4");
+                code.call(
+                    "9",
+                    &self.helpers.move_to,
+                    &[&value, &result],
+                    EXIT_LABEL,
+                );
+            }
+            Function::Minimization(function) => {
+                let callee = self.generate_sub_macro(owner, function);
+                let (candidate, value) = (temp("C"), temp("V"));
+
+                code.call("1", &self.helpers.clear, &[&candidate], "2");
+                let mut args = params.clone();
+                args.extend([candidate.clone(), value.clone()]);
+                code.call("2", &callee, &args, "3");
+                code.test_zero("3", &value, "4", "5");
+                code.call(
+                    "4",
+                    &self.helpers.move_to,
+                    &[&candidate, &result],
+                    EXIT_LABEL,
+                );
+                code.builtin("5", BuiltInOperation::Inc, &candidate, "2");
+            }
+        }
+
+        let mut all_params = params;
+        all_params.push(result);
+        let macro_def = make_macro(&name, &all_params, code);
+        self.macros.insert(name.clone(), macro_def);
+        name
+    }
+
+    /// Retorna o nome da macro de uma subfunção, gerando uma macro anônima se
+    /// ela não for uma função definida pelo nome.
+    fn generate_sub_macro(
+        &mut self,
+        owner: &str,
+        function: &Function,
+    ) -> String {
+        match function {
+            Function::Named(other) => other.clone(),
+            _ => {
+                let mut index = 1;
+                while self.used.contains(&format!("{}_{}", owner, index)) {
+                    index += 1;
+                }
+                let name = format!("{}_{}", owner, index);
+                self.used.insert(name.clone());
+                self.generate_macro(name, owner, function)
+            }
+        }
+    }
+}
+
+/// Instruções de uma macro em construção.
+#[derive(Debug, Default)]
+struct Code {
+    instr: IndexMap<String, ast::Instruction>,
+}
+
+impl Code {
+    /// Insere `label: do callee (args) goto next`.
+    fn call<S>(&mut self, label: &str, callee: &str, args: &[S], next: &str)
+    where
+        S: AsRef<str>,
+    {
+        let args = args
+            .iter()
+            .map(|arg| ast::MacroArgument::Register(symbol(arg.as_ref())))
+            .collect();
+        let oper_type = ast::OperationType::Macro(symbol(callee), args);
+        self.operation(label, oper_type, next);
+    }
+
+    /// Insere `label: do inc/dec register goto next`.
+    fn builtin(
+        &mut self,
+        label: &str,
+        operation: BuiltInOperation,
+        register: &str,
+        next: &str,
+    ) {
+        let oper_type =
+            ast::OperationType::BuiltIn(operation, symbol(register));
+        self.operation(label, oper_type, next);
+    }
+
+    /// Insere `label: if zero register then goto then else goto otherwise`.
+    fn test_zero(
+        &mut self,
+        label: &str,
+        register: &str,
+        then: &str,
+        otherwise: &str,
+    ) {
+        let test = ast::Test {
+            test_type: ast::TestType::BuiltIn(
+                BuiltInTest::Zero,
+                symbol(register),
+            ),
+            span: Span::default(),
+            next_true_label: symbol(then),
+            next_false_label: symbol(otherwise),
+        };
+        self.insert(label, ast::InstructionType::Test(test));
+    }
+
+    /// Insere uma operação.
+    fn operation(
+        &mut self,
+        label: &str,
+        oper_type: ast::OperationType,
+        next: &str,
+    ) {
+        let operation = ast::Operation {
+            oper_type,
+            span: Span::default(),
+            next_label: symbol(next),
+        };
+        self.insert(label, ast::InstructionType::Operation(operation));
+    }
+
+    /// Insere uma instrução.
+    fn insert(&mut self, label: &str, instruction_type: ast::InstructionType) {
+        let instruction =
+            ast::Instruction { label: symbol(label), instruction_type };
+        self.instr.insert(label.to_owned(), instruction);
+    }
+}
+
+/// Cria uma macro de operação.
+fn make_macro<S>(name: &str, parameters: &[S], code: Code) -> ast::Macro
+where
+    S: AsRef<str>,
+{
+    ast::Macro {
+        macro_type: ast::MacroType::Operation,
+        name: symbol(name),
+        parameters: parameters
+            .iter()
+            .map(|param| symbol(param.as_ref()))
+            .collect(),
+        instr: code.instr,
+        docs: Vec::new(),
+        tests: Vec::new(),
+    }
+}
+
+/// Parâmetros dos argumentos de uma função: `A1`, `A2`, ...
+fn parameters(arity: usize) -> Vec<String> {
+    (1..=arity).map(|index| format!("A{}", index)).collect()
+}
+
+/// Escolhe um nome a partir de `base` que não esteja em uso, acrescentando
+/// `_` se preciso, e o marca como usado.
+fn fresh_name(base: &str, used: &mut HashSet<String>) -> String {
+    let mut name = base.to_owned();
+    while used.contains(&name) {
+        name.push('_');
+    }
+    used.insert(name.clone());
+    name
+}
+
+/// Cria um símbolo sem localização no código.
+fn symbol(content: &str) -> ast::Symbol {
+    ast::Symbol { content: content.to_owned(), span: Span::default() }
+}
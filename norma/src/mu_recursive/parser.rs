@@ -0,0 +1,209 @@
+use super::{
+    error::{
+        ArityMismatch, DuplicateFunction, InvalidProjection, UndefinedFunction,
+    },
+    Definition, Function, Program,
+};
+use crate::iterative::{
+    lexer::{Token, TokenType},
+    parser::Parser,
+};
+use pinguim_language::{
+    error::{Diagnostics, Error},
+    position::Span,
+};
+
+/// Palavras que não podem ser usadas como nome de função.
+const RESERVED: &[&str] = &["zero", "suc", "proj", "comp", "rec", "mu", "μ"];
+
+/// Analisa os tokens de um programa de funções recursivas parciais, checando
+/// as aridades. Para no primeiro erro.
+pub fn parse(
+    tokens: Vec<Token>,
+    diagnostics: &mut Diagnostics,
+) -> Option<Program> {
+    let mut parser = FunctionParser {
+        parser: Parser { tokens, position: 0 },
+        definitions: Vec::new(),
+    };
+
+    match parser.parse_definitions() {
+        Ok(()) => Some(Program { definitions: parser.definitions }),
+        Err(error) => {
+            diagnostics.raise(error);
+            None
+        }
+    }
+}
+
+/// Analisador sintático de funções recursivas parciais.
+struct FunctionParser {
+    parser: Parser,
+    /// Definições já analisadas, que podem ser usadas pelas seguintes.
+    definitions: Vec<Definition>,
+}
+
+impl FunctionParser {
+    /// Analisa as definições até o fim do código.
+    fn parse_definitions(&mut self) -> Result<(), Error> {
+        loop {
+            let token = self.parser.next("uma definição")?;
+            if !is_identifier(&token) {
+                return Err(self.parser.unexpected(&token, "uma definição"));
+            }
+            if self.definition(&token.content).is_some() {
+                let name = token.content;
+                return Err(Error::new(DuplicateFunction { name }, token.span));
+            }
+
+            self.parser.expect_type(TokenType::Equals, "\"=\"")?;
+            let (function, arity, _) = self.parse_function()?;
+            self.definitions.push(Definition {
+                name: token.content,
+                function,
+                arity,
+            });
+
+            if self.parser.position == self.parser.tokens.len() {
+                break Ok(());
+            }
+        }
+    }
+
+    /// Analisa uma função, retornando-a junto da sua aridade e da localização
+    /// do seu primeiro token.
+    fn parse_function(&mut self) -> Result<(Function, usize, Span), Error> {
+        let token = self.parser.next("uma função")?;
+
+        let (function, arity) = if token.is_keyword("zero") {
+            self.open()?;
+            let arity = self.parse_number()?;
+            self.close()?;
+            (Function::Zero(arity), arity)
+        } else if token.is_keyword("suc") {
+            (Function::Successor, 1)
+        } else if token.is_keyword("proj") {
+            self.open()?;
+            let arity = self.parse_number()?;
+            self.comma()?;
+            let index = self.parse_number()?;
+            self.close()?;
+            if index == 0 || index > arity {
+                let cause = InvalidProjection { arity, index };
+                return Err(Error::new(cause, token.span));
+            }
+            (Function::Projection { arity, index }, arity)
+        } else if token.is_keyword("comp") {
+            self.open()?;
+            let (outer, outer_arity, outer_span) = self.parse_function()?;
+            let mut inner = Vec::new();
+            let mut arity = None;
+            loop {
+                self.comma()?;
+                let (function, found, span) = self.parse_function()?;
+                let expected = *arity.get_or_insert(found);
+                check_arity(expected, found, span)?;
+                inner.push(function);
+                if !self.parser.check(TokenType::Comma) {
+                    break;
+                }
+            }
+            self.close()?;
+            check_arity(inner.len(), outer_arity, outer_span)?;
+            let outer = Box::new(outer);
+            (Function::Composition { outer, inner }, arity.unwrap_or(0))
+        } else if token.is_keyword("rec") {
+            self.open()?;
+            let (base, arity, _) = self.parse_function()?;
+            self.comma()?;
+            let (step, found, span) = self.parse_function()?;
+            self.close()?;
+            check_arity(arity + 2, found, span)?;
+            let (base, step) = (Box::new(base), Box::new(step));
+            (Function::Recursion { base, step }, arity + 1)
+        } else if token.is_keyword("mu") || token.is_keyword("μ") {
+            self.open()?;
+            let (function, found, span) = self.parse_function()?;
+            self.close()?;
+            if found == 0 {
+                check_arity(1, found, span)?;
+            }
+            (Function::Minimization(Box::new(function)), found - 1)
+        } else if is_identifier(&token) {
+            match self.definition(&token.content) {
+                Some(definition) => {
+                    (Function::Named(token.content.clone()), definition.arity)
+                }
+                None => {
+                    let name = token.content;
+                    return Err(Error::new(
+                        UndefinedFunction { name },
+                        token.span,
+                    ));
+                }
+            }
+        } else {
+            return Err(self.parser.unexpected(&token, "uma função"));
+        };
+
+        Ok((function, arity, token.span))
+    }
+
+    /// Analisa um número natural.
+    fn parse_number(&mut self) -> Result<usize, Error> {
+        let token = self.parser.next("um número")?;
+        let is_number = token.token_type == TokenType::Word
+            && token
+                .content
+                .chars()
+                .all(|character| character.is_ascii_digit());
+        match token.content.parse() {
+            Ok(number) if is_number => Ok(number),
+            _ => Err(self.parser.unexpected(&token, "um número")),
+        }
+    }
+
+    /// Consome um `(`.
+    fn open(&mut self) -> Result<(), Error> {
+        self.parser.expect_type(TokenType::OpenParen, "\"(\"")
+    }
+
+    /// Consome um `)`.
+    fn close(&mut self) -> Result<(), Error> {
+        self.parser.expect_type(TokenType::CloseParen, "\")\"")
+    }
+
+    /// Consome uma `,`.
+    fn comma(&mut self) -> Result<(), Error> {
+        self.parser.expect_type(TokenType::Comma, "\",\"")
+    }
+
+    /// Busca uma definição já analisada.
+    fn definition(&self, name: &str) -> Option<&Definition> {
+        self.definitions.iter().find(|definition| definition.name == name)
+    }
+}
+
+/// Falha se a aridade encontrada não for a esperada.
+fn check_arity(expected: usize, found: usize, span: Span) -> Result<(), Error> {
+    if expected == found {
+        Ok(())
+    } else {
+        Err(Error::new(ArityMismatch { expected, found }, span))
+    }
+}
+
+/// Retorna se o token serve como nome de função: letras ASCII, dígitos e `_`,
+/// sem começar por dígito, e que não seja palavra reservada.
+fn is_identifier(token: &Token) -> bool {
+    let mut chars = token.content.chars();
+    token.token_type == TokenType::Word
+        && !RESERVED.iter().any(|&keyword| token.is_keyword(keyword))
+        && matches!(
+            chars.next(),
+            Some(first) if first.is_ascii_alphabetic() || first == '_'
+        )
+        && chars.all(|character| {
+            character.is_ascii_alphanumeric() || character == '_'
+        })
+}
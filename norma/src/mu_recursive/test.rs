@@ -0,0 +1,171 @@
+use super::{compile, input_registers, parse, Function, Program};
+use crate::interpreter::Interpreter;
+use num_bigint::BigUint;
+
+const ARITHMETIC: &str = "soma = rec(proj(1, 1), comp(suc, proj(3, 3)))
+mult = rec(zero(1), comp(soma, proj(3, 1), proj(3, 3)))
+pred = rec(zero(0), proj(2, 1))
+";
+
+const SUBTRACTION: &str = "pred = rec(zero(0), proj(2, 1))
+sub = rec(proj(1, 1), comp(pred, proj(3, 3)))
+";
+
+fn run(source: &str, args: &[u64]) -> BigUint {
+    let mut interpreter = Interpreter::new(compile(source).unwrap());
+    for (register, &arg) in input_registers(args.len()).iter().zip(args) {
+        // Funções que ignoram um argumento não usam o seu registrador.
+        let _ = interpreter.set_register(register, BigUint::from(arg));
+    }
+    interpreter.run_all();
+    interpreter.output()
+}
+
+fn evaluate(source: &str, args: &[u64]) -> BigUint {
+    let args: Vec<_> = args.iter().map(|&arg| BigUint::from(arg)).collect();
+    parse(source).unwrap().evaluate(&args)
+}
+
+fn assert_agrees(source: &str, args: &[u64], expected: u64) {
+    assert_eq!(evaluate(source, args), BigUint::from(expected));
+    assert_eq!(run(source, args), BigUint::from(expected));
+}
+
+fn messages(source: &str) -> Vec<String> {
+    parse(source).unwrap_err().iter().map(|error| error.to_string()).collect()
+}
+
+#[test]
+fn parse_arities() {
+    let program = parse(ARITHMETIC).unwrap();
+    let arities: Vec<_> =
+        program.definitions.iter().map(|definition| definition.arity).collect();
+    assert_eq!(arities, vec![2, 2, 1]);
+    assert_eq!(
+        program.definition("mult").unwrap().function,
+        Function::Recursion {
+            base: Box::new(Function::Zero(1)),
+            step: Box::new(Function::Composition {
+                outer: Box::new(Function::Named(String::from("soma"))),
+                inner: vec![
+                    Function::Projection { arity: 3, index: 1 },
+                    Function::Projection { arity: 3, index: 3 },
+                ],
+            }),
+        }
+    );
+}
+
+#[test]
+fn print_round_trip() {
+    let program = parse(ARITHMETIC).unwrap();
+    assert_eq!(program.to_string(), ARITHMETIC);
+    assert_eq!(parse(&program.to_string()).unwrap(), program);
+    assert_eq!(
+        parse("f = μ(zero(2))").unwrap().to_string(),
+        "f = mu(zero(2))\n"
+    );
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!(
+        messages("f = comp(suc, zero(1), zero(1))"),
+        vec![String::from(
+            "Esperava-se uma função de aridade 2, encontrada uma de aridade 1, \
+             da linha 1 e coluna 10, até a coluna 12"
+        )]
+    );
+    assert_eq!(
+        messages("f = proj(2, 3)"),
+        vec![String::from(
+            "Projeção inválida: o argumento deve estar entre 1 e 2, \
+             encontrado 3, da linha 1 e coluna 5, até a coluna 8"
+        )]
+    );
+    assert_eq!(
+        messages("f = comp(g, suc)"),
+        vec![String::from(
+            "Função \"g\" não foi definida, na linha 1 e coluna 10"
+        )]
+    );
+    assert_eq!(
+        messages("f = suc\nf = suc"),
+        vec![String::from(
+            "Função \"f\" foi definida mais de uma vez, na linha 2 e coluna 1"
+        )]
+    );
+    assert_eq!(
+        messages("f = mu(zero(0))"),
+        vec![String::from(
+            "Esperava-se uma função de aridade 1, encontrada uma de aridade 0, \
+             da linha 1 e coluna 8, até a coluna 11"
+        )]
+    );
+}
+
+#[test]
+fn zero() {
+    assert_agrees("f = zero(1)", &[7], 0);
+    assert_agrees("f = zero(0)", &[], 0);
+}
+
+#[test]
+fn successor() {
+    for input in 0..4 {
+        assert_agrees("f = suc", &[input], input + 1);
+    }
+}
+
+#[test]
+fn projection() {
+    assert_agrees("f = proj(3, 2)", &[4, 5, 6], 5);
+    assert_agrees("f = proj(1, 1)", &[9], 9);
+}
+
+#[test]
+fn composition() {
+    assert_agrees("f = comp(suc, comp(suc, proj(2, 2)))", &[1, 5], 7);
+    assert_agrees("f = comp(proj(2, 1), suc, comp(suc, suc))", &[3], 4);
+}
+
+#[test]
+fn recursion() {
+    for (x, y) in [(0, 0), (2, 3), (4, 0), (3, 4)] {
+        let source = format!("{}f = mult", ARITHMETIC);
+        assert_agrees(&source, &[x, y], x * y);
+        assert_agrees(&format!("{}f = soma", ARITHMETIC), &[x, y], x + y);
+        let sub = x.saturating_sub(y);
+        assert_agrees(SUBTRACTION, &[x, y], sub);
+    }
+    for input in 0..4 {
+        assert_agrees(ARITHMETIC, &[input], input.saturating_sub(1));
+    }
+}
+
+#[test]
+fn minimization() {
+    // O menor z tal que x - (y + z) = 0, ou seja, x - y truncado.
+    let source = format!(
+        "{}soma = rec(proj(1, 1), comp(suc, proj(3, 3)))
+dif = mu(comp(sub, proj(3, 1), comp(soma, proj(3, 2), proj(3, 3))))",
+        SUBTRACTION
+    );
+    for (x, y) in [(0, 0), (5, 2), (2, 5), (4, 4)] {
+        assert_agrees(&source, &[x, y], x.saturating_sub(y));
+    }
+}
+
+#[test]
+fn generated_names_avoid_definitions() {
+    let program = parse("clear = suc\nclear_1 = comp(clear, suc)").unwrap();
+    let ast = program.to_ast();
+    let mut names: Vec<_> = ast.macros.keys().cloned().collect();
+    names.sort();
+    assert_eq!(
+        names,
+        vec!["clear", "clear_", "clear_1", "clear_1_1", "copy", "move"]
+    );
+    assert_agrees(&program.to_string(), &[3], 5);
+    assert_eq!(Program { definitions: Vec::new() }.to_ast().macros.len(), 3);
+}
@@ -0,0 +1,460 @@
+//! Programas recursivos, escritos como definições `P = E`, onde `E` é formada
+//! por operações, `se T então E senão E`, composição sequencial (`;`) e
+//! chamadas a outros identificadores definidos, inclusive o próprio `P`.
+//!
+//! O `;` liga mais forte que o `senão`: em `se T então F; G senão H`, o
+//! ramo `então` é `F; G`, e o ramo `senão` vai até um `)`, o fim ou uma nova
+//! definição.
+//!
+//! A primeira definição é o programa principal. As definições não são
+//! separadas por `;`: uma nova definição começa logo após o fim da anterior.
+//! O léxico é o mesmo dos programas iterativos (ver [`crate::iterative`]). Por
+//! exemplo, `Y := 2 * X`:
+//!
+//! ```text
+//! P = se zero X então () senão (dec X; inc Y; inc Y; P)
+//! ```
+//!
+//! Diferente das macros da Norma, que são expandidas no lugar da chamada e por
+//! isso não podem ser recursivas, cada definição é compilada uma única vez. As
+//! chamadas usam uma pilha de endereços de retorno guardada em um registrador:
+//! com `n` pontos de chamada, a pilha é um número na base `n + 1`, cujos
+//! dígitos (de `1` a `n`) são os pontos de chamada ainda pendentes. Chamar
+//! empilha um dígito (`pilha := pilha * (n + 1) + i`) e o fim de uma definição
+//! desempilha o último dígito e volta para o ponto correspondente; com a
+//! pilha vazia, o programa termina.
+
+#[cfg(test)]
+mod test;
+
+pub mod error;
+
+mod parser;
+
+use crate::{
+    block::{self, block_size, entry, fresh_register, Node},
+    interpreter::program::{
+        self as monolithic, Instruction, InstructionKind, Operation,
+        OperationKind, Test, TestKind,
+    },
+    iterative::lexer,
+};
+use num_bigint::BigUint;
+use pinguim_language::error::Diagnostics;
+use std::{collections::HashMap, fmt};
+
+/// Número de instruções geradas por uma chamada.
+const CALL_SIZE: usize = 7;
+
+/// Rótulo de saída dos programas compilados.
+const EXIT_LABEL: &str = "0";
+
+/// Uma expressão de um programa recursivo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expression {
+    /// Uma operação.
+    Operation(OperationKind),
+    /// Chamada ao programa com o identificador dado.
+    Call(String),
+    /// `se test então then senão otherwise`.
+    If {
+        /// O teste.
+        test: TestKind,
+        /// Executado se o teste for verdadeiro.
+        then: Vec<Expression>,
+        /// Executado se o teste for falso.
+        otherwise: Vec<Expression>,
+    },
+}
+
+impl Node for Expression {
+    fn size(&self) -> usize {
+        match self {
+            Expression::Operation(_) => 1,
+            Expression::Call(_) => CALL_SIZE,
+            Expression::If { then, otherwise, .. } => {
+                1 + block_size(then) + block_size(otherwise)
+            }
+        }
+    }
+}
+
+impl Expression {
+    /// Número de chamadas feitas nesta expressão.
+    fn calls(&self) -> usize {
+        match self {
+            Expression::Operation(_) => 0,
+            Expression::Call(_) => 1,
+            Expression::If { then, otherwise, .. } => {
+                block_calls(then) + block_calls(otherwise)
+            }
+        }
+    }
+
+    /// Coleta os registradores usados nesta expressão.
+    fn collect_registers<F>(&self, collector: &mut F)
+    where
+        F: FnMut(&str),
+    {
+        match self {
+            Expression::Operation(kind) => kind.collect_registers(collector),
+            Expression::Call(_) => (),
+            Expression::If { test, then, otherwise } => {
+                test.collect_registers(&mut *collector);
+                for expression in then.iter().chain(otherwise) {
+                    expression.collect_registers(collector);
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expression::Operation(kind) => write!(fmtr, "{}", kind),
+            Expression::Call(name) => write!(fmtr, "{}", name),
+            Expression::If { test, then, otherwise } => {
+                write!(fmtr, "se {} então ", test)?;
+                write_branch(fmtr, then)?;
+                if !otherwise.is_empty() {
+                    write!(fmtr, " senão ")?;
+                    write_branch(fmtr, otherwise)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Uma definição `name = body`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Definition {
+    /// O identificador definido.
+    pub name: String,
+    /// As expressões, compostas sequencialmente.
+    pub body: Vec<Expression>,
+}
+
+impl fmt::Display for Definition {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmtr, "{} = ", self.name)?;
+        if self.body.is_empty() {
+            write!(fmtr, "()")
+        } else {
+            write_sequence(fmtr, &self.body)
+        }
+    }
+}
+
+/// Um programa recursivo: uma lista de definições, sendo a primeira o
+/// programa principal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    /// As definições, na ordem do texto.
+    pub definitions: Vec<Definition>,
+}
+
+impl Program {
+    /// Converte para um programa monolítico, com rótulos `1`, `2`, ... e
+    /// rótulo de saída `0`. As definições ficam na ordem do texto, seguidas
+    /// das instruções de retorno, que só existem se houver alguma chamada.
+    pub fn to_monolithic(&self) -> monolithic::Program {
+        let mut program = monolithic::Program::empty();
+        match self.definitions.first() {
+            Some(main) if !main.body.is_empty() => (),
+            _ => return program,
+        }
+
+        let calls: usize = self
+            .definitions
+            .iter()
+            .map(|definition| block_calls(&definition.body))
+            .sum();
+        let code_size: usize = self
+            .definitions
+            .iter()
+            .map(|definition| block_size(&definition.body))
+            .sum();
+        let ret = if calls == 0 {
+            EXIT_LABEL.to_owned()
+        } else {
+            (code_size + 1).to_string()
+        };
+
+        let mut start = 1;
+        let mut entries = HashMap::new();
+        for definition in &self.definitions {
+            entries.insert(
+                definition.name.as_str(),
+                entry(&definition.body, start, &ret),
+            );
+            start += block_size(&definition.body);
+        }
+
+        let mut used = Vec::new();
+        for definition in &self.definitions {
+            for expression in &definition.body {
+                expression.collect_registers(&mut |register| {
+                    used.push(register.to_owned())
+                });
+            }
+        }
+
+        let mut emitter = Emitter {
+            entries,
+            ret,
+            radix: BigUint::from(calls + 1),
+            stack: fresh_register("Pilha", &used),
+            quotient: fresh_register("PilhaAux", &used),
+            digit: fresh_register("PilhaTopo", &used),
+            returns: Vec::new(),
+            program: &mut program,
+        };
+        let mut start = 1;
+        for definition in &self.definitions {
+            let ret = emitter.ret.clone();
+            emitter.emit_block(&definition.body, start, &ret);
+            start += block_size(&definition.body);
+        }
+        if calls > 0 {
+            emitter.emit_return(start);
+        }
+
+        program
+    }
+}
+
+impl fmt::Display for Program {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        for definition in &self.definitions {
+            writeln!(fmtr, "{}", definition)?;
+        }
+        Ok(())
+    }
+}
+
+/// Analisa o código-fonte de um programa recursivo.
+pub fn parse(source: &str) -> Result<Program, Diagnostics> {
+    let mut diagnostics = Diagnostics::new();
+    let tokens = lexer::generate_tokens(source, &mut diagnostics);
+    let maybe_program = parser::parse(tokens, &mut diagnostics);
+
+    match maybe_program {
+        Some(program) if diagnostics.is_ok() => Ok(program),
+        _ => Err(diagnostics),
+    }
+}
+
+/// Compila o código-fonte de um programa recursivo para um programa
+/// monolítico.
+pub fn compile(source: &str) -> Result<monolithic::Program, Diagnostics> {
+    parse(source).map(|program| program.to_monolithic())
+}
+
+/// Gerador das instruções monolíticas de um programa recursivo.
+struct Emitter<'prog> {
+    /// Rótulo de entrada de cada definição.
+    entries: HashMap<&'prog str, String>,
+    /// Rótulo das instruções de retorno.
+    ret: String,
+    /// Base da pilha: número de pontos de chamada mais um.
+    radix: BigUint,
+    /// Registrador da pilha.
+    stack: String,
+    /// Registrador temporário para multiplicar e dividir a pilha.
+    quotient: String,
+    /// Registrador com o dígito desempilhado.
+    digit: String,
+    /// Rótulo para onde cada ponto de chamada volta, na ordem do texto.
+    returns: Vec<String>,
+    /// Programa sendo gerado.
+    program: &'prog mut monolithic::Program,
+}
+
+impl<'prog> Emitter<'prog> {
+    /// Gera as instruções de uma sequência a partir do rótulo `start`, saindo
+    /// para `next`.
+    fn emit_block(&mut self, block: &[Expression], start: usize, next: &str) {
+        block::emit_block(block, start, next, |expression, label, next| {
+            self.emit(expression, label, next)
+        });
+    }
+
+    /// Gera as instruções de uma expressão a partir do rótulo `start`, saindo
+    /// para `next`.
+    fn emit(&mut self, expression: &Expression, start: usize, next: &str) {
+        match expression {
+            Expression::Operation(kind) => {
+                self.operation(start, kind.clone(), next.to_owned());
+            }
+            Expression::If { test, then, otherwise } => {
+                let else_start = start + 1 + block_size(then);
+                self.test(
+                    start,
+                    test.clone(),
+                    entry(then, start + 1, next),
+                    entry(otherwise, else_start, next),
+                );
+                self.emit_block(then, start + 1, next);
+                self.emit_block(otherwise, else_start, next);
+            }
+            Expression::Call(name) => {
+                self.returns.push(next.to_owned());
+                let digit = BigUint::from(self.returns.len());
+                let target = self.entries[name.as_str()].clone();
+                self.emit_push(start, digit, target);
+            }
+        }
+    }
+
+    /// Empilha `digit` e vai para `target`:
+    ///
+    /// ```text
+    /// até zero pilha faça (dec pilha; add (resto, base));
+    /// até zero resto faça (dec resto; inc pilha);
+    /// add (pilha, digit)
+    /// ```
+    fn emit_push(&mut self, start: usize, digit: BigUint, target: String) {
+        let (stack, quotient) = (self.stack.clone(), self.quotient.clone());
+        let label = |offset: usize| (start + offset).to_string();
+
+        self.test(start, TestKind::Zero(stack.clone()), label(3), label(1));
+        self.operation(start + 1, OperationKind::Dec(stack.clone()), label(2));
+        self.operation(
+            start + 2,
+            OperationKind::AddConst(quotient.clone(), self.radix.clone()),
+            label(0),
+        );
+        self.test(
+            start + 3,
+            TestKind::Zero(quotient.clone()),
+            label(6),
+            label(4),
+        );
+        self.operation(start + 4, OperationKind::Dec(quotient), label(5));
+        self.operation(start + 5, OperationKind::Inc(stack.clone()), label(3));
+        self.operation(
+            start + 6,
+            OperationKind::AddConst(stack, digit),
+            target,
+        );
+    }
+
+    /// Gera as instruções de retorno a partir do rótulo `start`: termina se a
+    /// pilha estiver vazia, senão desempilha o último dígito e volta para o
+    /// ponto de chamada correspondente.
+    fn emit_return(&mut self, start: usize) {
+        let (stack, quotient, digit) =
+            (self.stack.clone(), self.quotient.clone(), self.digit.clone());
+        let label = |offset: usize| (start + offset).to_string();
+
+        // Divide a pilha pela base: o quociente fica em `quotient` e o resto
+        // (o dígito) em `stack`.
+        let empty = TestKind::Zero(stack.clone());
+        self.test(start, empty, EXIT_LABEL.to_owned(), label(1));
+        let less = TestKind::LessThanConst(stack.clone(), self.radix.clone());
+        self.test(start + 1, less, label(4), label(2));
+        let sub = OperationKind::SubConst(stack.clone(), self.radix.clone());
+        self.operation(start + 2, sub, label(3));
+        self.operation(
+            start + 3,
+            OperationKind::Inc(quotient.clone()),
+            label(1),
+        );
+
+        // Move o dígito para `digit` e o quociente para `stack`.
+        self.test(start + 4, TestKind::Zero(stack.clone()), label(7), label(5));
+        self.operation(start + 5, OperationKind::Dec(stack.clone()), label(6));
+        self.operation(start + 6, OperationKind::Inc(digit.clone()), label(4));
+        self.test(
+            start + 7,
+            TestKind::Zero(quotient.clone()),
+            label(10),
+            label(8),
+        );
+        self.operation(start + 8, OperationKind::Dec(quotient), label(9));
+        self.operation(start + 9, OperationKind::Inc(stack), label(7));
+
+        // Decrementa o dígito até zerar, para saber o ponto de chamada.
+        let returns = self.returns.clone();
+        for (i, next) in returns.iter().enumerate() {
+            let position = start + 10 + 2 * i;
+            let dec = OperationKind::Dec(digit.clone());
+            self.operation(position, dec, (position + 1).to_string());
+            let otherwise = if i + 1 == returns.len() {
+                next.clone()
+            } else {
+                (position + 2).to_string()
+            };
+            let zero = TestKind::Zero(digit.clone());
+            self.test(position + 1, zero, next.clone(), otherwise);
+        }
+    }
+
+    /// Insere uma operação.
+    fn operation(&mut self, label: usize, kind: OperationKind, next: String) {
+        let operation = Operation { kind, next };
+        self.program.insert(Instruction::new(
+            label.to_string(),
+            InstructionKind::Operation(operation),
+        ));
+    }
+
+    /// Insere um teste.
+    fn test(
+        &mut self,
+        label: usize,
+        kind: TestKind,
+        next_then: String,
+        next_else: String,
+    ) {
+        let test = Test { kind, next_then, next_else };
+        self.program.insert(Instruction::new(
+            label.to_string(),
+            InstructionKind::Test(test),
+        ));
+    }
+}
+
+/// Número de chamadas feitas em uma sequência.
+fn block_calls(block: &[Expression]) -> usize {
+    block.iter().map(Expression::calls).sum()
+}
+
+/// Escreve uma sequência separada por `; `. Um `se` que não é o último fica
+/// entre parênteses, pois os ramos vão até o fim da sequência.
+fn write_sequence(
+    fmtr: &mut fmt::Formatter,
+    block: &[Expression],
+) -> fmt::Result {
+    for (i, expression) in block.iter().enumerate() {
+        if i > 0 {
+            write!(fmtr, "; ")?;
+        }
+        match expression {
+            Expression::If { .. } if i + 1 < block.len() => {
+                write!(fmtr, "({})", expression)?
+            }
+            _ => write!(fmtr, "{}", expression)?,
+        }
+    }
+    Ok(())
+}
+
+/// Escreve um ramo de um `se`: uma operação ou chamada sozinha fica sem
+/// parênteses.
+fn write_branch(
+    fmtr: &mut fmt::Formatter,
+    block: &[Expression],
+) -> fmt::Result {
+    match block {
+        [expression @ Expression::Operation(_)]
+        | [expression @ Expression::Call(_)] => write!(fmtr, "{}", expression),
+        _ => {
+            write!(fmtr, "(")?;
+            write_sequence(fmtr, block)?;
+            write!(fmtr, ")")
+        }
+    }
+}
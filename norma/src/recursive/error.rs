@@ -0,0 +1,37 @@
+use std::{error::Error, fmt};
+
+#[derive(Debug, Clone)]
+/// Erro em que um programa recursivo chama um identificador sem definição
+pub struct UndefinedProgram {
+    ///
+    /// - `name`: identificador chamado
+    pub name: String,
+}
+
+impl fmt::Display for UndefinedProgram {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "Programa \"{}\" não foi definido", self.name)
+    }
+}
+
+impl Error for UndefinedProgram {}
+
+#[derive(Debug, Clone)]
+/// Erro em que um identificador é definido mais de uma vez
+pub struct DuplicateDefinition {
+    ///
+    /// - `name`: identificador definido novamente
+    pub name: String,
+}
+
+impl fmt::Display for DuplicateDefinition {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "Programa \"{}\" foi definido mais de uma vez",
+            self.name
+        )
+    }
+}
+
+impl Error for DuplicateDefinition {}
@@ -0,0 +1,156 @@
+use super::{
+    error::{DuplicateDefinition, UndefinedProgram},
+    Definition, Expression, Program,
+};
+use crate::{
+    interpreter::program::OperationKind,
+    iterative::{
+        lexer::{Token, TokenType},
+        parser::{Parser, RESERVED},
+    },
+};
+use pinguim_language::error::{Diagnostics, Error};
+use std::collections::HashSet;
+
+/// Analisa os tokens de um programa recursivo. Para no primeiro erro de
+/// sintaxe; chamadas a programas não definidos são todas relatadas.
+pub fn parse(
+    tokens: Vec<Token>,
+    diagnostics: &mut Diagnostics,
+) -> Option<Program> {
+    let mut parser = RecursiveParser {
+        parser: Parser { tokens, position: 0 },
+        calls: Vec::new(),
+    };
+
+    match parser.parse_definitions() {
+        Ok(program) => {
+            let names: HashSet<_> = program
+                .definitions
+                .iter()
+                .map(|definition| definition.name.as_str())
+                .collect();
+            for call in &parser.calls {
+                if !names.contains(call.content.as_str()) {
+                    let name = call.content.clone();
+                    diagnostics.raise(Error::new(
+                        UndefinedProgram { name },
+                        call.span,
+                    ));
+                }
+            }
+            Some(program)
+        }
+        Err(error) => {
+            diagnostics.raise(error);
+            None
+        }
+    }
+}
+
+/// Analisador sintático de programas recursivos, construído sobre o de
+/// programas iterativos.
+struct RecursiveParser {
+    parser: Parser,
+    /// Identificadores chamados, para checar as definições no final.
+    calls: Vec<Token>,
+}
+
+impl RecursiveParser {
+    /// Analisa as definições até o fim do código.
+    fn parse_definitions(&mut self) -> Result<Program, Error> {
+        let mut definitions = Vec::<Definition>::new();
+
+        loop {
+            let token = self.parser.next("uma definição")?;
+            if !is_identifier(&token) {
+                let expected = if definitions.is_empty() {
+                    "uma definição"
+                } else {
+                    "\";\" ou uma definição"
+                };
+                return Err(self.parser.unexpected(&token, expected));
+            }
+            if definitions.iter().any(|other| other.name == token.content) {
+                let name = token.content;
+                return Err(Error::new(
+                    DuplicateDefinition { name },
+                    token.span,
+                ));
+            }
+
+            self.parser.expect_type(TokenType::Equals, "\"=\"")?;
+            let body = self.parse_sequence()?;
+            definitions.push(Definition { name: token.content, body });
+
+            if self.parser.position == self.parser.tokens.len() {
+                break Ok(Program { definitions });
+            }
+        }
+    }
+
+    /// Analisa uma sequência de expressões separadas por `;`, possivelmente
+    /// vazia, até um `)`, o fim ou uma nova definição.
+    fn parse_sequence(&mut self) -> Result<Vec<Expression>, Error> {
+        if self.parser.at_block_end() {
+            return Ok(Vec::new());
+        }
+        self.parse_branch()
+    }
+
+    /// Analisa uma sequência não vazia de expressões separadas por `;`, como
+    /// um ramo de um `se`. O `;` liga mais forte que o `senão`, então o ramo
+    /// vai até um `senão`, um `)`, o fim ou uma nova definição.
+    fn parse_branch(&mut self) -> Result<Vec<Expression>, Error> {
+        let mut block = Vec::new();
+        loop {
+            block.extend(self.parse_expression()?);
+            if self.parser.check(TokenType::Semicolon) {
+                self.parser.position += 1;
+            } else {
+                break Ok(block);
+            }
+        }
+    }
+
+    /// Analisa uma expressão. Uma sequência entre parênteses conta como uma
+    /// expressão, por isso o retorno é uma sequência.
+    fn parse_expression(&mut self) -> Result<Vec<Expression>, Error> {
+        let token = self.parser.next("uma expressão")?;
+
+        if token.token_type == TokenType::OpenParen {
+            let block = self.parse_sequence()?;
+            self.parser.expect_type(TokenType::CloseParen, "\")\"")?;
+            Ok(block)
+        } else if token.is_keyword("se") {
+            let test = self.parser.parse_test()?;
+            self.parser.expect_keyword("então")?;
+            let then = self.parse_branch()?;
+            let otherwise = if self.parser.check_keyword("senão") {
+                self.parser.position += 1;
+                self.parse_branch()?
+            } else {
+                Vec::new()
+            };
+            Ok(vec![Expression::If { test, then, otherwise }])
+        } else if token.is_keyword("inc") {
+            let register = self.parser.parse_register()?;
+            Ok(vec![Expression::Operation(OperationKind::Inc(register))])
+        } else if token.is_keyword("dec") {
+            let register = self.parser.parse_register()?;
+            Ok(vec![Expression::Operation(OperationKind::Dec(register))])
+        } else if is_identifier(&token) {
+            let name = token.content.clone();
+            self.calls.push(token);
+            Ok(vec![Expression::Call(name)])
+        } else {
+            Err(self.parser.unexpected(&token, "uma expressão"))
+        }
+    }
+}
+
+/// Retorna se o token serve como identificador de programa.
+fn is_identifier(token: &Token) -> bool {
+    token.token_type == TokenType::Word
+        && !RESERVED.iter().any(|&keyword| token.is_keyword(keyword))
+}
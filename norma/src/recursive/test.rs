@@ -0,0 +1,146 @@
+use super::{compile, parse, Definition, Expression, Program};
+use crate::interpreter::{
+    program::{OperationKind, TestKind},
+    run_once,
+};
+use num_bigint::BigUint;
+
+const DOUBLE: &str = "// Y := 2 * X, com Y incrementado na volta das chamadas
+P = se zero X então () senão (dec X; P; inc Y; inc Y)";
+
+const PARITY: &str = "Par = se zero X então inc Y senão (dec X; Impar)
+Impar = se zero X então () senão (dec X; Par)
+";
+
+fn run(source: &str, input: u64) -> BigUint {
+    run_once(BigUint::from(input), compile(source).unwrap())
+}
+
+fn messages(source: &str) -> Vec<String> {
+    parse(source).unwrap_err().iter().map(|error| error.to_string()).collect()
+}
+
+#[test]
+fn parse_definitions() {
+    let program = parse(DOUBLE).unwrap();
+    let inc_y = Expression::Operation(OperationKind::Inc(String::from("Y")));
+    let expected = Program {
+        definitions: vec![Definition {
+            name: String::from("P"),
+            body: vec![Expression::If {
+                test: TestKind::Zero(String::from("X")),
+                then: Vec::new(),
+                otherwise: vec![
+                    Expression::Operation(OperationKind::Dec(String::from(
+                        "X",
+                    ))),
+                    Expression::Call(String::from("P")),
+                    inc_y.clone(),
+                    inc_y,
+                ],
+            }],
+        }],
+    };
+    assert_eq!(program, expected);
+}
+
+#[test]
+fn sequence_binds_tighter_than_else() {
+    let program = parse(
+        "P = (se zero X então F; R1 senão G)
+F = inc Y
+R1 = inc Y
+G = dec X",
+    )
+    .unwrap();
+    let call = |name: &str| Expression::Call(String::from(name));
+    assert_eq!(
+        program.definitions[0].body,
+        vec![Expression::If {
+            test: TestKind::Zero(String::from("X")),
+            then: vec![call("F"), call("R1")],
+            otherwise: vec![call("G")],
+        }]
+    );
+
+    let nested = parse("P = (se zero X então inc Y); inc Y").unwrap();
+    assert_eq!(nested.to_string(), "P = (se zero X então inc Y); inc Y\n");
+    assert_eq!(parse(&nested.to_string()).unwrap(), nested);
+}
+
+#[test]
+fn print_round_trip() {
+    let program = parse(PARITY).unwrap();
+    assert_eq!(program.to_string(), PARITY);
+    assert_eq!(parse(&program.to_string()).unwrap(), program);
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!(
+        messages("P = inc X; Q"),
+        vec![String::from(
+            "Programa \"Q\" não foi definido, na linha 1 e coluna 12"
+        )]
+    );
+    assert_eq!(
+        messages("P = inc X\nP = dec X"),
+        vec![String::from(
+            "Programa \"P\" foi definido mais de uma vez, na linha 2 e coluna 1"
+        )]
+    );
+    assert_eq!(
+        messages("P inc X"),
+        vec![String::from(
+            "Esperava-se \"=\", encontrado \"inc\", da linha 1 e coluna 3, até a coluna 5"
+        )]
+    );
+    assert_eq!(
+        messages(""),
+        vec![String::from("Esperava-se uma definição, mas o código terminou")]
+    );
+}
+
+#[test]
+fn compile_without_calls() {
+    let program = compile("P = se zero X então inc Y senão dec X").unwrap();
+    assert_eq!(
+        program.to_string(),
+        "1: if zero X then goto 2 else goto 3\n\
+         2: do inc Y goto 0\n\
+         3: do dec X goto 0\n"
+    );
+    assert!(compile("P = ()").unwrap().is_empty());
+}
+
+#[test]
+fn recursion() {
+    for input in 0..5 {
+        assert_eq!(run(DOUBLE, input), BigUint::from(2 * input));
+    }
+}
+
+#[test]
+fn mutual_recursion() {
+    for input in 0..6 {
+        assert_eq!(run(PARITY, input), BigUint::from(1 - input % 2));
+    }
+}
+
+#[test]
+fn stack_registers_avoid_user_registers() {
+    let program =
+        compile("P = se zero Pilha então () senão (dec Pilha; inc Y; P)")
+            .unwrap();
+    let mut registers = Vec::new();
+    program.collect_registers(|register| {
+        if !registers.iter().any(|other| other == register) {
+            registers.push(register.to_owned());
+        }
+    });
+    registers.sort();
+    assert_eq!(
+        registers,
+        vec!["Pilha", "PilhaAux", "PilhaTopo", "Pilha_", "Y"]
+    );
+}
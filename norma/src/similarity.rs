@@ -0,0 +1,187 @@
+//! Detecção de submissões parecidas, e.g. soluções copiadas com rótulos e
+//! registradores renomeados.
+//!
+//! Os programas são comparados na forma canônica (ver
+//! [`canonical`](crate::interpreter::program::canonical)), então renomear
+//! rótulos e registradores, reordenar instruções ou acrescentar código morto
+//! não muda nada. Na forma canônica, cada instrução vira uma "forma": a
+//! operação ou teste com os registradores canônicos e os destinos como
+//! deslocamentos relativos à própria instrução, de forma que inserir uma
+//! instrução só afeta os saltos que passam por ela. A similaridade é `1`
+//! menos a distância de edição entre as sequências de formas, dividida pelo
+//! tamanho da maior.
+
+#[cfg(test)]
+mod test;
+
+use crate::{
+    compiler::compile,
+    interpreter::program::{canonical::canonicalize, InstructionKind, Program},
+};
+use std::{
+    cmp::Ordering, collections::HashMap, ffi::OsStr, fmt, fs, io, mem,
+    path::Path,
+};
+
+/// Extensão dos arquivos de código-fonte da Norma.
+const SOURCE_EXTENSION: &str = "mn";
+
+/// Calcula a similaridade estrutural entre dois programas, de `0`
+/// (completamente diferentes) a `1` (mesma forma canônica).
+pub fn similarity(first: &Program, second: &Program) -> f64 {
+    score(&shape(&canonicalize(first)), &shape(&canonicalize(second)))
+}
+
+/// Um par de submissões e a similaridade entre elas.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pair {
+    /// Identificação da primeira submissão.
+    pub first: String,
+    /// Identificação da segunda submissão.
+    pub second: String,
+    /// Similaridade, de `0` a `1`.
+    pub score: f64,
+}
+
+/// Pares suspeitos, ordenados da maior similaridade para a menor.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Report {
+    /// Pares com similaridade acima do limite.
+    pub pairs: Vec<Pair>,
+    /// Submissões que não compilaram e, portanto, não foram comparadas.
+    pub uncompiled: Vec<String>,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        for pair in &self.pairs {
+            writeln!(
+                fmtr,
+                "{:.2} {} x {}",
+                pair.score, pair.first, pair.second
+            )?;
+        }
+        for name in &self.uncompiled {
+            writeln!(fmtr, "{} não compila", name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compara todas as submissões duas a duas, mantendo os pares com
+/// similaridade maior ou igual a `threshold`. Recebe pares `(nome,
+/// código-fonte)`.
+pub fn compare_all<I, S, T>(submissions: I, threshold: f64) -> Report
+where
+    I: IntoIterator<Item = (S, T)>,
+    S: Into<String>,
+    T: AsRef<str>,
+{
+    let mut report = Report::default();
+    let mut shapes = Vec::new();
+
+    for (name, source) in submissions {
+        let name = name.into();
+        match compile(source.as_ref()) {
+            Ok(program) => shapes.push((name, shape(&canonicalize(&program)))),
+            Err(_) => report.uncompiled.push(name),
+        }
+    }
+
+    for (index, (first, first_shape)) in shapes.iter().enumerate() {
+        for (second, second_shape) in &shapes[index + 1..] {
+            let score = score(first_shape, second_shape);
+            if score >= threshold {
+                report.pairs.push(Pair {
+                    first: first.clone(),
+                    second: second.clone(),
+                    score,
+                });
+            }
+        }
+    }
+
+    report.pairs.sort_by(|left, right| {
+        right.score.partial_cmp(&left.score).unwrap_or(Ordering::Equal)
+    });
+    report
+}
+
+/// Lê todos os arquivos `.mn` de um diretório, em ordem de nome, como pares
+/// `(nome do arquivo, código-fonte)` prontos para [`compare_all`].
+pub fn read_dir<P>(path: P) -> io::Result<Vec<(String, String)>>
+where
+    P: AsRef<Path>,
+{
+    let mut submissions = Vec::new();
+    for entry in fs::read_dir(path)? {
+        let path = entry?.path();
+        let is_source = path.extension() == Some(OsStr::new(SOURCE_EXTENSION));
+        if path.is_file() && is_source {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            submissions.push((name, fs::read_to_string(&path)?));
+        }
+    }
+    submissions.sort();
+    Ok(submissions)
+}
+
+/// Converte um programa canônico na sequência de formas das instruções.
+fn shape(program: &Program) -> Vec<String> {
+    let positions: HashMap<&str, usize> = program
+        .instructions()
+        .enumerate()
+        .map(|(position, instruction)| (instruction.label(), position))
+        .collect();
+
+    program
+        .instructions()
+        .enumerate()
+        .map(|(position, instruction)| {
+            let mut kind = instruction.kind.clone();
+            kind.rename_labels(|label| {
+                *label = match positions.get(label.as_str()) {
+                    Some(&target) => {
+                        format!("{:+}", target as i64 - position as i64)
+                    }
+                    None => "fim".to_owned(),
+                };
+            });
+            match kind {
+                InstructionKind::Operation(oper) => oper.to_string(),
+                InstructionKind::Test(test) => test.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Similaridade entre duas sequências de formas.
+fn score(first: &[String], second: &[String]) -> f64 {
+    let longest = first.len().max(second.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - edit_distance(first, second) as f64 / longest as f64
+}
+
+/// Distância de Levenshtein entre duas sequências.
+fn edit_distance(first: &[String], second: &[String]) -> usize {
+    let mut previous: Vec<usize> = (0..=second.len()).collect();
+    let mut current = vec![0; second.len() + 1];
+
+    for (i, first_item) in first.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, second_item) in second.iter().enumerate() {
+            let substitution =
+                previous[j] + usize::from(first_item != second_item);
+            current[j + 1] =
+                substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        mem::swap(&mut previous, &mut current);
+    }
+
+    previous[second.len()]
+}
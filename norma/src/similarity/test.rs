@@ -0,0 +1,87 @@
+use super::{compare_all, read_dir, similarity};
+use crate::compiler::compile;
+use std::fs;
+
+/// Y := X.
+const ORIGINAL: &str = "main {
+    1: if zero X then goto 0 else goto 2
+    2: do dec X goto 3
+    3: do inc Y goto 1
+}";
+
+/// Cópia de `ORIGINAL` com nomes trocados e código morto.
+const DISGUISED: &str = "main {
+    loop: if zero X then goto done else goto step
+    unused: do inc Z goto loop
+    step: do dec X goto copy
+    copy: do inc Y goto loop
+}";
+
+/// Cópia de `ORIGINAL` com uma instrução a mais.
+const EXTENDED: &str = "main {
+    1: if zero X then goto 0 else goto 2
+    2: do dec X goto 3
+    3: do inc A goto 4
+    4: do inc Y goto 1
+}";
+
+/// Y := 2, sem laço.
+const UNRELATED: &str = "main {
+    1: do inc Y goto 2
+    2: do inc Y goto 0
+}";
+
+#[test]
+fn similarity_scores() {
+    let original = compile(ORIGINAL).unwrap();
+    let disguised = compile(DISGUISED).unwrap();
+    let extended = compile(EXTENDED).unwrap();
+    let unrelated = compile(UNRELATED).unwrap();
+
+    assert_eq!(similarity(&original, &disguised), 1.0);
+    assert_eq!(similarity(&original, &extended), 0.5);
+    assert_eq!(similarity(&original, &unrelated), 0.0);
+    assert_eq!(similarity(&unrelated, &unrelated), 1.0);
+}
+
+#[test]
+fn ranked_report() {
+    let report = compare_all(
+        vec![
+            ("ana", ORIGINAL),
+            ("bia", UNRELATED),
+            ("caio", EXTENDED),
+            ("davi", DISGUISED),
+            ("eva", "main {"),
+        ],
+        0.5,
+    );
+
+    assert_eq!(report.uncompiled, vec!["eva".to_owned()]);
+    assert_eq!(
+        report.to_string(),
+        "1.00 ana x davi
+0.50 ana x caio
+0.50 caio x davi
+eva não compila
+"
+    );
+}
+
+#[test]
+fn reads_sources_from_directory() {
+    let dir = std::env::temp_dir()
+        .join(format!("norma-similarity-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("b.mn"), DISGUISED).unwrap();
+    fs::write(dir.join("a.mn"), ORIGINAL).unwrap();
+    fs::write(dir.join("notas.txt"), "").unwrap();
+
+    let submissions = read_dir(&dir).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    let names: Vec<_> =
+        submissions.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, vec!["a.mn", "b.mn"]);
+    assert_eq!(compare_all(submissions, 0.9).pairs.len(), 1);
+}
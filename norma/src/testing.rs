@@ -0,0 +1,298 @@
+//! Testes baseados em propriedades para programas Norma: confere que um
+//! programa computa uma dada função Rust, ou que computa o mesmo que outro
+//! programa (teste diferencial, e.g. solução de um aluno contra uma solução de
+//! referência).
+//!
+//! As entradas testadas são as de fronteira (valores pequenos e potências de
+//! dois e seus vizinhos) seguidas de entradas pseudo-aleatórias. Cada execução
+//! tem um limite de passos, e um contraexemplo encontrado é reduzido até uma
+//! entrada menor que ainda falhe.
+//!
+//! ```
+//! use norma::testing;
+//!
+//! testing::check(
+//!     "main {
+//!         1: if zero X then goto 0 else goto 2
+//!         2: do dec X goto 3
+//!         3: do inc Y goto 1
+//!     }",
+//!     |x| x.clone(),
+//! );
+//! ```
+
+#[cfg(test)]
+mod test;
+
+pub mod suite;
+
+use crate::{
+    batch::{self, run_one, Outcome},
+    compiler::compile,
+    interpreter::program::Program,
+};
+use num_bigint::BigUint;
+use std::{error::Error, fmt, sync::Arc};
+
+/// Configuração de um teste.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    /// Número de entradas pseudo-aleatórias testadas, além das entradas de
+    /// fronteira.
+    pub cases: usize,
+    /// Maior entrada testada.
+    pub max_input: u64,
+    /// Máximo de passos de cada execução. Uma execução que não termina
+    /// dentro do limite é uma falha.
+    pub fuel: u64,
+    /// Semente do gerador de entradas. A mesma semente gera sempre as mesmas
+    /// entradas.
+    pub seed: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self { cases: 100, max_input: 1000, fuel: 1_000_000, seed: 0x5eed }
+    }
+}
+
+impl Config {
+    /// Gera as entradas a serem testadas: primeiro as de fronteira, em ordem
+    /// crescente, depois as pseudo-aleatórias.
+    pub fn inputs(&self) -> Vec<BigUint> {
+        let mut inputs: Vec<u64> = (0..=self.max_input.min(16)).collect();
+        let mut power = 16u64;
+        while power <= self.max_input {
+            for input in [power - 1, power, power + 1] {
+                if input <= self.max_input && !inputs.contains(&input) {
+                    inputs.push(input);
+                }
+            }
+            power = match power.checked_mul(2) {
+                Some(power) => power,
+                None => break,
+            };
+        }
+        if !inputs.contains(&self.max_input) {
+            inputs.push(self.max_input);
+        }
+
+        let mut rng = SplitMix64(self.seed);
+        for _ in 0..self.cases {
+            inputs.push(rng.up_to(self.max_input));
+        }
+        inputs.into_iter().map(BigUint::from).collect()
+    }
+
+    fn batch_config(&self) -> batch::Config {
+        batch::Config { fuel: self.fuel, threads: 1 }
+    }
+}
+
+/// Uma entrada para a qual o programa testado não computa o esperado.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counterexample {
+    /// Entrada (AKA registrador X), já reduzida.
+    pub input: BigUint,
+    /// Primeira entrada em que a falha foi encontrada, antes da redução.
+    pub original: BigUint,
+    /// Saída esperada (AKA registrador Y).
+    pub expected: BigUint,
+    /// Resultado obtido pelo programa testado.
+    pub found: Outcome,
+    /// Passos dados pelo programa testado.
+    pub steps: BigUint,
+}
+
+impl fmt::Display for Counterexample {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmtr, "Contraexemplo X = {}", self.input)?;
+        if self.original != self.input {
+            write!(fmtr, " (reduzido de X = {})", self.original)?;
+        }
+        write!(fmtr, ": esperado Y = {}, ", self.expected)?;
+        match &self.found {
+            Outcome::Halted(output) => write!(fmtr, "obtido Y = {}", output),
+            Outcome::OutOfFuel => {
+                write!(fmtr, "programa não terminou em {} passos", self.steps)
+            }
+        }
+    }
+}
+
+impl Error for Counterexample {}
+
+/// Confere que o programa computa a função `oracle`, com a configuração
+/// padrão. Entra em pânico com o contraexemplo, ou se o código não compilar;
+/// feita para ser usada dentro de `#[test]`.
+pub fn check<F, T>(source: &str, oracle: F)
+where
+    F: Fn(&BigUint) -> T,
+    T: Into<BigUint>,
+{
+    check_with(source, oracle, &Config::default())
+}
+
+/// Como [`check`], mas com a configuração dada.
+pub fn check_with<F, T>(source: &str, oracle: F, config: &Config)
+where
+    F: Fn(&BigUint) -> T,
+    T: Into<BigUint>,
+{
+    if let Err(counterexample) =
+        verify(compile_or_panic(source), oracle, config)
+    {
+        panic!("{}", counterexample);
+    }
+}
+
+/// Confere que o programa `candidate` computa o mesmo que o programa
+/// `reference`, com a configuração padrão. Entra em pânico com o
+/// contraexemplo, ou se algum dos códigos não compilar.
+pub fn check_against(reference: &str, candidate: &str) {
+    check_against_with(reference, candidate, &Config::default())
+}
+
+/// Como [`check_against`], mas com a configuração dada.
+pub fn check_against_with(reference: &str, candidate: &str, config: &Config) {
+    let reference = compile_or_panic(reference);
+    let candidate = compile_or_panic(candidate);
+    if let Err(counterexample) = verify_against(reference, candidate, config) {
+        panic!("{}", counterexample);
+    }
+}
+
+/// Testa o programa contra a função `oracle`, retornando o primeiro
+/// contraexemplo encontrado, já reduzido.
+pub fn verify<P, F, T>(
+    program: P,
+    oracle: F,
+    config: &Config,
+) -> Result<(), Counterexample>
+where
+    P: Into<Arc<Program>>,
+    F: Fn(&BigUint) -> T,
+    T: Into<BigUint>,
+{
+    let program = program.into();
+    search(config, |input| {
+        let expected = oracle(input).into();
+        test_input(&program, input, expected, config)
+    })
+}
+
+/// Testa o programa `candidate` contra o programa `reference`, retornando o
+/// primeiro contraexemplo encontrado, já reduzido. Entradas para as quais a
+/// referência não termina dentro do limite de passos são ignoradas.
+pub fn verify_against<P, Q>(
+    reference: P,
+    candidate: Q,
+    config: &Config,
+) -> Result<(), Counterexample>
+where
+    P: Into<Arc<Program>>,
+    Q: Into<Arc<Program>>,
+{
+    let reference = reference.into();
+    let candidate = candidate.into();
+    search(config, |input| {
+        let row = run_one(&reference, input.clone(), &config.batch_config());
+        match row.outcome {
+            Outcome::Halted(expected) => {
+                test_input(&candidate, input, expected, config)
+            }
+            Outcome::OutOfFuel => None,
+        }
+    })
+}
+
+fn compile_or_panic(source: &str) -> Program {
+    compile(source).unwrap_or_else(|diagnostics| {
+        panic!("Código não compila: {:?}", diagnostics)
+    })
+}
+
+/// Roda o programa para a entrada, retornando um contraexemplo se o resultado
+/// não for o esperado.
+fn test_input(
+    program: &Arc<Program>,
+    input: &BigUint,
+    expected: BigUint,
+    config: &Config,
+) -> Option<Counterexample> {
+    let row = run_one(program, input.clone(), &config.batch_config());
+    if row.outcome.output() == Some(&expected) {
+        None
+    } else {
+        Some(Counterexample {
+            input: row.input,
+            original: input.clone(),
+            expected,
+            found: row.outcome,
+            steps: row.steps,
+        })
+    }
+}
+
+/// Procura a primeira entrada que falha e a reduz.
+fn search<T>(config: &Config, test: T) -> Result<(), Counterexample>
+where
+    T: Fn(&BigUint) -> Option<Counterexample>,
+{
+    let failure = config.inputs().iter().find_map(&test);
+    match failure {
+        Some(failure) => Err(shrink(failure, test)),
+        None => Ok(()),
+    }
+}
+
+/// Reduz um contraexemplo: enquanto alguma entrada menor falhar, troca pela
+/// menor delas. As candidatas para `n` são `n - d` para `d` em `n`, `n / 2`,
+/// `n / 4`, ..., `1`, testadas nesta ordem.
+fn shrink<T>(mut failure: Counterexample, test: T) -> Counterexample
+where
+    T: Fn(&BigUint) -> Option<Counterexample>,
+{
+    let original = failure.original.clone();
+    loop {
+        let input = failure.input.clone();
+        let mut distance = input.clone();
+        let mut smaller = None;
+        while distance != BigUint::from(0u8) {
+            if let Some(found) = test(&(&input - &distance)) {
+                smaller = Some(found);
+                break;
+            }
+            distance >>= 1;
+        }
+        match smaller {
+            Some(found) => failure = found,
+            None => break,
+        }
+    }
+    failure.original = original;
+    failure
+}
+
+/// Gerador pseudo-aleatório SplitMix64: pequeno, rápido e determinístico, o
+/// bastante para gerar entradas de teste.
+#[derive(Debug, Clone)]
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut bits = self.0;
+        bits = (bits ^ (bits >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        bits = (bits ^ (bits >> 27)).wrapping_mul(0x94d049bb133111eb);
+        bits ^ (bits >> 31)
+    }
+
+    /// Gera um número em `0 ..= max`.
+    fn up_to(&mut self, max: u64) -> u64 {
+        match max.checked_add(1) {
+            Some(bound) => self.next_u64() % bound,
+            None => self.next_u64(),
+        }
+    }
+}
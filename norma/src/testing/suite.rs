@@ -0,0 +1,366 @@
+//! Execução dos casos de teste escritos no próprio código-fonte, em blocos
+//! `tests` logo após o corpo da `main` ou de uma macro:
+//!
+//! ```text
+//! main {
+//!     ...
+//! }
+//! tests {
+//!     X = 8 => Y = 3;
+//!     X = 0 => Y = 0;
+//! }
+//!
+//! test isZero (A) {
+//!     1: if zero A then goto true else goto false
+//! }
+//! tests {
+//!     A = 0 => true;
+//!     A = 2 => false, A = 2;
+//! }
+//! ```
+//!
+//! Cada caso dá valores iniciais a registradores e, após `=>`, o valor que os
+//! registradores devem ter ao fim da execução. Casos de macros ligam os
+//! parâmetros da macro diretamente, e casos de macros de teste também podem
+//! esperar `true` ou `false`.
+
+#[cfg(test)]
+mod test;
+
+use crate::{
+    batch::run_with_fuel,
+    compiler::{
+        expansor::expand,
+        lexer::generate_tokens,
+        parser::{ast, parse},
+    },
+    interpreter::{program::Program, Interpreter},
+    machine::Machine,
+};
+use indexmap::IndexMap;
+use num_bigint::BigUint;
+use num_traits::Zero;
+use pinguim_language::{error::Diagnostics, position::Span};
+use std::{error::Error, fmt, sync::Arc};
+
+/// Rótulo para o qual a `main` auxiliar de uma macro de teste desvia quando o
+/// teste é verdadeiro.
+const TRUE_TARGET: &str = "then";
+/// Rótulo para o qual a `main` auxiliar de uma macro de teste desvia quando o
+/// teste é falso.
+const FALSE_TARGET: &str = "else";
+
+/// O que um caso testa: a `main` ou uma macro.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    /// A `main` do programa.
+    Main,
+    /// Uma macro, com o dado nome.
+    Macro(String),
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Target::Main => write!(fmtr, "main"),
+            Target::Macro(name) => write!(fmtr, "macro {}", name),
+        }
+    }
+}
+
+/// Motivo da falha de um caso.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CaseFailure {
+    /// Um registrador terminou com um valor diferente do esperado.
+    WrongValue {
+        /// Nome do registrador.
+        register: String,
+        /// Valor esperado.
+        expected: BigUint,
+        /// Valor obtido.
+        found: BigUint,
+    },
+    /// O caso dá ou espera um valor de um registrador que não existe.
+    UndefinedRegister(String),
+    /// A macro de teste resultou no oposto do esperado.
+    WrongBranch {
+        /// Resultado esperado.
+        expected: bool,
+    },
+    /// O caso espera `true` ou `false` de algo que não é macro de teste.
+    NotATest,
+    /// A execução não terminou dentro do limite de passos.
+    OutOfFuel {
+        /// Passos dados.
+        steps: BigUint,
+    },
+}
+
+impl fmt::Display for CaseFailure {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CaseFailure::WrongValue { register, expected, found } => write!(
+                fmtr,
+                "esperado {} = {}, obtido {} = {}",
+                register, expected, register, found
+            ),
+            CaseFailure::UndefinedRegister(reg_name) => {
+                write!(fmtr, "Registrador {} não existe", reg_name)
+            }
+            CaseFailure::WrongBranch { expected } => {
+                write!(fmtr, "esperado {}, obtido {}", expected, !expected)
+            }
+            CaseFailure::NotATest => write!(
+                fmtr,
+                "somente macros de teste resultam em true ou false"
+            ),
+            CaseFailure::OutOfFuel { steps } => {
+                write!(fmtr, "programa não terminou em {} passos", steps)
+            }
+        }
+    }
+}
+
+impl Error for CaseFailure {}
+
+/// Resultado de um caso de teste.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaseReport {
+    /// O que o caso testa.
+    pub target: Target,
+    /// Localização do caso no código-fonte, ou da entrada em um registrador
+    /// que não existe.
+    pub span: Span,
+    /// Motivo da falha, ou `None` se o caso passou.
+    pub failure: Option<CaseFailure>,
+}
+
+impl CaseReport {
+    /// Retorna se o caso passou.
+    pub fn passed(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+impl fmt::Display for CaseReport {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmtr, "{}, caso {}: ", self.target, self.span)?;
+        match &self.failure {
+            Some(failure) => write!(fmtr, "falhou, {}", failure),
+            None => write!(fmtr, "passou"),
+        }
+    }
+}
+
+/// Resultado de todos os casos de teste de um código-fonte, na ordem em que
+/// aparecem.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Report {
+    /// Resultado de cada caso.
+    pub cases: Vec<CaseReport>,
+}
+
+impl Report {
+    /// Retorna se todos os casos passaram.
+    pub fn passed(&self) -> bool {
+        self.cases.iter().all(CaseReport::passed)
+    }
+
+    /// Itera sobre os casos que falharam.
+    pub fn failures(&self) -> impl Iterator<Item = &CaseReport> {
+        self.cases.iter().filter(|case| !case.passed())
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, fmtr: &mut fmt::Formatter) -> fmt::Result {
+        for case in &self.cases {
+            writeln!(fmtr, "{}", case)?;
+        }
+        let passed = self.cases.iter().filter(|case| case.passed()).count();
+        write!(fmtr, "{} de {} casos passaram", passed, self.cases.len())
+    }
+}
+
+/// Compila o código-fonte e roda cada caso de teste dos seus blocos `tests`,
+/// cada execução limitada a `fuel` passos. Retorna os erros de compilação se
+/// o código for inválido.
+pub fn run(source: &str, fuel: u64) -> Result<Report, Diagnostics> {
+    let mut diagnostics = Diagnostics::new();
+    let tokens = generate_tokens(source, &mut diagnostics);
+    let maybe_ast = parse(tokens, &mut diagnostics);
+    let maybe_prog =
+        maybe_ast.as_ref().and_then(|ast| expand(ast, &mut diagnostics));
+
+    let (ast, program) = match (maybe_ast, maybe_prog) {
+        (Some(ast), Some(program)) if diagnostics.is_ok() => (ast, program),
+        _ => return Err(diagnostics),
+    };
+
+    let mut report = Report::default();
+
+    let program = Arc::new(program);
+    for case in &ast.main.tests {
+        report.cases.push(run_case(Target::Main, &program, case, false, fuel));
+    }
+
+    for macro_def in ast.macros.values() {
+        if macro_def.tests.is_empty() {
+            continue;
+        }
+        let harness = match expand(&harness(&ast, macro_def), &mut diagnostics)
+        {
+            Some(harness) if diagnostics.is_ok() => Arc::new(harness),
+            _ => return Err(diagnostics),
+        };
+        let is_test = macro_def.macro_type == ast::MacroType::Test;
+        for case in &macro_def.tests {
+            let target = Target::Macro(macro_def.name.content.clone());
+            report.cases.push(run_case(target, &harness, case, is_test, fuel));
+        }
+    }
+
+    Ok(report)
+}
+
+/// Cria um programa auxiliar cuja `main` chama só a macro dada, passando os
+/// próprios parâmetros da macro como argumentos, de forma que os casos possam
+/// ligar os parâmetros diretamente.
+///
+/// - `ast`: programa original, com todas as macros
+/// - `macro_def`: macro a ser testada
+fn harness(ast: &ast::Program, macro_def: &ast::Macro) -> ast::Program {
+    let span = macro_def.name.span;
+    let symbol =
+        |content: &str| ast::Symbol { content: content.to_owned(), span };
+    let name = macro_def.name.clone();
+    let arguments: Vec<_> = macro_def
+        .parameters
+        .iter()
+        .cloned()
+        .map(ast::MacroArgument::Register)
+        .collect();
+
+    let instruction_type = match macro_def.macro_type {
+        ast::MacroType::Operation => {
+            ast::InstructionType::Operation(ast::Operation {
+                span,
+                oper_type: ast::OperationType::Macro(name, arguments),
+                next_label: symbol("0"),
+            })
+        }
+        ast::MacroType::Test => ast::InstructionType::Test(ast::Test {
+            span,
+            test_type: ast::TestType::Macro(name, arguments),
+            next_true_label: symbol(TRUE_TARGET),
+            next_false_label: symbol(FALSE_TARGET),
+        }),
+    };
+
+    let mut code = IndexMap::new();
+    code.insert(
+        "1".to_owned(),
+        ast::Instruction { label: symbol("1"), instruction_type },
+    );
+
+    ast::Program {
+        main: ast::Main { code, docs: Vec::new(), tests: Vec::new() },
+        macros: ast.macros.clone(),
+    }
+}
+
+/// Roda um caso de teste. Uma entrada em um registrador que o programa não
+/// usa, e.g. um nome digitado errado, falha sem rodar, apontando para a
+/// entrada.
+///
+/// - `target`: o que o caso testa
+/// - `program`: programa a ser executado
+/// - `case`: caso de teste
+/// - `is_test`: se o programa é a `main` auxiliar de uma macro de teste
+/// - `fuel`: máximo de passos da execução
+fn run_case(
+    target: Target,
+    program: &Arc<Program>,
+    case: &ast::TestCase,
+    is_test: bool,
+    fuel: u64,
+) -> CaseReport {
+    let machine = Interpreter::new(program.clone()).machine().clone();
+    let undefined = case
+        .inputs
+        .iter()
+        .find(|input| machine.try_get_value(&input.register.content).is_err());
+
+    match undefined {
+        Some(input) => CaseReport {
+            target,
+            span: input.register.span,
+            failure: Some(CaseFailure::UndefinedRegister(
+                input.register.content.clone(),
+            )),
+        },
+        None => CaseReport {
+            target,
+            span: case.span,
+            failure: case_failure(program, machine, case, is_test, fuel),
+        },
+    }
+}
+
+/// Roda um caso de teste cujas entradas existem na máquina, retornando o
+/// motivo da falha, se houver.
+///
+/// - `program`: programa a ser executado
+/// - `machine`: máquina inicial, com os registradores do programa
+/// - `case`: caso de teste
+/// - `is_test`: se o programa é a `main` auxiliar de uma macro de teste
+/// - `fuel`: máximo de passos da execução
+fn case_failure(
+    program: &Arc<Program>,
+    mut machine: Machine,
+    case: &ast::TestCase,
+    is_test: bool,
+    fuel: u64,
+) -> Option<CaseFailure> {
+    for input in &case.inputs {
+        machine.insert_with_value(&input.register.content, input.value.clone());
+    }
+    let start = program.first_label().to_owned();
+    let mut interpreter = Interpreter::from_state(
+        start,
+        program.clone(),
+        machine,
+        BigUint::zero(),
+    );
+
+    if !run_with_fuel(&mut interpreter, fuel) {
+        return Some(CaseFailure::OutOfFuel { steps: interpreter.steps() });
+    }
+
+    case.expected.iter().find_map(|expectation| match expectation {
+        ast::Expectation::Register(expected) => {
+            let register = &expected.register.content;
+            match interpreter.machine().try_get_value(register) {
+                Ok(found) if found == expected.value => None,
+                Ok(found) => Some(CaseFailure::WrongValue {
+                    register: register.clone(),
+                    expected: expected.value.clone(),
+                    found,
+                }),
+                Err(_) => {
+                    Some(CaseFailure::UndefinedRegister(register.clone()))
+                }
+            }
+        }
+        ast::Expectation::Branch(_) if !is_test => Some(CaseFailure::NotATest),
+        ast::Expectation::Branch(branch) => {
+            let expected = branch.content == "true";
+            let found = interpreter.current_label() == TRUE_TARGET;
+            if expected == found {
+                None
+            } else {
+                Some(CaseFailure::WrongBranch { expected })
+            }
+        }
+    })
+}
@@ -0,0 +1,130 @@
+use super::{run, CaseFailure, Target};
+use num_bigint::BigUint;
+
+const SOURCE: &str = "main {
+    1: do double (X, Y, A) goto 0
+}
+tests {
+    X = 0 => Y = 0;
+    X = 4 => Y = 8, X = 0;
+    X = 5 => Y = 11;
+    X = 1 => Z = 1;
+}
+
+operation double (R, S, T) {
+    1: if zero R then goto 0 else goto 2
+    2: do dec R goto 3
+    3: do inc S goto 4
+    4: do inc S goto 1
+}
+tests {
+    R = 3, S = 1 => R = 0, S = 7;
+    => S = 0;
+}
+
+test isZero (R) {
+    1: if zero R then goto true else goto false
+}
+tests {
+    R = 0 => true;
+    R = 2 => false, R = 2;
+    R = 2 => true;
+}
+
+operation wait (R) {
+    1: if zero R then goto 0 else goto 1
+}
+tests {
+    R = 1 => R = 1;
+    R = 0 => false;
+}";
+
+#[test]
+fn runs_main_and_macro_cases() {
+    let report = run(SOURCE, 1000).unwrap();
+    let summary: Vec<_> = report
+        .cases
+        .iter()
+        .map(|case| (case.target.clone(), case.failure.clone()))
+        .collect();
+
+    let main = || Target::Main;
+    let double = || Target::Macro("double".to_owned());
+    let is_zero = || Target::Macro("isZero".to_owned());
+    let wait = || Target::Macro("wait".to_owned());
+    assert_eq!(
+        summary,
+        vec![
+            (main(), None),
+            (main(), None),
+            (
+                main(),
+                Some(CaseFailure::WrongValue {
+                    register: "Y".to_owned(),
+                    expected: BigUint::from(11u8),
+                    found: BigUint::from(10u8),
+                })
+            ),
+            (main(), Some(CaseFailure::UndefinedRegister("Z".to_owned()))),
+            (double(), None),
+            (double(), None),
+            (is_zero(), None),
+            (is_zero(), None),
+            (is_zero(), Some(CaseFailure::WrongBranch { expected: true })),
+            (
+                wait(),
+                Some(CaseFailure::OutOfFuel { steps: BigUint::from(1000u16) })
+            ),
+            (wait(), Some(CaseFailure::NotATest)),
+        ]
+    );
+    assert!(!report.passed());
+    assert_eq!(report.failures().count(), 5);
+}
+
+#[test]
+fn report_with_spans() {
+    let source = "main {
+    1: do inc Y goto 0
+}
+tests {
+    => Y = 1;
+    => Y = 2;
+}";
+
+    let report = run(source, 10).unwrap();
+    assert_eq!(
+        report.to_string(),
+        "main, caso da linha 5 e coluna 5, até a coluna 13: passou
+main, caso da linha 6 e coluna 5, até a coluna 13: falhou, esperado Y = 2, \
+         obtido Y = 1
+1 de 2 casos passaram"
+    );
+}
+
+#[test]
+fn undefined_input_register() {
+    let source = "main {
+    1: do inc Y goto 0
+}
+tests {
+    Xx = 3 => Y = 1;
+}";
+
+    let report = run(source, 10).unwrap();
+    assert_eq!(
+        report.cases[0].failure,
+        Some(CaseFailure::UndefinedRegister("Xx".to_owned()))
+    );
+    assert_eq!(
+        report.cases[0].to_string(),
+        "main, caso da linha 5 e coluna 5, até a coluna 6: falhou, \
+         Registrador Xx não existe"
+    );
+}
+
+#[test]
+fn compile_errors() {
+    assert!(run("main { 1: do inc X goto 0 } tests { X = 1 }", 10).is_err());
+    assert!(run("main { 1: do foo (X) goto 0 }", 10).is_err());
+}
@@ -0,0 +1,119 @@
+use super::{verify, verify_against, Config, Counterexample};
+use crate::{batch::Outcome, compiler::compile};
+use num_bigint::BigUint;
+
+/// Y := X.
+const IDENTITY: &str = "main {
+    1: if zero X then goto 0 else goto 2
+    2: do dec X goto 3
+    3: do inc Y goto 1
+}";
+
+/// Y := 2 * X.
+const DOUBLE: &str = "main {
+    1: if zero X then goto 0 else goto 2
+    2: do dec X goto 3
+    3: do inc Y goto 4
+    4: do inc Y goto 1
+}";
+
+/// Y := 2 * X, mas não termina quando X é maior que 6.
+const DOUBLE_SMALL: &str = "main {
+    1: do inc A goto 2
+    2: do inc A goto 3
+    3: do inc A goto 4
+    4: do inc A goto 5
+    5: do inc A goto 6
+    6: do inc A goto 7
+    7: if zero X then goto 0 else goto 8
+    8: if zero A then goto 8 else goto 9
+    9: do dec A goto 10
+    10: do dec X goto 11
+    11: do inc Y goto 12
+    12: do inc Y goto 7
+}";
+
+fn config() -> Config {
+    Config { cases: 20, max_input: 300, fuel: 10_000, seed: 7 }
+}
+
+#[test]
+fn boundary_and_random_inputs() {
+    let inputs = config().inputs();
+    let expected_start: Vec<BigUint> = (0..=16u32)
+        .chain(vec![
+            17, 31, 32, 33, 63, 64, 65, 127, 128, 129, 255, 256, 257, 300,
+        ])
+        .map(BigUint::from)
+        .collect();
+
+    assert_eq!(inputs[..expected_start.len()], expected_start[..]);
+    assert_eq!(inputs.len(), expected_start.len() + 20);
+    assert!(inputs.iter().all(|input| *input <= BigUint::from(300u16)));
+    assert_eq!(inputs, config().inputs());
+    assert_ne!(inputs, Config { seed: 8, ..config() }.inputs());
+}
+
+#[test]
+fn passes_when_program_computes_oracle() {
+    let program = compile(DOUBLE).unwrap();
+    assert_eq!(verify(program, |x| x * 2u8, &config()), Ok(()));
+}
+
+#[test]
+fn shrinks_wrong_output() {
+    let program = compile(IDENTITY).unwrap();
+    let oracle = |x: &BigUint| {
+        if *x < BigUint::from(100u8) {
+            x.clone()
+        } else {
+            x + 1u8
+        }
+    };
+
+    let counterexample = verify(program, oracle, &config()).unwrap_err();
+    assert_eq!(
+        counterexample,
+        Counterexample {
+            input: BigUint::from(100u8),
+            original: BigUint::from(127u8),
+            expected: BigUint::from(101u8),
+            found: Outcome::Halted(BigUint::from(100u8)),
+            steps: BigUint::from(301u16),
+        }
+    );
+    assert_eq!(
+        counterexample.to_string(),
+        "Contraexemplo X = 100 (reduzido de X = 127): esperado Y = 101, \
+         obtido Y = 100"
+    );
+}
+
+#[test]
+fn step_budget_catches_non_termination() {
+    let program = compile(DOUBLE_SMALL).unwrap();
+    let config = Config { fuel: 1000, ..config() };
+
+    let counterexample = verify(program, |x| x * 2u8, &config).unwrap_err();
+    assert_eq!(counterexample.input, BigUint::from(7u8));
+    assert_eq!(counterexample.found, Outcome::OutOfFuel);
+    assert_eq!(
+        counterexample.to_string(),
+        "Contraexemplo X = 7: esperado Y = 14, programa não terminou em 1000 \
+         passos"
+    );
+}
+
+#[test]
+fn differential_testing() {
+    let reference = compile(DOUBLE).unwrap();
+
+    let student = compile(DOUBLE).unwrap();
+    assert_eq!(verify_against(reference.clone(), student, &config()), Ok(()));
+
+    let student = compile(DOUBLE_SMALL).unwrap();
+    let counterexample =
+        verify_against(reference, student, &config()).unwrap_err();
+    assert_eq!(counterexample.input, BigUint::from(7u8));
+    assert_eq!(counterexample.expected, BigUint::from(14u8));
+}
@@ -0,0 +1,647 @@
+//! Programa universal da Norma: um programa que recebe em `X` o par de Cantor
+//! `π(p, x)` (ver [`pair`]) de um número de programa `p` (ver
+//! [`godel`](crate::encoding::godel)) e de uma entrada `x`, simula o programa
+//! `p` com a entrada `x` e deixa em `Y` a saída dele. Se o programa simulado
+//! não termina, o programa universal também não termina.
+//!
+//! O programa é gerado a partir das mesmas tabelas da numeração de Gödel: a
+//! decodificação de cada tipo de instrução vem de
+//! [`godel`](crate::encoding::godel), e a simulação de cada tipo é escolhida
+//! por um `match` sobre os tipos de operação e de teste, então um tipo novo
+//! de instrução não compila sem a sua simulação.
+//!
+//! Os registradores do programa simulado ficam todos em um único registrador,
+//! a memória, como potências de primos: o registrador de número `i` (ver
+//! [`register_number`](crate::encoding::godel::register_number)) é o expoente
+//! do `i`-ésimo primo, de modo que incrementar é multiplicar pelo primo,
+//! decrementar é dividir e testar se é zero é testar a divisibilidade.
+//!
+//! O programa é montado como um programa iterativo e usa as instruções
+//! estendidas (como `add` e `less_than`). Multiplicação, divisão e o inverso
+//! do pareamento de Cantor são feitos só com somas, subtrações e comparações,
+//! dobrando valores, o que custa um número de instruções quadrático no número
+//! de dígitos dos operandos.
+
+#[cfg(test)]
+mod test;
+
+use crate::{
+    compiler::emit::{self, emit},
+    encoding::{
+        godel::{self, register_number, ARITIES, OPERATIONS},
+        pair,
+    },
+    interpreter::program::{
+        self as monolithic, InstructionKind, OperationKind, TestKind,
+    },
+    iterative::{self, Statement},
+};
+use num_bigint::BigUint;
+
+/// Uma sequência de instruções.
+type Block = Vec<Statement>;
+
+/// Temporário das instruções estendidas, que sempre fica zerado.
+const TMP: &str = "Lixo";
+/// Número do programa simulado.
+const PROGRAM: &str = "Programa";
+/// Entrada do programa simulado.
+const INPUT: &str = "Entrada";
+/// Número de instruções do programa simulado.
+const SIZE: &str = "Tamanho";
+/// Tupla dos códigos das instruções do programa simulado.
+const CODES: &str = "Codigos";
+/// Rótulo da instrução simulada atual; `0` quando a simulação terminou.
+const LABEL: &str = "Rotulo";
+/// Memória com os registradores do programa simulado.
+const MEMORY: &str = "Memoria";
+/// Parte da tupla de códigos ainda não percorrida.
+const LIST: &str = "Lista";
+/// Quantos códigos ainda devem ser pulados até a instrução atual.
+const INDEX: &str = "Indice";
+/// Quantos pares restam na tupla de códigos.
+const LEFT: &str = "Restantes";
+/// Primeiro elemento de um par.
+const FIRST: &str = "Primeiro";
+/// Segundo elemento de um par.
+const SECOND: &str = "Segundo";
+/// Código da instrução atual.
+const INSTRUCTION: &str = "Instrucao";
+/// Número de tipos de instrução.
+const KINDS: &str = "Tipos";
+/// Tipo da instrução atual.
+const KIND: &str = "Tipo";
+/// Parte do código da instrução ainda não decodificada.
+const REST: &str = "Resto";
+/// Base dos rótulos de destino: o número de instruções mais um.
+const BASE: &str = "Base";
+/// Quociente de uma divisão.
+const QUOTIENT: &str = "Quociente";
+/// Divisor para tirar os fatores dois do número do programa.
+const TWO: &str = "Dois";
+/// Destino de uma operação, ou de um teste verdadeiro.
+const THEN: &str = "Destino1";
+/// Destino de um teste falso.
+const ELSE: &str = "Destino2";
+/// Parâmetros da instrução atual.
+const PARAMS: [&str; 3] = ["Param1", "Param2", "Param3"];
+/// Primos dos registradores que são parâmetros da instrução atual.
+const PRIMES: [&str; 3] = ["Primo1", "Primo2", "Primo3"];
+/// Valores de registradores do programa simulado.
+const VALUES: [&str; 2] = ["Valor1", "Valor2"];
+/// Resultado de um teste simulado: `1` se verdadeiro, `0` se falso.
+const TRUTH: &str = "Verdade";
+/// Contador de repetições.
+const REPEAT: &str = "Repete";
+/// Cópia de um registrador que está sendo dobrado.
+const DOUBLE: &str = "Dobro";
+
+/// Gera o programa universal, como programa monolítico.
+pub fn generate() -> monolithic::Program {
+    structured().to_monolithic()
+}
+
+/// Gera o programa universal como código-fonte da Norma, com as instruções
+/// estendidas reescritas como `inc`, `dec` e `zero`.
+pub fn source() -> String {
+    let config = emit::Config { mapping_comments: false, ..Default::default() };
+    emit(&generate(), &config)
+}
+
+/// Gera o programa universal como programa iterativo, antes de ser convertido
+/// para monolítico.
+pub fn structured() -> iterative::Program {
+    let x_number = register_number("X").unwrap_or_default();
+    let y_number = register_number("Y").unwrap_or_default();
+
+    let body = [
+        // Separa o programa da entrada e coloca a entrada em X.
+        unpair("X", PROGRAM, INPUT),
+        vec![load(PARAMS[0], x_number)],
+        prime(PARAMS[0], PRIMES[0]),
+        vec![load(MEMORY, 1u8)],
+        repeat(INPUT, multiply_memory(PRIMES[0])),
+        // Separa o número de instruções e os códigos.
+        vec![if_then(
+            zero(PROGRAM),
+            vec![clear(LABEL)],
+            [
+                vec![load(SIZE, 1u8), load(TWO, 2u8)],
+                divide(PROGRAM, TWO, QUOTIENT, REST),
+                vec![while_true(
+                    zero(REST),
+                    [
+                        vec![inc(SIZE)],
+                        copy(QUOTIENT, LIST),
+                        divide(LIST, TWO, QUOTIENT, REST),
+                    ]
+                    .concat(),
+                )],
+                copy(QUOTIENT, CODES),
+                vec![load(LABEL, 1u8)],
+            ]
+            .concat(),
+        )],
+        vec![load(KINDS, ARITIES.len()), until(zero(LABEL), step())],
+        // Copia o Y simulado para o Y.
+        vec![load(PARAMS[0], y_number)],
+        prime(PARAMS[0], PRIMES[0]),
+        value(PRIMES[0], "Y"),
+    ]
+    .concat();
+
+    iterative::Program { body }
+}
+
+/// Entrada do programa universal para simular o programa com a dada entrada.
+///
+/// # Panics
+///
+/// Invoca `panic!()` se o programa não tiver nomes canônicos (ver
+/// [`godel::encode`]).
+pub fn input(program: &monolithic::Program, input: &BigUint) -> BigUint {
+    pair(&godel::encode(program), input)
+}
+
+/// Simula uma instrução: busca o código, decodifica e executa.
+fn step() -> Block {
+    let mut dispatch = Vec::new();
+    for tag in (0..ARITIES.len()).rev() {
+        let then = [parameters(ARITIES[tag]), simulate(tag)].concat();
+        dispatch = vec![if_then(equals_const(KIND, tag), then, dispatch)];
+    }
+
+    [
+        // Busca: pula `Rotulo - 1` elementos da tupla.
+        copy(CODES, LIST),
+        copy(LABEL, INDEX),
+        copy(SIZE, LEFT),
+        vec![
+            dec(INDEX),
+            dec(LEFT),
+            until(
+                zero(INDEX),
+                [
+                    unpair(LIST, FIRST, SECOND),
+                    copy(SECOND, LIST),
+                    vec![dec(INDEX), dec(LEFT)],
+                ]
+                .concat(),
+            ),
+            if_then(
+                zero(LEFT),
+                copy(LIST, INSTRUCTION),
+                [unpair(LIST, FIRST, SECOND), copy(FIRST, INSTRUCTION)]
+                    .concat(),
+            ),
+        ],
+        // Decodifica o tipo e os destinos.
+        divide(INSTRUCTION, KINDS, REST, KIND),
+        copy(SIZE, BASE),
+        vec![inc(BASE)],
+        divide(REST, BASE, QUOTIENT, THEN),
+        copy(QUOTIENT, REST),
+        vec![if_then(
+            less_const(KIND, OPERATIONS),
+            Vec::new(),
+            [divide(REST, BASE, QUOTIENT, ELSE), copy(QUOTIENT, REST)].concat(),
+        )],
+        dispatch,
+    ]
+    .concat()
+}
+
+/// Decodifica os `arity` parâmetros da instrução atual, que estão em `Resto`.
+fn parameters(arity: usize) -> Block {
+    let mut block = Vec::new();
+    for param in &PARAMS[..arity - 1] {
+        block.extend(unpair(REST, param, SECOND));
+        block.extend(copy(SECOND, REST));
+    }
+    block.extend(copy(REST, PARAMS[arity - 1]));
+    block
+}
+
+/// Simula uma instrução do tipo dado, cujos parâmetros já foram decodificados,
+/// e atualiza o rótulo atual.
+fn simulate(tag: usize) -> Block {
+    match godel::decode_instruction(&BigUint::from(tag), 1) {
+        InstructionKind::Operation(operation) => {
+            let mut block = simulate_operation(&operation.kind);
+            block.extend(copy(THEN, LABEL));
+            block
+        }
+        InstructionKind::Test(test) => {
+            let mut block = simulate_test(&test.kind);
+            block.push(if_then(
+                zero(TRUTH),
+                copy(ELSE, LABEL),
+                copy(THEN, LABEL),
+            ));
+            block
+        }
+    }
+}
+
+/// Simula uma operação. Só o tipo importa: os parâmetros estão em `Param1`,
+/// `Param2` e `Param3`.
+fn simulate_operation(kind: &OperationKind) -> Block {
+    let dest = PRIMES[0];
+    match kind {
+        OperationKind::Inc(_) => {
+            [prime(PARAMS[0], dest), multiply_memory(dest)].concat()
+        }
+        OperationKind::Dec(_) => {
+            [prime(PARAMS[0], dest), divide_memory(dest)].concat()
+        }
+        OperationKind::Clear(_) => {
+            [prime(PARAMS[0], dest), clear_memory(dest)].concat()
+        }
+        OperationKind::Load(_, _) => [
+            prime(PARAMS[0], dest),
+            clear_memory(dest),
+            repeat(PARAMS[1], multiply_memory(dest)),
+        ]
+        .concat(),
+        OperationKind::AddConst(_, _) => {
+            [prime(PARAMS[0], dest), repeat(PARAMS[1], multiply_memory(dest))]
+                .concat()
+        }
+        OperationKind::Add(_, _, _) => [
+            register_primes(3),
+            value(PRIMES[1], VALUES[0]),
+            repeat(VALUES[0], multiply_memory(dest)),
+            clear_memory(PRIMES[2]),
+        ]
+        .concat(),
+        OperationKind::SubConst(_, _) => {
+            [prime(PARAMS[0], dest), repeat(PARAMS[1], divide_memory(dest))]
+                .concat()
+        }
+        OperationKind::Sub(_, _, _) => [
+            register_primes(3),
+            value(PRIMES[1], VALUES[0]),
+            repeat(VALUES[0], divide_memory(dest)),
+            clear_memory(PRIMES[2]),
+        ]
+        .concat(),
+    }
+}
+
+/// Simula um teste, deixando o resultado em `Verdade`. Só o tipo importa: os
+/// parâmetros estão em `Param1`, `Param2` e `Param3`.
+fn simulate_test(kind: &TestKind) -> Block {
+    match kind {
+        TestKind::Zero(_) => [
+            prime(PARAMS[0], PRIMES[0]),
+            divide(MEMORY, PRIMES[0], QUOTIENT, REST),
+            vec![
+                load(TRUTH, 1u8),
+                if_then(zero(REST), vec![clear(TRUTH)], Vec::new()),
+            ],
+        ]
+        .concat(),
+        TestKind::EqualsConst(_, _) => [
+            prime(PARAMS[0], PRIMES[0]),
+            value(PRIMES[0], VALUES[0]),
+            truth(equals(VALUES[0], PARAMS[1])),
+        ]
+        .concat(),
+        TestKind::Equals(_, _, _) => [
+            register_primes(3),
+            clear_memory(PRIMES[2]),
+            value(PRIMES[0], VALUES[0]),
+            value(PRIMES[1], VALUES[1]),
+            truth(equals(VALUES[0], VALUES[1])),
+        ]
+        .concat(),
+        TestKind::LessThanConst(_, _) => [
+            prime(PARAMS[0], PRIMES[0]),
+            value(PRIMES[0], VALUES[0]),
+            truth(less(VALUES[0], PARAMS[1])),
+        ]
+        .concat(),
+        TestKind::LessThan(_, _, _) => [
+            register_primes(3),
+            clear_memory(PRIMES[2]),
+            value(PRIMES[0], VALUES[0]),
+            value(PRIMES[1], VALUES[1]),
+            truth(less(VALUES[0], VALUES[1])),
+        ]
+        .concat(),
+    }
+}
+
+/// Calcula os primos dos `count` primeiros parâmetros.
+fn register_primes(count: usize) -> Block {
+    (0..count).flat_map(|index| prime(PARAMS[index], PRIMES[index])).collect()
+}
+
+/// `Verdade := 1` se o teste for verdadeiro, senão `Verdade := 0`.
+fn truth(test: TestKind) -> Block {
+    vec![clear(TRUTH), if_then(test, vec![load(TRUTH, 1u8)], Vec::new())]
+}
+
+/// `Memoria := Memoria * primo`, incrementando o registrador simulado.
+fn multiply_memory(prime: &str) -> Block {
+    let product = "Produto";
+    [multiply(MEMORY, prime, product), copy(product, MEMORY)].concat()
+}
+
+/// Divide a memória pelo primo se for divisível, decrementando o registrador
+/// simulado.
+fn divide_memory(prime: &str) -> Block {
+    [
+        divide(MEMORY, prime, QUOTIENT, REST),
+        vec![if_then(zero(REST), copy(QUOTIENT, MEMORY), Vec::new())],
+    ]
+    .concat()
+}
+
+/// Divide a memória pelo primo enquanto for divisível, zerando o registrador
+/// simulado.
+fn clear_memory(prime: &str) -> Block {
+    let divide_block = divide(MEMORY, prime, QUOTIENT, REST);
+    [
+        divide_block.clone(),
+        vec![while_true(
+            zero(REST),
+            [copy(QUOTIENT, MEMORY), divide_block].concat(),
+        )],
+    ]
+    .concat()
+}
+
+/// `result :=` expoente do primo na memória, o valor do registrador simulado.
+fn value(prime: &str, result: &str) -> Block {
+    let (memory, quotient, rest) = ("ValorMem", "ValorQ", "ValorR");
+    let divide_block = divide(memory, prime, quotient, rest);
+    [
+        copy(MEMORY, memory),
+        vec![clear(result)],
+        divide_block.clone(),
+        vec![while_true(
+            zero(rest),
+            [vec![inc(result)], copy(quotient, memory), divide_block].concat(),
+        )],
+    ]
+    .concat()
+}
+
+/// `result :=` o primo de índice `index`, a partir de zero: `2`, `3`, `5`, ...
+fn prime(index: &str, result: &str) -> Block {
+    let (candidate, count, divisor) = ("PrimoCand", "PrimoCont", "PrimoDiv");
+    let (composite, quotient, rest) = ("PrimoComposto", "PrimoQ", "PrimoR");
+    let trial = [
+        vec![inc(candidate), clear(composite), load(divisor, 2u8)],
+        vec![while_true(
+            less(divisor, candidate),
+            [
+                divide(candidate, divisor, quotient, rest),
+                vec![
+                    if_then(zero(rest), vec![load(composite, 1u8)], Vec::new()),
+                    inc(divisor),
+                ],
+            ]
+            .concat(),
+        )],
+        vec![if_then(zero(composite), vec![dec(count)], Vec::new())],
+    ]
+    .concat();
+
+    [
+        vec![load(candidate, 1u8)],
+        copy(index, count),
+        vec![inc(count), until(zero(count), trial)],
+        copy(candidate, result),
+    ]
+    .concat()
+}
+
+/// `quotient := dividend / divisor` e `rest := dividend % divisor`, com o
+/// divisor positivo. Subtrai do resto o maior múltiplo do divisor por uma
+/// potência de dois, obtido dobrando, até o resto ficar menor que o divisor.
+fn divide(dividend: &str, divisor: &str, quotient: &str, rest: &str) -> Block {
+    let (multiple, power) = ("DivMultiplo", "DivPotencia");
+    let doubled = "DivDobro";
+    let grow = [
+        double(multiple),
+        double(power),
+        copy(multiple, doubled),
+        vec![add(doubled, multiple)],
+    ]
+    .concat();
+    let subtract = [
+        copy(divisor, multiple),
+        vec![load(power, 1u8)],
+        copy(multiple, doubled),
+        vec![
+            add(doubled, multiple),
+            until(less(rest, doubled), grow),
+            sub(rest, multiple),
+            add(quotient, power),
+        ],
+    ]
+    .concat();
+
+    [
+        copy(dividend, rest),
+        vec![clear(quotient), until(less(rest, divisor), subtract)],
+    ]
+    .concat()
+}
+
+/// `product := left * right`. Soma ao produto `left` vezes a maior potência
+/// de dois que cabe no que falta de `right`, obtida dobrando.
+fn multiply(left: &str, right: &str, product: &str) -> Block {
+    let (remaining, power) = ("MulFalta", "MulPotencia");
+    let (multiple, doubled) = ("MulMultiplo", "MulDobro");
+    let grow = [
+        double(power),
+        double(multiple),
+        copy(power, doubled),
+        vec![add(doubled, power)],
+    ]
+    .concat();
+    let subtract = [
+        vec![load(power, 1u8)],
+        copy(left, multiple),
+        copy(power, doubled),
+        vec![
+            add(doubled, power),
+            until(less(remaining, doubled), grow),
+            sub(remaining, power),
+            add(product, multiple),
+        ],
+    ]
+    .concat();
+
+    [
+        copy(right, remaining),
+        vec![clear(product), until(zero(remaining), subtract)],
+    ]
+    .concat()
+}
+
+/// `(first, second) := π⁻¹(number)`. Encontra o maior `w` com
+/// `w (w + 1) / 2 <= number` bit a bit, do mais significativo para o menos,
+/// usando `T(w + d) = T(w) + d w + T(d)` e `T(2 d) = 4 T(d) - d`, onde
+/// `T(n) = n (n + 1) / 2`.
+fn unpair(number: &str, first: &str, second: &str) -> Block {
+    let (levels, count) = ("ParNiveis", "ParCont");
+    let (root, triangle) = ("ParRaiz", "ParTriangulo");
+    let (bit, bit_triangle) = ("ParBit", "ParBitTriangulo");
+    let (product, candidate) = ("ParProduto", "ParCandidato");
+    // (bit, bit_triangle) := (2 d, T(2 d)), e dobra o produto `d w`.
+    let double_bit = [
+        double(bit_triangle),
+        double(bit_triangle),
+        vec![sub(bit_triangle, bit)],
+        double(bit),
+    ]
+    .concat();
+
+    let level = [
+        vec![dec(levels), load(bit, 1u8), load(bit_triangle, 1u8)],
+        copy(root, product),
+        copy(levels, count),
+        vec![until(
+            zero(count),
+            [double_bit.clone(), double(product), vec![dec(count)]].concat(),
+        )],
+        copy(triangle, candidate),
+        vec![
+            add(candidate, product),
+            add(candidate, bit_triangle),
+            if_then(
+                less(number, candidate),
+                Vec::new(),
+                [vec![add(root, bit)], copy(candidate, triangle)].concat(),
+            ),
+        ],
+    ]
+    .concat();
+
+    [
+        vec![
+            clear(levels),
+            load(bit, 1u8),
+            load(bit_triangle, 1u8),
+            until(
+                less(number, bit_triangle),
+                [double_bit, vec![inc(levels)]].concat(),
+            ),
+            clear(root),
+            clear(triangle),
+            until(zero(levels), level),
+        ],
+        copy(number, second),
+        vec![sub(second, triangle)],
+        copy(root, first),
+        vec![sub(first, second)],
+    ]
+    .concat()
+}
+
+/// Repete o corpo tantas vezes quanto o valor do registrador.
+fn repeat(times: &str, body: Block) -> Block {
+    [
+        copy(times, REPEAT),
+        vec![until(zero(REPEAT), [body, vec![dec(REPEAT)]].concat())],
+    ]
+    .concat()
+}
+
+/// `dest := src`, preservando `src`.
+fn copy(src: &str, dest: &str) -> Block {
+    vec![clear(dest), add(dest, src)]
+}
+
+/// `register := 2 * register`, somando a ele uma cópia em `Dobro`.
+fn double(register: &str) -> Block {
+    [copy(register, DOUBLE), vec![add(register, DOUBLE)]].concat()
+}
+
+/// `enquanto test faça (body)`.
+fn while_true(test: TestKind, body: Block) -> Statement {
+    Statement::While { test, body }
+}
+
+/// `até test faça (body)`.
+fn until(test: TestKind, body: Block) -> Statement {
+    Statement::Until { test, body }
+}
+
+/// `se test então then senão otherwise`.
+fn if_then(test: TestKind, then: Block, otherwise: Block) -> Statement {
+    Statement::If { test, then, otherwise }
+}
+
+/// `inc register`.
+fn inc(register: &str) -> Statement {
+    Statement::Operation(OperationKind::Inc(register.to_owned()))
+}
+
+/// `dec register`.
+fn dec(register: &str) -> Statement {
+    Statement::Operation(OperationKind::Dec(register.to_owned()))
+}
+
+/// `clear (register)`.
+fn clear(register: &str) -> Statement {
+    Statement::Operation(OperationKind::Clear(register.to_owned()))
+}
+
+/// `load (register, constant)`.
+fn load<N>(register: &str, constant: N) -> Statement
+where
+    N: Into<BigUint>,
+{
+    Statement::Operation(OperationKind::Load(
+        register.to_owned(),
+        constant.into(),
+    ))
+}
+
+/// `add (dest, src, Lixo)`.
+fn add(dest: &str, src: &str) -> Statement {
+    Statement::Operation(OperationKind::Add(
+        dest.to_owned(),
+        src.to_owned(),
+        TMP.to_owned(),
+    ))
+}
+
+/// `sub (dest, src, Lixo)`.
+fn sub(dest: &str, src: &str) -> Statement {
+    Statement::Operation(OperationKind::Sub(
+        dest.to_owned(),
+        src.to_owned(),
+        TMP.to_owned(),
+    ))
+}
+
+/// `zero register`.
+fn zero(register: &str) -> TestKind {
+    TestKind::Zero(register.to_owned())
+}
+
+/// `equals (left, right, Lixo)`.
+fn equals(left: &str, right: &str) -> TestKind {
+    TestKind::Equals(left.to_owned(), right.to_owned(), TMP.to_owned())
+}
+
+/// `equals (register, constant)`.
+fn equals_const(register: &str, constant: usize) -> TestKind {
+    TestKind::EqualsConst(register.to_owned(), BigUint::from(constant))
+}
+
+/// `less_than (left, right, Lixo)`.
+fn less(left: &str, right: &str) -> TestKind {
+    TestKind::LessThan(left.to_owned(), right.to_owned(), TMP.to_owned())
+}
+
+/// `less_than (register, constant)`.
+fn less_const(register: &str, constant: usize) -> TestKind {
+    TestKind::LessThanConst(register.to_owned(), BigUint::from(constant))
+}
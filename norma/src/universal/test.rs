@@ -0,0 +1,171 @@
+use super::{generate, input, source};
+use crate::{
+    compiler::compile,
+    encoding::godel::programs,
+    interpreter::{
+        program::{
+            canonical::canonicalize,
+            fixture::{operation, reg, test},
+            OperationKind, Program, TestKind,
+        },
+        Interpreter,
+    },
+};
+use num_bigint::BigUint;
+
+fn program(source: &str) -> Program {
+    canonicalize(&compile(source).unwrap())
+}
+
+// Programas de duas instruções, cada um usando um tipo de instrução que não é
+// escrito diretamente no código-fonte, seguido de uma que deixa o resultado em
+// Y.
+fn extended() -> Vec<Program> {
+    let tmp = || reg("R1");
+    let copy = || OperationKind::Add(reg("Y"), reg("X"), tmp());
+    let operations = vec![
+        (OperationKind::Clear(reg("X")), copy()),
+        (OperationKind::Load(reg("X"), 3u8.into()), copy()),
+        (OperationKind::AddConst(reg("X"), 2u8.into()), copy()),
+        (OperationKind::Add(reg("X"), reg("X"), tmp()), copy()),
+        (OperationKind::SubConst(reg("X"), 2u8.into()), copy()),
+        (
+            OperationKind::AddConst(reg("Y"), 3u8.into()),
+            OperationKind::Sub(reg("Y"), reg("X"), tmp()),
+        ),
+    ];
+    let tests = vec![
+        TestKind::Zero(reg("X")),
+        TestKind::EqualsConst(reg("X"), 2u8.into()),
+        TestKind::Equals(reg("X"), reg("Y"), tmp()),
+        TestKind::LessThanConst(reg("X"), 2u8.into()),
+        TestKind::LessThan(reg("Y"), reg("X"), tmp()),
+        TestKind::Equals(reg("X"), reg("Y"), reg("X")),
+        TestKind::LessThan(reg("Y"), reg("X"), reg("X")),
+    ];
+
+    let mut programs = Vec::new();
+    for (first, second) in operations {
+        let mut program = Program::empty();
+        program.insert(operation("1", first, "2"));
+        program.insert(operation("2", second, "0"));
+        programs.push(program);
+    }
+    for kind in tests {
+        let mut program = Program::empty();
+        program.insert(test("1", kind, "2", "0"));
+        program.insert(operation("2", OperationKind::Inc(reg("Y")), "0"));
+        programs.push(program);
+    }
+    programs
+}
+
+// Roda o programa universal simulando o programa dado, com um limite de
+// passos; `None` se o limite acabar.
+fn simulate(program: &Program, value: u32) -> Option<BigUint> {
+    simulate_steps(program, value, 200_000)
+}
+
+fn simulate_steps(
+    program: &Program,
+    value: u32,
+    max_steps: u32,
+) -> Option<BigUint> {
+    let mut interpreter = Interpreter::new(generate());
+    interpreter.input(input(program, &BigUint::from(value)));
+    if interpreter.run_steps(max_steps) {
+        None
+    } else {
+        Some(interpreter.output())
+    }
+}
+
+// Roda o programa diretamente, com um limite de passos; `None` se o limite
+// acabar.
+fn run(program: &Program, value: u32, max_steps: u32) -> Option<BigUint> {
+    let mut interpreter = Interpreter::new(program.clone());
+    interpreter.input(BigUint::from(value));
+    if interpreter.run_steps(max_steps) {
+        None
+    } else {
+        Some(interpreter.output())
+    }
+}
+
+#[test]
+fn empty_program() {
+    for value in 0..3 {
+        assert_eq!(
+            simulate(&Program::empty(), value),
+            Some(BigUint::from(0u8))
+        );
+    }
+}
+
+#[test]
+fn copy_program() {
+    let copy = program(
+        "main {
+            1: if zero X then goto 0 else goto 2
+            2: do dec X goto 3
+            3: do inc Y goto 1
+        }",
+    );
+    for value in 0..4 {
+        assert_eq!(simulate(&copy, value), Some(BigUint::from(value)));
+    }
+}
+
+#[test]
+fn extended_instructions() {
+    for program in extended() {
+        for value in 0..4 {
+            let expected = run(&program, value, 10);
+            assert!(expected.is_some());
+            assert_eq!(simulate(&program, value), expected, "{}", program);
+        }
+    }
+}
+
+#[test]
+fn first_programs() {
+    for program in programs().take(60) {
+        for value in 0..2 {
+            if let Some(output) = run(&program, value, 20) {
+                assert_eq!(
+                    simulate(&program, value),
+                    Some(output),
+                    "{}",
+                    program
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn loops_when_simulated_program_loops() {
+    let forever = program("main { 1: do inc Y goto 1 }");
+    assert_eq!(simulate_steps(&forever, 0, 100_000), None);
+}
+
+// O código-fonte reescrito só com `inc`, `dec` e `zero` leva muitos passos,
+// então só programas de código pequeno são simulados por ele.
+#[test]
+fn source_runs_like_generated() {
+    let compiled = compile(&source()).unwrap();
+    let programs = [Program::empty(), program("main { 1: do inc Y goto 0 }")];
+    for program in &programs {
+        for value in 0..2 {
+            let mut interpreter = Interpreter::new(compiled.clone());
+            interpreter.input(input(program, &BigUint::from(value)));
+            assert!(!interpreter.run_steps(1_000_000), "{}", program);
+            assert_eq!(
+                Some(interpreter.output()),
+                simulate(program, value),
+                "{}",
+                program
+            );
+        }
+    }
+}
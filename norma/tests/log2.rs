@@ -1,83 +1,9 @@
-use num_bigint::BigUint;
+use norma::testing::{self, Config};
 
 #[test]
-fn log2_input0() {
-    assert_eq!(
-        norma::run(SOURCE, BigUint::from(0u8)).unwrap(),
-        BigUint::from(0u8)
-    );
-}
-
-#[test]
-fn log2_input1() {
-    assert_eq!(
-        norma::run(SOURCE, BigUint::from(1u8)).unwrap(),
-        BigUint::from(0u8)
-    );
-}
-
-#[test]
-fn log2_input2() {
-    assert_eq!(
-        norma::run(SOURCE, BigUint::from(2u8)).unwrap(),
-        BigUint::from(1u8)
-    );
-}
-
-#[test]
-fn log2_input3() {
-    assert_eq!(
-        norma::run(SOURCE, BigUint::from(3u8)).unwrap(),
-        BigUint::from(1u8)
-    );
-}
-
-#[test]
-fn log2_input4() {
-    assert_eq!(
-        norma::run(SOURCE, BigUint::from(4u8)).unwrap(),
-        BigUint::from(2u8)
-    );
-}
-
-#[test]
-fn log2_input8() {
-    assert_eq!(
-        norma::run(SOURCE, BigUint::from(8u8)).unwrap(),
-        BigUint::from(3u8)
-    );
-}
-
-#[test]
-fn log2_input11() {
-    assert_eq!(
-        norma::run(SOURCE, BigUint::from(11u8)).unwrap(),
-        BigUint::from(3u8)
-    );
-}
-
-#[test]
-fn log2_input15() {
-    assert_eq!(
-        norma::run(SOURCE, BigUint::from(15u8)).unwrap(),
-        BigUint::from(3u8)
-    );
-}
-
-#[test]
-fn log2_input29() {
-    assert_eq!(
-        norma::run(SOURCE, BigUint::from(29u8)).unwrap(),
-        BigUint::from(4u8)
-    );
-}
-
-#[test]
-fn log2_input345() {
-    assert_eq!(
-        norma::run(SOURCE, BigUint::from(345u16)).unwrap(),
-        BigUint::from(8u8)
-    );
+fn log2() {
+    let config = Config { max_input: 400, ..Config::default() };
+    testing::check_with(SOURCE, |x| x.bits().saturating_sub(1), &config);
 }
 
 const SOURCE: &str = "operation clear (R) {
@@ -1,9 +1,15 @@
+use norma::encoding::{self, Data, Encoding};
 use num_bigint::BigUint;
 
+/// Codifica a tupla de entrada como `2^a * 3^b`.
+fn input(tuple: &str) -> BigUint {
+    Encoding::PrimePowers { arity: 2 }.encode_str(tuple).unwrap()
+}
+
 #[test]
 fn modtuple_input_3_2() {
     assert_eq!(
-        norma::run(SOURCE, BigUint::from(2u64.pow(3) * 3u64.pow(2))).unwrap(),
+        norma::run(SOURCE, input("(3, 2)")).unwrap(),
         BigUint::from(1u8)
     );
 }
@@ -11,7 +17,7 @@ fn modtuple_input_3_2() {
 #[test]
 fn modtuple_input_2_3() {
     assert_eq!(
-        norma::run(SOURCE, BigUint::from(2u64.pow(2) * 3u64.pow(3))).unwrap(),
+        norma::run(SOURCE, input("(2, 3)")).unwrap(),
         BigUint::from(2u8)
     );
 }
@@ -19,7 +25,7 @@ fn modtuple_input_2_3() {
 #[test]
 fn modtuple_input_4_2() {
     assert_eq!(
-        norma::run(SOURCE, BigUint::from(2u64.pow(4) * 3u64.pow(2))).unwrap(),
+        norma::run(SOURCE, input("(4, 2)")).unwrap(),
         BigUint::from(0u8)
     );
 }
@@ -27,7 +33,7 @@ fn modtuple_input_4_2() {
 #[test]
 fn modtuple_input_5_2() {
     assert_eq!(
-        norma::run(SOURCE, BigUint::from(2u64.pow(5) * 3u64.pow(2))).unwrap(),
+        norma::run(SOURCE, input("(5, 2)")).unwrap(),
         BigUint::from(1u8)
     );
 }
@@ -35,7 +41,7 @@ fn modtuple_input_5_2() {
 #[test]
 fn modtuple_input_5_3() {
     assert_eq!(
-        norma::run(SOURCE, BigUint::from(2u64.pow(5) * 3u64.pow(3))).unwrap(),
+        norma::run(SOURCE, input("(5, 3)")).unwrap(),
         BigUint::from(2u8)
     );
 }
@@ -43,11 +49,24 @@ fn modtuple_input_5_3() {
 #[test]
 fn modtuple_input_7_3() {
     assert_eq!(
-        norma::run(SOURCE, BigUint::from(2u64.pow(7) * 3u64.pow(3))).unwrap(),
+        norma::run(SOURCE, input("(7, 3)")).unwrap(),
         BigUint::from(1u8)
     );
 }
 
+#[test]
+fn modtuple_encoded_run() {
+    let program = norma::compiler::compile(SOURCE).unwrap();
+    let output = encoding::run_once(
+        program,
+        &"(7, 3)".parse().unwrap(),
+        &Encoding::PrimePowers { arity: 2 },
+        &Encoding::Natural,
+    )
+    .unwrap();
+    assert_eq!(output, Data::Number(BigUint::from(1u8)));
+}
+
 const SOURCE: &str = "// input A
 // output A := 0
 operation clear (A) {
@@ -87,6 +87,7 @@ if (currentTheme) {
 const storagePrefix = "pinguim.norma";
 const storageCodeKey = storagePrefix + '.userCode';
 const storageCodeHistKey = storagePrefix + '.userCodeHistory';
+const storageSnapshotKey = storagePrefix + '.snapshot';
 
 // Local Storage
 export const saveCode = baseText => {
@@ -104,3 +105,15 @@ export const saveCodeHist = array => {
 export const loadCodeHist = () => {
     return JSON.parse(localStorage.getItem(storageCodeHistKey));
 };
+
+export const saveSnapshot = snapshotJson => {
+    localStorage.setItem(storageSnapshotKey, snapshotJson);
+};
+
+export const loadSnapshot = () => {
+    return localStorage.getItem(storageSnapshotKey);
+};
+
+export const clearSnapshot = () => {
+    localStorage.removeItem(storageSnapshotKey);
+};
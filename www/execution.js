@@ -1,4 +1,10 @@
-import { init, loadCode } from './common.js';
+import {
+    init,
+    loadCode,
+    saveSnapshot,
+    loadSnapshot,
+    clearSnapshot
+} from './common.js';
 import * as wasm from "norma-wasm";
 
 init(() => {
@@ -9,21 +15,47 @@ init(() => {
 
     const source = () => loadCode();
     const userInput = document.getElementById('input');
-    const registerX = () => document.getElementById('input').value;
+
+    //---------- CODIFICAÇÕES DE ENTRADA E SAÍDA ==========
+    // Monta a codificação no formato do módulo `encoding`, e.g. `cantor:2`.
+    const encoding = (prefix) => {
+        const kind = document.getElementById(prefix + '-encoding').value;
+        const parameter = document.getElementById(prefix + '-encoding-parameter');
+        const hasParameter = kind == 'cantor' || kind == 'primes' || kind == 'list';
+        parameter.hidden = !hasParameter;
+        return hasParameter ? kind + ':' + parameter.value : kind;
+    }
+
+    // Valor de X codificado a partir do texto da entrada, ou `null` caso o
+    // texto não sirva para a codificação escolhida.
+    const registerX = () => {
+        try {
+            return wasm.encode(userInput.value, encoding('input'));
+        } catch (error) {
+            return null;
+        }
+    }
 
     const reset = () => {
         interpreter.reset();
         setInput();
         cleanHTML();
         running = false;
+        clearSnapshot();
     };
 
     //---------- ATUALIZA X ON CHANGE ==========
-    userInput.onchange = () => {
+    const inputChanged = () => {
         reset();
-        document.getElementById('reg-value-X').innerHTML = userInput.value
+        document.getElementById('reg-value-X').innerHTML = registerX() || '0';
     }
 
+    userInput.onchange = inputChanged;
+    document.getElementById('input-encoding').onchange = inputChanged;
+    document.getElementById('input-encoding-parameter').onchange = inputChanged;
+    document.getElementById('output-encoding').onchange = () => updateSaida();
+    document.getElementById('output-encoding-parameter').onchange = () => updateSaida();
+
     //---------- COMPILAR CÓDIGO  ==========
     const compile = () => {
         interpreter = null;
@@ -43,24 +75,31 @@ init(() => {
             setInput();
             compiled = true;
             makeTable();
+            makeFlowchart();
             makeRegisters();
         }
     }
 
     //---------- INPUT REGISTRADOR X ==========
     const setInput = () => {
-        interpreter.input(registerX());
+        try {
+            interpreter.input(wasm.encode(userInput.value, encoding('input')));
+        } catch (error) {
+            alert(error);
+            interpreter.input('0');
+        }
     }
 
     //---------- RODAR PASSO ==========
     document.getElementById('step').onclick = () => {
         compileTest();
-        const status = interpreter.runSteps(1);
-        running = status.running;
+        const status = tryRunSteps(1);
+        running = status.running && !status.error;
         updateRegisters();
 
         let line = data();
         lineHighlight(line['status']['currentLabel']);
+        saveSnapshot(interpreter.snapshot());
     }
 
     //---------- UPDATE REGISTERS ==========
@@ -70,10 +109,27 @@ init(() => {
         registers = registers['status']['registers'];
 
         for(let i in registers) {
-            document.getElementById('reg-value-' + registers[i]['name']).innerHTML = registers[i]['value'];
+            let regValue = document.getElementById('reg-value-' + registers[i]['name']);
+            if (!regValue) {
+                regValue = makeRegister(registers[i]['name'], registers[i]['value']);
+            }
+            regValue.innerHTML = registers[i]['value'];
             updatePassos(numPassos);
         }
         updateSaida();
+        updateEdits();
+    }
+
+    //---------- RODAR PASSOS COM ERRO ==========
+    // Roda passos, interrompendo a execução se o programa usar um
+    // registrador inexistente. O estado retornado é o real, com os passos
+    // dados antes do erro.
+    const tryRunSteps = (steps) => {
+        const outcome = interpreter.runSteps(steps);
+        if (outcome.error) {
+            alert(outcome.error);
+        }
+        return outcome;
     }
 
     //---------- RODAR N-PASSOS ==========
@@ -88,12 +144,14 @@ init(() => {
 
         const tick = () => {
             if (running) {
-                const status = interpreter.runSteps(stepSpeed ? 1 : 1347);
-                running = status.running;
+                const status = tryRunSteps(stepSpeed ? 1 : 1347);
+                running = status.running && !status.error;
                 updateRegisters();
 
-                if (running) {
+                if (running || status.error) {
                     lineHighlight(status.currentLabel);
+                }
+                if (running) {
                     setTimeout(tick, stepSpeed || 1);
                 } else {
                     const end = performance.now();
@@ -114,6 +172,7 @@ init(() => {
     //---------- ABORTAR PROGRAMA ==========
     document.getElementById('abort').onclick = () => {
         running = false;
+        saveSnapshot(interpreter.snapshot());
     }
 
     //---------- DADOS DO CÓDIGO ==========
@@ -157,26 +216,77 @@ init(() => {
     //---------- REGISTRADORES NO HTML ==========
     const regSection = document.getElementById('registers-section');
 
+    const makeRegister = (name, value) => {
+        const outerDiv = document.createElement('div');
+        outerDiv.id = 'reg-' + name;
+        outerDiv.className = 'register';
+        regSection.appendChild(outerDiv);
+
+        const innerH3 = document.createElement('h3');
+        const innerDiv = document.createElement('div');
+        innerH3.id = 'reg-name-' + name;
+        innerDiv.id = 'reg-value-' + name;
+        innerH3.className = 'register_name';
+        innerDiv.className = 'register_value';
+        innerDiv.title = 'Clique para alterar o valor';
+        innerH3.innerText = name;
+        innerDiv.innerText = value;
+        innerDiv.onclick = () => editRegister(name);
+        outerDiv.appendChild(innerH3);
+        outerDiv.appendChild(innerDiv);
+        return innerDiv;
+    }
+
     const makeRegisters = () => {
         let registers = data();
         registers = registers['status']['registers'];
 
         for(let i in registers) {
-            const outerDiv = document.createElement('div');
-            outerDiv.id = 'reg-' + registers[i]['name'];
-            outerDiv.className = 'register';
-            regSection.appendChild(outerDiv);
-
-            const innerH3 = document.createElement('h3');
-            const innerDiv = document.createElement('div');
-            innerH3.id = 'reg-name-' + registers[i]['name'];
-            innerDiv.id = 'reg-value-' + registers[i]['name'];
-            innerH3.className = 'register_name';
-            innerDiv.className = 'register_value';
-            innerH3.innerText = registers[i]['name'];
-            innerDiv.innerText = registers[i]['value'];
-            outerDiv.appendChild(innerH3);
-            outerDiv.appendChild(innerDiv);
+            makeRegister(registers[i]['name'], registers[i]['value']);
+        }
+    }
+
+    //---------- EDIÇÕES COM A EXECUÇÃO PAUSADA ==========
+    const editsList = document.getElementById('edits');
+
+    const updateEdits = () => {
+        editsList.innerHTML = '';
+        for (const edit of interpreter.edits()) {
+            const item = document.createElement('li');
+            item.innerText = edit;
+            editsList.appendChild(item);
+        }
+    }
+
+    // Aplica uma edição somente com a execução pausada, mostrando o erro caso
+    // ela seja rejeitada.
+    const applyEdit = (edit) => {
+        if (running) {
+            return;
+        }
+        try {
+            edit();
+        } catch (error) {
+            alert(error);
+            return;
+        }
+        updateRegisters();
+        lineHighlight(interpreter.status().currentLabel);
+        saveSnapshot(interpreter.snapshot());
+    }
+
+    const editRegister = (name) => {
+        const current = document.getElementById('reg-value-' + name).innerText;
+        const value = prompt('Novo valor de ' + name, current);
+        if (value !== null) {
+            applyEdit(() => interpreter.setRegister(name, value));
+        }
+    }
+
+    document.getElementById('new-register').onclick = () => {
+        const name = prompt('Nome do novo registrador');
+        if (name !== null) {
+            applyEdit(() => interpreter.createRegister(name.trim(), '0'));
         }
     }
 
@@ -197,6 +307,35 @@ init(() => {
             stepColumn.innerHTML = instList[i]['label'];
             programColumn.innerHTML = instList[i]['kind'];
             newRow.id = instList[i]['label'];
+
+            const label = instList[i]['label'];
+            stepColumn.title = 'Clique para ir a este rótulo';
+            stepColumn.onclick = () => applyEdit(() => interpreter.jumpTo(label));
+        }
+    }
+
+    //---------- FLUXOGRAMA ==========
+    // O SVG é gerado uma vez só; o destaque troca a classe `current` dos nós,
+    // identificados pelo atributo `data-label`.
+    const flowchart = document.getElementById('flowchart');
+
+    // O SVG vem sem destaque embutido: o nó atual é marcado só pela classe
+    // `current`, com a cor do tema.
+    const makeFlowchart = () => {
+        flowchart.innerHTML = interpreter ? interpreter.flowchart() : '';
+        if (interpreter) {
+            flowchartHighlight(interpreter.status().currentLabel);
+        }
+    }
+
+    const flowchartHighlight = (label) => {
+        for (const node of flowchart.querySelectorAll('.node.current')) {
+            node.classList.remove('current');
+        }
+        for (const node of flowchart.querySelectorAll('.node')) {
+            if (node.dataset.label == label) {
+                node.classList.add('current');
+            }
         }
     }
 
@@ -213,6 +352,7 @@ init(() => {
     }
     let firstLine = lastLine;
     const lineHighlight = (lineId) => {
+        flowchartHighlight(lineId);
         try {
             if (lastLine) {
                 lastLine.classList.remove('line_selected')
@@ -237,7 +377,11 @@ init(() => {
         } else {
             let values = data();
             values = values.status.registers.find(values => values.name == "Y");
-            outputSpan.innerHTML = values.value;
+            try {
+                outputSpan.innerText = wasm.decode(values.value, encoding('output'));
+            } catch (error) {
+                outputSpan.innerText = values.value + ' (' + error + ')';
+            }
         }
     }
 
@@ -250,12 +394,34 @@ init(() => {
             firstLine.classList.add('line_selected');
             lastLine = firstLine;
         }
+        if (interpreter) {
+            flowchartHighlight(interpreter.status().currentLabel);
+        }
 
         for (let i in regSection.children) {
             try {
                 regSection.children[i].lastChild.innerHTML = 0
             } catch(e) {}
         }
-        document.getElementById('reg-value-X').innerHTML = userInput.value
+        document.getElementById('reg-value-X').innerHTML = registerX() || '0';
+        editsList.innerHTML = '';
+    }
+
+    //---------- SALVAR E RESTAURAR EXECUÇÃO ==========
+    window.addEventListener('beforeunload', () => {
+        if (interpreter) {
+            saveSnapshot(interpreter.snapshot());
+        }
+    });
+
+    const savedSnapshot = loadSnapshot();
+    if (interpreter && savedSnapshot) {
+        try {
+            interpreter.restore(savedSnapshot);
+            updateRegisters();
+            lineHighlight(interpreter.status().currentLabel);
+        } catch (error) {
+            clearSnapshot();
+        }
     }
 })
@@ -30,6 +30,10 @@ const editor = new Editor({
         }
     },
     highlighter: new Highlighter(
+        {
+            className: 'comment',
+            regex: /\/\*[\s\S]*?(\*\/|$)/
+        },
         {
             className: 'comment',
             regex: /\/\/.*\n/
@@ -125,6 +129,21 @@ init(() => {
     let interpreter = null;
 
     //---------- VERIFICAR CÓDIGO  ==========
+    const showErrors = errors => {
+        logAreaText.textContent = '';
+        let first = true;
+        for (const error of errors) {
+            if (first) {
+                first = false;
+            } else {
+                logAreaText.textContent += '\n\n\n';
+            }
+            logAreaText.textContent += 'ERRO: ' + error.span.rendered;
+            logAreaText.textContent += '\n\n' + error.message;
+        }
+        toggleLogColor(false);
+    };
+
     document.getElementById('verify').onclick = () => {
         interpreter = null;
 
@@ -137,18 +156,23 @@ init(() => {
                 logAreaText.textContent = 'Código OK!';
                 toggleLogColor(true);
             } catch (errors) {
-                logAreaText.textContent = '';
-                let first = true;
-                for (const error of errors) {
-                    if (first) {
-                        first = false;
-                    } else {
-                        logAreaText.textContent += '\n\n\n';
-                    }
-                    logAreaText.textContent += 'ERRO: ' + error.span.rendered;
-                    logAreaText.textContent += '\n\n' + error.message;
-                }
-                toggleLogColor(false);
+                showErrors(errors);
+            }
+        }
+    };
+
+    //---------- FORMATAR CÓDIGO  ==========
+    document.getElementById('format').onclick = () => {
+        if (editor.content == '') {
+            logAreaText.textContent = 'Entrada vazia!';
+            toggleLogColor(false);
+        } else {
+            try {
+                editor.content = wasm.format(editor.content);
+                logAreaText.textContent = 'Código formatado!';
+                toggleLogColor(true);
+            } catch (errors) {
+                showErrors(errors);
             }
         }
     };